@@ -1,19 +1,151 @@
 use crate::{
-    cli::input_path_from_args, clipboard::copy_file_to_clipboard, errors::AppResult,
-    output::write_output_file, paths::validate_root_path,
+    cli::{
+        CliOptions, Command, console_locale, document_locale, parse_args, parse_args_with_config,
+        resolved_options,
+    },
+    clipboard::copy_file_to_clipboard,
+    config::{load_config_arguments, resolve_config_path},
+    dry_run::dry_run_lines,
+    errors::{AppResult, ErrorFormat, FailureKind, set_error_format},
+    explain::{explain_path, explanation_lines},
+    i18n::{self, Message, set_console_locale, tr},
+    inventory::{InventoryOptions, collect_project_inventory},
+    languages::LanguageRegistry,
+    logging::{self, LogFormat, LogLevel},
+    output::{output_path, write_output_file},
+    paths::validate_root_path,
+    render::RenderOptions,
+    template::Template,
 };
-use std::ffi::OsString;
+use std::{ffi::OsString, io, path::Path};
 pub(crate) fn run<I>(args: I) -> AppResult<()>
 where
     I: IntoIterator<Item = OsString>,
 {
-    let root_path = input_path_from_args(args)?;
-    validate_root_path(&root_path)?;
-    println!("正在生成文档...");
-    let output_path = write_output_file(&root_path)?;
+    let raw_args: Vec<OsString> = args.into_iter().collect();
+    set_error_format(requested_error_format(&raw_args));
+    let cli_options = parse_args(raw_args.clone()).map_err(|err| FailureKind::Cli.wrap(err))?;
+    apply_console_options(&cli_options);
+    validate_root_path(&cli_options.root_path).map_err(|err| FailureKind::Path.wrap(err))?;
+    let options =
+        apply_config_file(cli_options, raw_args).map_err(|err| FailureKind::Cli.wrap(err))?;
+    apply_console_options(&options);
+    let inventory_options =
+        build_inventory_options(&options).map_err(|err| FailureKind::Cli.wrap(err))?;
+    let render_options =
+        build_render_options(&options).map_err(|err| FailureKind::Cli.wrap(err))?;
+    match options.command.clone() {
+        Command::Generate => generate(&options.root_path, &inventory_options, &render_options),
+        Command::DryRun => dry_run(&options.root_path, &inventory_options),
+        Command::Explain(target) => explain(&options.root_path, &target, &inventory_options),
+    }
+}
+fn generate(
+    root_path: &Path,
+    inventory_options: &InventoryOptions,
+    render_options: &RenderOptions,
+) -> AppResult<()> {
+    if logging::enabled(LogLevel::Normal) {
+        println!("{}", tr(Message::GeneratingDocument, &[]));
+    }
+    let inventory = collect_project_inventory(root_path, inventory_options)?;
+    let output_path = write_output_file(&inventory, render_options)?;
     copy_file_to_clipboard(&output_path)?;
-    println!("文档文件已复制到剪贴板: {}", output_path.display());
+    if logging::enabled(LogLevel::Normal) {
+        println!(
+            "{}",
+            tr(Message::CopiedToClipboard, &[&output_path.display()])
+        );
+    }
+    Ok(())
+}
+fn dry_run(root_path: &Path, inventory_options: &InventoryOptions) -> AppResult<()> {
+    let inventory = collect_project_inventory(root_path, inventory_options)?;
+    for line in dry_run_lines(&inventory, i18n::console_locale()) {
+        println!("{line}");
+    }
+    Ok(())
+}
+fn explain(root_path: &Path, target: &Path, inventory_options: &InventoryOptions) -> AppResult<()> {
+    let explanation = explain_path(root_path, target, inventory_options)?;
+    for line in explanation_lines(&explanation, i18n::console_locale()) {
+        println!("{line}");
+    }
     Ok(())
 }
+fn requested_error_format(raw_args: &[OsString]) -> ErrorFormat {
+    raw_args
+        .windows(2)
+        .rev()
+        .filter(|pair| {
+            pair.first()
+                .is_some_and(|option| option == "--error-format")
+        })
+        .find_map(|pair| pair.get(1)?.to_str().and_then(ErrorFormat::parse))
+        .unwrap_or_default()
+}
+fn apply_console_options(options: &CliOptions) {
+    set_console_locale(console_locale(options));
+    set_error_format(options.error_format);
+    let level = if options.log_format == LogFormat::Jsonl && options.log_level == LogLevel::Normal {
+        LogLevel::Verbose
+    } else {
+        options.log_level
+    };
+    logging::configure(level, options.log_format);
+}
+fn apply_config_file(cli_options: CliOptions, raw_args: Vec<OsString>) -> io::Result<CliOptions> {
+    let Some((config_path, source)) =
+        resolve_config_path(&cli_options.root_path, cli_options.config_path.as_deref())
+    else {
+        return Ok(cli_options);
+    };
+    let config_args = load_config_arguments(&config_path, source)?;
+    parse_args_with_config(raw_args, config_args)
+}
+fn build_inventory_options(options: &CliOptions) -> io::Result<InventoryOptions> {
+    let mut languages = LanguageRegistry::default();
+    for spec in &options.language_overrides {
+        languages.add_override(spec)?;
+    }
+    Ok(InventoryOptions {
+        languages,
+        max_file_size: options.max_file_size,
+        show_ignored_directories: options.show_ignored_directories,
+        content_selection: options.content_selection.clone(),
+        exclusions: options.exclusions.clone(),
+        output_path: Some(output_path(options.format)),
+        changes: options.changes.clone(),
+        revision: options.revision.clone(),
+        tracking: options.tracking,
+        generated_files: options.generated_files,
+        submodules: options.submodules,
+        order: options.order,
+        touched: options.touched,
+        modified: options.modified,
+        files_from: options.files_from.clone(),
+    })
+}
+fn build_render_options(options: &CliOptions) -> io::Result<RenderOptions> {
+    let template = options
+        .template_path
+        .as_deref()
+        .map(Template::load)
+        .transpose()?;
+    Ok(RenderOptions {
+        format: options.format,
+        mode: options.mode,
+        summary: options.summary,
+        front_matter: options.front_matter,
+        resolved_options: resolved_options(options),
+        template,
+        diff_reference: options.diff_reference.clone(),
+        revision: options.revision.clone(),
+        permalink: options.permalink.clone(),
+        line_anchors: options.line_anchors,
+        tree: options.tree,
+        locale: document_locale(options),
+    })
+}
 #[cfg(test)]
 mod tests;
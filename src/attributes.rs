@@ -0,0 +1,157 @@
+use crate::i18n::{Message, tr};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+const ATTRIBUTES_FILENAME: &str = ".gitattributes";
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub(crate) enum GeneratedFiles {
+    #[default]
+    Omit,
+    Include,
+}
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub(crate) struct FileAttributes {
+    pub(crate) generated: bool,
+    pub(crate) vendored: bool,
+    pub(crate) binary: bool,
+    pub(crate) encoding: Option<String>,
+}
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum AttributeState {
+    Set,
+    Unset,
+    Unspecified,
+    Value(String),
+}
+#[derive(Clone, Debug)]
+struct AttributeRule {
+    directory: PathBuf,
+    depth: usize,
+    matcher: Gitignore,
+    assignments: Vec<(String, AttributeState)>,
+}
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Attributes {
+    rules: Vec<AttributeRule>,
+}
+impl Attributes {
+    pub(crate) fn load_directory(&mut self, root_path: &Path, directory: &Path) -> io::Result<()> {
+        let file = root_path.join(directory).join(ATTRIBUTES_FILENAME);
+        if !file.is_file() {
+            return Ok(());
+        }
+        let text = fs::read_to_string(&file).map_err(|err| {
+            io::Error::new(err.kind(), tr(Message::ReadFileFailed, &[&file.display()]))
+        })?;
+        self.add_source(root_path, directory, &text)
+    }
+    pub(crate) fn load_ancestors(
+        &mut self,
+        root_path: &Path,
+        relative_path: &Path,
+    ) -> io::Result<()> {
+        let mut directories: Vec<&Path> = relative_path.ancestors().skip(1).collect();
+        directories.reverse();
+        for directory in directories {
+            self.load_directory(root_path, directory)?;
+        }
+        Ok(())
+    }
+    pub(crate) fn add_source(
+        &mut self,
+        root_path: &Path,
+        directory: &Path,
+        text: &str,
+    ) -> io::Result<()> {
+        let base: PathBuf = root_path.join(directory);
+        let depth = directory.components().count();
+        for line in text.lines() {
+            let mut fields = line.split_whitespace();
+            let Some(pattern) = fields.next().filter(|pattern| !pattern.starts_with('#')) else {
+                continue;
+            };
+            let mut builder = GitignoreBuilder::new(&base);
+            builder.add_line(None, pattern).map_err(|err| {
+                io::Error::other(tr(Message::InvalidAttributesPattern, &[&pattern, &err]))
+            })?;
+            let matcher = builder.build().map_err(|err| {
+                io::Error::other(tr(Message::InvalidAttributesPattern, &[&pattern, &err]))
+            })?;
+            self.rules.push(AttributeRule {
+                directory: base.clone(),
+                depth,
+                matcher,
+                assignments: fields.map(parse_assignment).collect(),
+            });
+        }
+        Ok(())
+    }
+    pub(crate) fn lookup(&self, root_path: &Path, relative_path: &Path) -> FileAttributes {
+        let path = root_path.join(relative_path);
+        let mut rules: Vec<&AttributeRule> = self
+            .rules
+            .iter()
+            .filter(|rule| {
+                path.starts_with(&rule.directory) && rule.matcher.matched(&path, false).is_ignore()
+            })
+            .collect();
+        rules.sort_by_key(|rule| rule.depth);
+        let mut attributes = FileAttributes::default();
+        for (name, state) in rules
+            .into_iter()
+            .flat_map(|rule| rule.assignments.iter().cloned())
+        {
+            let enabled = match state.clone() {
+                AttributeState::Set => true,
+                AttributeState::Value(value) => value != "false",
+                AttributeState::Unset | AttributeState::Unspecified => false,
+            };
+            match name.as_str() {
+                "linguist-generated" => attributes.generated = enabled,
+                "linguist-vendored" => attributes.vendored = enabled,
+                "binary" => attributes.binary = enabled,
+                "diff" => attributes.binary = state == AttributeState::Unset,
+                "working-tree-encoding" => {
+                    attributes.encoding = match state {
+                        AttributeState::Value(label) => Some(label),
+                        AttributeState::Set
+                        | AttributeState::Unset
+                        | AttributeState::Unspecified => None,
+                    };
+                }
+                _ => {}
+            }
+        }
+        attributes
+    }
+}
+impl FileAttributes {
+    pub(crate) const fn linguist_label(&self) -> Option<&'static str> {
+        if self.generated {
+            Some("linguist-generated")
+        } else if self.vendored {
+            Some("linguist-vendored")
+        } else {
+            None
+        }
+    }
+}
+pub(crate) fn is_attributes_file(relative_path: &Path) -> bool {
+    relative_path.file_name() == Some(ATTRIBUTES_FILENAME.as_ref())
+}
+fn parse_assignment(field: &str) -> (String, AttributeState) {
+    if let Some(name) = field.strip_prefix('-') {
+        return (name.to_owned(), AttributeState::Unset);
+    }
+    if let Some(name) = field.strip_prefix('!') {
+        return (name.to_owned(), AttributeState::Unspecified);
+    }
+    match field.split_once('=') {
+        Some((name, value)) => (name.to_owned(), AttributeState::Value(value.to_owned())),
+        None => (field.to_owned(), AttributeState::Set),
+    }
+}
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,76 @@
+use super::{Attributes, FileAttributes};
+use crate::test_support::{TestDir, must};
+use std::path::Path;
+#[test]
+fn nested_files_and_later_lines_take_precedence() {
+    let dir = must(TestDir::new("attributes-precedence"), "创建测试目录失败");
+    must(
+        dir.write_str(
+            ".gitattributes",
+            "# generated code\n*.pb.go linguist-generated\nvendor/** linguist-vendored\n*.dat binary\n",
+        ),
+        "写入 .gitattributes 失败",
+    );
+    must(
+        dir.write_str(
+            "api/.gitattributes",
+            "*.pb.go -linguist-generated\n*.txt working-tree-encoding=UTF-16LE\n*.txt -diff\n*.txt diff\n",
+        ),
+        "写入 api/.gitattributes 失败",
+    );
+    let mut attributes = Attributes::default();
+    must(
+        attributes.load_directory(dir.path(), Path::new("")),
+        "读取根目录属性失败",
+    );
+    must(
+        attributes.load_directory(dir.path(), Path::new("api")),
+        "读取 api 属性失败",
+    );
+    assert!(
+        attributes
+            .lookup(dir.path(), Path::new("gen/a.pb.go"))
+            .generated
+    );
+    assert!(
+        !attributes
+            .lookup(dir.path(), Path::new("api/a.pb.go"))
+            .generated
+    );
+    assert!(
+        attributes
+            .lookup(dir.path(), Path::new("vendor/lib/x.rs"))
+            .vendored
+    );
+    assert!(
+        attributes
+            .lookup(dir.path(), Path::new("data/a.dat"))
+            .binary
+    );
+    assert_eq!(
+        attributes.lookup(dir.path(), Path::new("api/notes.txt")),
+        FileAttributes {
+            encoding: Some("UTF-16LE".to_owned()),
+            ..FileAttributes::default()
+        }
+    );
+}
+#[test]
+fn negative_diff_marks_files_binary() {
+    let dir = must(TestDir::new("attributes-diff"), "创建测试目录失败");
+    let mut attributes = Attributes::default();
+    must(
+        attributes.add_source(dir.path(), Path::new(""), "*.lock -diff\n"),
+        "解析属性失败",
+    );
+    assert!(
+        attributes
+            .lookup(dir.path(), Path::new("Cargo.lock"))
+            .binary
+    );
+    assert!(
+        !attributes
+            .lookup(dir.path(), Path::new("Cargo.toml"))
+            .binary
+    );
+}
@@ -0,0 +1,131 @@
+use crate::{
+    git::{git_stdout, resolve_commit},
+    paths,
+};
+use alloc::collections::BTreeSet;
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) enum ChangeScope {
+    Since(String),
+    Staged,
+    Uncommitted,
+}
+impl ChangeScope {
+    pub(crate) fn describe(&self) -> String {
+        match self {
+            Self::Since(reference) => format!("since:{reference}"),
+            Self::Staged => "staged".to_owned(),
+            Self::Uncommitted => "uncommitted".to_owned(),
+        }
+    }
+    pub(crate) const fn option_name(&self) -> &'static str {
+        match self {
+            Self::Since(_) => "--changed-since",
+            Self::Staged => "--staged",
+            Self::Uncommitted => "--uncommitted",
+        }
+    }
+    fn diff_target(&self, root_path: &Path) -> io::Result<String> {
+        match self {
+            Self::Since(reference) => resolve_commit(root_path, reference),
+            Self::Staged => Ok("--cached".to_owned()),
+            Self::Uncommitted => Ok("HEAD".to_owned()),
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum Tracking {
+    TrackedOnly,
+    IncludeUntracked,
+}
+impl Tracking {
+    pub(crate) const fn name(self) -> &'static str {
+        match self {
+            Self::TrackedOnly => "tracked-only",
+            Self::IncludeUntracked => "include-untracked",
+        }
+    }
+}
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub(crate) struct TrackedFiles {
+    pub(crate) tracked: BTreeSet<String>,
+    pub(crate) untracked: BTreeSet<String>,
+}
+impl TrackedFiles {
+    pub(crate) fn contains_under(&self, directory: &str) -> bool {
+        let prefix = format!("{directory}/");
+        self.tracked
+            .iter()
+            .chain(&self.untracked)
+            .any(|path| path.starts_with(&prefix))
+    }
+}
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub(crate) struct ChangedFiles {
+    pub(crate) changed: BTreeSet<String>,
+    pub(crate) deleted: Vec<String>,
+}
+pub(crate) fn changed_files(root_path: &Path, scope: &ChangeScope) -> io::Result<ChangedFiles> {
+    let target = scope.diff_target(root_path)?;
+    let diff = git_stdout(
+        root_path,
+        &[
+            "diff",
+            "--name-status",
+            "--no-renames",
+            "--relative",
+            "-z",
+            &target,
+            "--",
+        ],
+    )?;
+    let mut changes = ChangedFiles::default();
+    let mut fields = diff.split('\0').filter(|field| !field.is_empty());
+    while let (Some(status), Some(path)) = (fields.next(), fields.next()) {
+        let relative_path = git_path_text(path)?;
+        if status.starts_with('D') {
+            changes.deleted.push(relative_path);
+        } else {
+            changes.changed.insert(relative_path);
+        }
+    }
+    if *scope != ChangeScope::Staged {
+        let untracked = git_stdout(
+            root_path,
+            &["ls-files", "--others", "--exclude-standard", "-z"],
+        )?;
+        for path in untracked.split('\0').filter(|field| !field.is_empty()) {
+            changes.changed.insert(git_path_text(path)?);
+        }
+    }
+    Ok(changes)
+}
+pub(crate) fn tracked_files(root_path: &Path, tracking: Tracking) -> io::Result<TrackedFiles> {
+    let mut files = TrackedFiles {
+        tracked: git_paths(root_path, &["ls-files", "--cached", "-z"])?,
+        untracked: BTreeSet::new(),
+    };
+    if tracking == Tracking::IncludeUntracked {
+        files.untracked = git_paths(
+            root_path,
+            &["ls-files", "--others", "--exclude-standard", "-z"],
+        )?;
+    }
+    Ok(files)
+}
+fn git_paths(root_path: &Path, args: &[&str]) -> io::Result<BTreeSet<String>> {
+    git_stdout(root_path, args)?
+        .split('\0')
+        .filter(|field| !field.is_empty())
+        .map(git_path_text)
+        .collect()
+}
+pub(crate) fn git_path_text(path: &str) -> io::Result<String> {
+    let native: PathBuf = path.split('/').collect();
+    paths::relative_path_text(&native)
+}
+#[cfg(test)]
+mod tests;
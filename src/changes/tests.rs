@@ -0,0 +1,129 @@
+use super::{ChangeScope, Tracking, changed_files, tracked_files};
+use crate::test_support::{TestDir, must, must_err};
+use std::fs;
+fn committed_repository(name: &str) -> TestDir {
+    let dir = must(TestDir::new(name), "创建测试目录失败");
+    must(
+        dir.write_str("kept.rs", "fn kept() {}\n"),
+        "写入 kept.rs 失败",
+    );
+    must(
+        dir.write_str("edited.rs", "fn a() {}\n"),
+        "写入 edited.rs 失败",
+    );
+    must(
+        dir.write_str("removed.rs", "fn b() {}\n"),
+        "写入 removed.rs 失败",
+    );
+    must(dir.git(&["init", "-q"]), "初始化仓库失败");
+    must(dir.git(&["add", "-A"]), "暂存文件失败");
+    must(dir.git(&["commit", "-q", "-m", "init"]), "提交失败");
+    dir
+}
+#[test]
+fn uncommitted_changes_include_untracked_and_deleted_files() {
+    let dir = committed_repository("changes-uncommitted");
+    must(
+        dir.write_str("edited.rs", "fn a() { 1; }\n"),
+        "修改 edited.rs 失败",
+    );
+    must(dir.write_str("new.rs", "fn c() {}\n"), "写入 new.rs 失败");
+    must(
+        fs::remove_file(dir.path().join("removed.rs")),
+        "删除 removed.rs 失败",
+    );
+    let changes = must(
+        changed_files(dir.path(), &ChangeScope::Uncommitted),
+        "读取 git 变更失败",
+    );
+    assert_eq!(
+        changes
+            .changed
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>(),
+        ["edited.rs", "new.rs"]
+    );
+    assert_eq!(changes.deleted, ["removed.rs"]);
+}
+#[test]
+fn staged_and_since_scopes_follow_git() {
+    let dir = committed_repository("changes-staged");
+    let first = must(dir.git(&["rev-parse", "HEAD"]), "读取提交失败");
+    must(
+        dir.write_str("edited.rs", "fn a() { 2; }\n"),
+        "修改 edited.rs 失败",
+    );
+    must(dir.git(&["add", "edited.rs"]), "暂存 edited.rs 失败");
+    must(
+        dir.write_str("kept.rs", "fn kept() { 3; }\n"),
+        "修改 kept.rs 失败",
+    );
+    must(dir.write_str("new.rs", "fn c() {}\n"), "写入 new.rs 失败");
+    let staged = must(
+        changed_files(dir.path(), &ChangeScope::Staged),
+        "读取暂存变更失败",
+    );
+    assert_eq!(
+        staged
+            .changed
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>(),
+        ["edited.rs"]
+    );
+    must(dir.git(&["commit", "-q", "-m", "edit"]), "提交失败");
+    let since = must(
+        changed_files(dir.path(), &ChangeScope::Since(first)),
+        "读取历史变更失败",
+    );
+    assert_eq!(
+        since.changed.iter().map(String::as_str).collect::<Vec<_>>(),
+        ["edited.rs", "kept.rs", "new.rs"]
+    );
+    assert!(since.deleted.is_empty());
+}
+#[test]
+fn unknown_reference_is_reported() {
+    let dir = committed_repository("changes-bad-ref");
+    let err = must_err(
+        changed_files(dir.path(), &ChangeScope::Since("nope".to_owned())),
+        "未知的引用应当报错",
+    );
+    assert!(err.to_string().contains("nope"));
+    let option = must_err(
+        changed_files(
+            dir.path(),
+            &ChangeScope::Since("--output=injected.txt".to_owned()),
+        ),
+        "以 - 开头的引用应当报错",
+    );
+    assert!(option.to_string().contains("--output=injected.txt"));
+    assert!(!dir.path().join("injected.txt").exists());
+}
+#[test]
+fn tracked_files_list_the_index_and_optionally_untracked_files() {
+    let dir = committed_repository("changes-tracked");
+    must(dir.write_str("new.rs", "fn c() {}\n"), "写入 new.rs 失败");
+    let tracked = must(
+        tracked_files(dir.path(), Tracking::TrackedOnly),
+        "读取已跟踪文件失败",
+    );
+    assert_eq!(
+        tracked
+            .tracked
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>(),
+        ["edited.rs", "kept.rs", "removed.rs"]
+    );
+    assert!(tracked.untracked.is_empty());
+    let all = must(
+        tracked_files(dir.path(), Tracking::IncludeUntracked),
+        "读取未跟踪文件失败",
+    );
+    assert_eq!(
+        all.untracked.iter().map(String::as_str).collect::<Vec<_>>(),
+        ["new.rs"]
+    );
+}
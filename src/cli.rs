@@ -1,18 +1,498 @@
+use crate::{
+    attributes::GeneratedFiles,
+    changes::{ChangeScope, Tracking},
+    errors::ErrorFormat,
+    front_matter::ResolvedOption,
+    gitmodules::SubmoduleMode,
+    history::{HistoryOrder, HistoryWindow},
+    i18n::{Locale, Message, tr},
+    logging::{LogFormat, LogLevel},
+    modified::ModifiedWindow,
+    permalink::{LineAnchors, PermalinkStyle},
+    render::{DocumentMode, OutputFormat},
+    tree::{TreeAnnotations, TreeOptions, TreeStyle},
+};
 use std::{env, ffi::OsString, io, path::PathBuf};
-pub(crate) fn input_path_from_args<I>(raw_args: I) -> io::Result<PathBuf>
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub(crate) enum Command {
+    #[default]
+    Generate,
+    DryRun,
+    Explain(PathBuf),
+}
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub(crate) struct CliOptions {
+    pub(crate) root_path: PathBuf,
+    pub(crate) command: Command,
+    pub(crate) config_path: Option<PathBuf>,
+    pub(crate) format: OutputFormat,
+    pub(crate) mode: DocumentMode,
+    pub(crate) summary: bool,
+    pub(crate) front_matter: bool,
+    pub(crate) content_selection: Vec<String>,
+    pub(crate) exclusions: Vec<String>,
+    pub(crate) changes: Option<ChangeScope>,
+    pub(crate) diff_reference: Option<String>,
+    pub(crate) permalink: Option<PermalinkStyle>,
+    pub(crate) line_anchors: LineAnchors,
+    pub(crate) revision: Option<String>,
+    pub(crate) tracking: Option<Tracking>,
+    pub(crate) generated_files: GeneratedFiles,
+    pub(crate) submodules: SubmoduleMode,
+    pub(crate) order: Option<HistoryOrder>,
+    pub(crate) touched: Option<HistoryWindow>,
+    pub(crate) modified: Option<ModifiedWindow>,
+    pub(crate) files_from: Option<PathBuf>,
+    pub(crate) template_path: Option<PathBuf>,
+    pub(crate) language_overrides: Vec<String>,
+    pub(crate) tree: TreeOptions,
+    pub(crate) max_file_size: Option<u64>,
+    pub(crate) show_ignored_directories: bool,
+    pub(crate) lang: Option<Locale>,
+    pub(crate) document_lang: Option<Locale>,
+    pub(crate) console_lang: Option<Locale>,
+    pub(crate) error_format: ErrorFormat,
+    pub(crate) log_level: LogLevel,
+    pub(crate) log_format: LogFormat,
+}
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum ArgumentSource {
+    CommandLine,
+    Config,
+}
+pub(crate) fn parse_args<I>(raw_args: I) -> io::Result<CliOptions>
+where
+    I: IntoIterator<Item = OsString>,
+{
+    parse_args_with_config(raw_args, Vec::new())
+}
+pub(crate) fn parse_args_with_config<I>(
+    raw_args: I,
+    config_args: Vec<OsString>,
+) -> io::Result<CliOptions>
 where
     I: IntoIterator<Item = OsString>,
 {
     let mut arguments = raw_args.into_iter();
     let _program = arguments.next();
-    let input_path = arguments.next();
-    if arguments.next().is_some() {
-        return Err(io::Error::new(
+    let mut options = CliOptions::default();
+    apply_arguments(&mut options, config_args, ArgumentSource::Config)?;
+    let input_path = apply_arguments(&mut options, arguments, ArgumentSource::CommandLine)?;
+    options.root_path = input_path.map_or_else(env::current_dir, |path| Ok(PathBuf::from(path)))?;
+    check_revision_conflicts(&options)?;
+    Ok(options)
+}
+fn check_revision_conflicts(options: &CliOptions) -> io::Result<()> {
+    if options.revision.is_none() {
+        return Ok(());
+    }
+    let conflict = if matches!(options.command, Command::Explain(_)) {
+        Some("explain".to_owned())
+    } else if let Some(scope) = options.changes.as_ref() {
+        Some(scope.option_name().to_owned())
+    } else {
+        options
+            .tracking
+            .map(|tracking| format!("--{}", tracking.name()))
+    };
+    conflict.map_or(Ok(()), |name| {
+        Err(io::Error::new(
             io::ErrorKind::InvalidInput,
-            "只接受一个项目路径参数",
+            tr(Message::RevisionConflict, &[&name]),
+        ))
+    })
+}
+fn apply_arguments<I>(
+    options: &mut CliOptions,
+    raw_args: I,
+    source: ArgumentSource,
+) -> io::Result<Option<OsString>>
+where
+    I: IntoIterator<Item = OsString>,
+{
+    let mut arguments = raw_args.into_iter();
+    let mut input_path = None;
+    while let Some(argument) = arguments.next() {
+        match argument.to_str() {
+            Some("--console-lang") => {
+                options.console_lang = Some(parse_locale(arguments.next(), "--console-lang")?);
+            }
+            Some("--config") => {
+                options.config_path = Some(path_value(arguments.next(), "--config")?);
+            }
+            Some("--dry-run") => options.command = Command::DryRun,
+            Some("--diff") => {
+                options.diff_reference = Some(option_value(arguments.next(), "--diff")?);
+            }
+            Some("--document-lang") => {
+                options.document_lang = Some(parse_locale(arguments.next(), "--document-lang")?);
+            }
+            Some("--error-format") => {
+                options.error_format = parse_error_format(arguments.next())?;
+            }
+            Some("--changed-since") => {
+                let reference = option_value(arguments.next(), "--changed-since")?;
+                options.changes = Some(ChangeScope::Since(reference));
+            }
+            Some("--exclude") => {
+                let pattern = option_value(arguments.next(), "--exclude")?;
+                options.exclusions.push(pattern);
+            }
+            Some("--files-from") => {
+                options.files_from = Some(path_value(arguments.next(), "--files-from")?);
+            }
+            Some("--format") => options.format = parse_format(arguments.next())?,
+            Some("--front-matter") => options.front_matter = true,
+            Some("--lang") => options.lang = Some(parse_locale(arguments.next(), "--lang")?),
+            Some("--include-generated") => options.generated_files = GeneratedFiles::Include,
+            Some("--include-untracked") => options.tracking = Some(Tracking::IncludeUntracked),
+            Some("--language") => {
+                let spec = option_value(arguments.next(), "--language")?;
+                options.language_overrides.push(spec);
+            }
+            Some("--log-format") => options.log_format = parse_log_format(arguments.next())?,
+            Some("--max-file-size") => {
+                options.max_file_size = Some(parse_size(arguments.next(), "--max-file-size")?);
+            }
+            Some("--sort") => options.order = Some(parse_history_order(arguments.next())?),
+            Some("--staged") => options.changes = Some(ChangeScope::Staged),
+            Some("--submodules") => options.submodules = parse_submodules(arguments.next())?,
+            Some("--summary") => options.summary = true,
+            Some("--template") => {
+                options.template_path = Some(path_value(arguments.next(), "--template")?);
+            }
+            Some("--mode") => options.mode = parse_mode(arguments.next())?,
+            Some("-q" | "--quiet") => options.log_level = LogLevel::Quiet,
+            Some("--permalink") => options.permalink = Some(parse_permalink(arguments.next())?),
+            Some("--modified-since") => {
+                options.modified = Some(parse_modified_since(arguments.next())?);
+            }
+            Some("--modified-within") => {
+                options.modified = Some(parse_modified_within(arguments.next())?);
+            }
+            Some("--permalink-lines") => options.line_anchors = LineAnchors::Range,
+            Some("--rev") => options.revision = Some(option_value(arguments.next(), "--rev")?),
+            Some("--select") => {
+                let pattern = option_value(arguments.next(), "--select")?;
+                options.content_selection.push(pattern);
+            }
+            Some("--show-ignored-dirs") => options.show_ignored_directories = true,
+            Some("--touched-within") => {
+                options.touched = Some(parse_history_window(arguments.next())?);
+            }
+            Some("--tracked-only") => options.tracking = Some(Tracking::TrackedOnly),
+            Some("--tree-annotations") => {
+                options.tree.annotations = parse_tree_annotations(arguments.next())?;
+            }
+            Some("--tree-collapse") => {
+                options.tree.collapse_threshold =
+                    Some(parse_count(arguments.next(), "--tree-collapse")?);
+            }
+            Some("--tree-dir-totals") => options.tree.directory_totals = true,
+            Some("--tree-style") => options.tree.style = parse_tree_style(arguments.next())?,
+            Some("--uncommitted") => options.changes = Some(ChangeScope::Uncommitted),
+            Some("-v" | "--verbose") => options.log_level = options.log_level.louder(),
+            Some("-vv") => options.log_level = LogLevel::Trace,
+            Some(unknown) if unknown.starts_with("--") => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    tr(Message::UnknownOption, &[&unknown]),
+                ));
+            }
+            Some("explain")
+                if source == ArgumentSource::CommandLine
+                    && input_path.is_none()
+                    && !matches!(options.command, Command::Explain(_)) =>
+            {
+                options.command = Command::Explain(path_value(arguments.next(), "explain")?);
+            }
+            _ if source == ArgumentSource::Config => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    tr(Message::PathInConfig, &[&argument.display()]),
+                ));
+            }
+            _ if input_path.is_none() => input_path = Some(argument),
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    tr(Message::SinglePathOnly, &[]),
+                ));
+            }
+        }
+    }
+    Ok(input_path)
+}
+fn parse_format(value: Option<OsString>) -> io::Result<OutputFormat> {
+    let name = option_value(value, "--format")?;
+    OutputFormat::parse(&name).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            tr(Message::UnsupportedFormat, &[&name]),
+        )
+    })
+}
+pub(crate) fn resolved_options(options: &CliOptions) -> Vec<ResolvedOption> {
+    let mut resolved = vec![
+        ResolvedOption::single("format", options.format.name()),
+        ResolvedOption::single("mode", options.mode.name()),
+        ResolvedOption::single("summary", options.summary.to_string()),
+        ResolvedOption::single("document-lang", document_locale(options).name()),
+        ResolvedOption::single("tree-style", options.tree.style.name()),
+        ResolvedOption::single("tree-annotations", options.tree.annotations.names()),
+        ResolvedOption::single("tree-dir-totals", options.tree.directory_totals.to_string()),
+        ResolvedOption::single(
+            "show-ignored-dirs",
+            options.show_ignored_directories.to_string(),
+        ),
+    ];
+    if let Some(threshold) = options.tree.collapse_threshold {
+        resolved.push(ResolvedOption::single(
+            "tree-collapse",
+            threshold.to_string(),
         ));
     }
-    input_path.map_or_else(env::current_dir, |path| Ok(PathBuf::from(path)))
+    if let Some(limit) = options.max_file_size {
+        resolved.push(ResolvedOption::single("max-file-size", limit.to_string()));
+    }
+    if let Some(template_path) = options.template_path.as_ref() {
+        resolved.push(ResolvedOption::single(
+            "template",
+            template_path.display().to_string(),
+        ));
+    }
+    resolved.push(ResolvedOption::list("select", &options.content_selection));
+    resolved.push(ResolvedOption::list("exclude", &options.exclusions));
+    if let Some(reference) = options.diff_reference.as_ref() {
+        resolved.push(ResolvedOption::single("diff", reference.clone()));
+    }
+    if let Some(permalink) = options.permalink.as_ref() {
+        resolved.push(ResolvedOption::single("permalink", permalink.describe()));
+        resolved.push(ResolvedOption::single(
+            "permalink-lines",
+            (options.line_anchors == LineAnchors::Range).to_string(),
+        ));
+    }
+    if let Some(revision) = options.revision.as_ref() {
+        resolved.push(ResolvedOption::single("rev", revision.clone()));
+    }
+    if options.generated_files == GeneratedFiles::Include {
+        resolved.push(ResolvedOption::single(
+            "include-generated",
+            "true".to_owned(),
+        ));
+    }
+    resolved.push(ResolvedOption::single(
+        "submodules",
+        options.submodules.name().to_owned(),
+    ));
+    if let Some(files_from) = options.files_from.as_ref() {
+        resolved.push(ResolvedOption::single(
+            "files-from",
+            files_from.display().to_string(),
+        ));
+    }
+    if let Some(window) = options.modified {
+        resolved.push(ResolvedOption::single(
+            window.option_name(),
+            window.describe(),
+        ));
+    }
+    if let Some(order) = options.order {
+        resolved.push(ResolvedOption::single("sort", order.describe()));
+    }
+    if let Some(window) = options.touched {
+        resolved.push(ResolvedOption::single("touched-within", window.describe()));
+    }
+    if let Some(tracking) = options.tracking {
+        resolved.push(ResolvedOption::single(
+            "tracking",
+            tracking.name().to_owned(),
+        ));
+    }
+    if let Some(changes) = options.changes.as_ref() {
+        resolved.push(ResolvedOption::single("changes", changes.describe()));
+    }
+    resolved.push(ResolvedOption::list(
+        "language",
+        &options.language_overrides,
+    ));
+    resolved
+}
+pub(crate) fn document_locale(options: &CliOptions) -> Locale {
+    options
+        .document_lang
+        .or(options.lang)
+        .unwrap_or_else(Locale::from_environment)
+}
+pub(crate) fn console_locale(options: &CliOptions) -> Locale {
+    options
+        .console_lang
+        .or(options.lang)
+        .unwrap_or_else(Locale::from_environment)
+}
+fn parse_error_format(value: Option<OsString>) -> io::Result<ErrorFormat> {
+    let name = option_value(value, "--error-format")?;
+    ErrorFormat::parse(&name).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            tr(Message::UnsupportedErrorFormat, &[&name]),
+        )
+    })
+}
+fn parse_log_format(value: Option<OsString>) -> io::Result<LogFormat> {
+    let name = option_value(value, "--log-format")?;
+    LogFormat::parse(&name).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            tr(Message::UnsupportedLogFormat, &[&name]),
+        )
+    })
+}
+fn parse_mode(value: Option<OsString>) -> io::Result<DocumentMode> {
+    let name = option_value(value, "--mode")?;
+    DocumentMode::parse(&name).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            tr(Message::UnsupportedMode, &[&name]),
+        )
+    })
+}
+fn parse_permalink(value: Option<OsString>) -> io::Result<PermalinkStyle> {
+    let style = option_value(value, "--permalink")?;
+    PermalinkStyle::parse(&style).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            tr(Message::InvalidPermalinkStyle, &[&style]),
+        )
+    })
+}
+fn parse_history_order(value: Option<OsString>) -> io::Result<HistoryOrder> {
+    let order = option_value(value, "--sort")?;
+    HistoryOrder::parse(&order).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            tr(Message::InvalidHistoryOrder, &[&order]),
+        )
+    })
+}
+fn parse_history_window(value: Option<OsString>) -> io::Result<HistoryWindow> {
+    let window = option_value(value, "--touched-within")?;
+    HistoryWindow::parse(&window).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            tr(Message::InvalidHistoryWindow, &[&window]),
+        )
+    })
+}
+fn parse_modified_within(value: Option<OsString>) -> io::Result<ModifiedWindow> {
+    let duration = option_value(value, "--modified-within")?;
+    ModifiedWindow::parse_within(&duration).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            tr(Message::InvalidModifiedWithin, &[&duration]),
+        )
+    })
+}
+fn parse_modified_since(value: Option<OsString>) -> io::Result<ModifiedWindow> {
+    let timestamp = option_value(value, "--modified-since")?;
+    ModifiedWindow::parse_since(&timestamp).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            tr(Message::InvalidModifiedSince, &[&timestamp]),
+        )
+    })
+}
+fn parse_submodules(value: Option<OsString>) -> io::Result<SubmoduleMode> {
+    let name = option_value(value, "--submodules")?;
+    SubmoduleMode::parse(&name).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            tr(Message::InvalidSubmoduleMode, &[&name]),
+        )
+    })
+}
+fn parse_tree_style(value: Option<OsString>) -> io::Result<TreeStyle> {
+    let name = option_value(value, "--tree-style")?;
+    TreeStyle::parse(&name).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            tr(Message::UnsupportedTreeStyle, &[&name]),
+        )
+    })
+}
+fn parse_tree_annotations(value: Option<OsString>) -> io::Result<TreeAnnotations> {
+    let list = option_value(value, "--tree-annotations")?;
+    TreeAnnotations::parse(&list).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            tr(Message::UnsupportedTreeAnnotations, &[&list]),
+        )
+    })
+}
+fn parse_locale(value: Option<OsString>, option: &str) -> io::Result<Locale> {
+    let name = option_value(value, option)?;
+    Locale::parse(&name).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            tr(Message::UnsupportedLocale, &[&name]),
+        )
+    })
+}
+fn parse_count(value: Option<OsString>, option: &str) -> io::Result<usize> {
+    let text = option_value(value, option)?;
+    text.parse().map_err(|err| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            tr(Message::InvalidCount, &[&option, &text, &err]),
+        )
+    })
+}
+fn parse_size(value: Option<OsString>, option: &str) -> io::Result<u64> {
+    let text = option_value(value, option)?;
+    size_bytes(&text).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            tr(Message::InvalidSize, &[&option, &text]),
+        )
+    })
+}
+pub(crate) fn size_bytes(text: &str) -> Option<u64> {
+    let lowercase = text.trim().to_ascii_lowercase();
+    let number_end = lowercase
+        .find(|character: char| !character.is_ascii_digit())
+        .unwrap_or(lowercase.len());
+    let (number, unit) = lowercase.split_at(number_end);
+    let multiplier: u64 = match unit.trim() {
+        "" | "b" => 1,
+        "k" | "kb" | "kib" => 1 << 10,
+        "m" | "mb" | "mib" => 1 << 20,
+        "g" | "gb" | "gib" => 1 << 30,
+        _ => return None,
+    };
+    number.parse::<u64>().ok()?.checked_mul(multiplier)
+}
+fn path_value(value: Option<OsString>, option: &str) -> io::Result<PathBuf> {
+    value.map(PathBuf::from).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            tr(Message::MissingOptionValue, &[&option]),
+        )
+    })
+}
+fn option_value(value: Option<OsString>, option: &str) -> io::Result<String> {
+    let raw_value = value.ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            tr(Message::MissingOptionValue, &[&option]),
+        )
+    })?;
+    raw_value.into_string().map_err(|raw| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            tr(Message::OptionValueNotUtf8, &[&option, &raw.display()]),
+        )
+    })
 }
 #[cfg(test)]
 mod tests;
@@ -1,26 +1,36 @@
-use super::input_path_from_args;
-use crate::test_support::{must, must_err};
+use super::{
+    Command, console_locale, document_locale, parse_args, parse_args_with_config, resolved_options,
+    size_bytes,
+};
+use crate::{
+    i18n::{Locale, Message, tr},
+    logging::{LogFormat, LogLevel},
+    render::OutputFormat,
+    test_support::{must, must_err},
+    tree::{TreeAnnotation, TreeStyle},
+};
 use std::{ffi::OsString, io, path::PathBuf};
 #[test]
 fn explicit_argument_becomes_input_path() {
-    let path = must(
-        input_path_from_args([OsString::from("proj2md"), OsString::from("sample-project")]),
+    let options = must(
+        parse_args([OsString::from("proj2md"), OsString::from("sample-project")]),
         "解析显式路径参数失败",
     );
-    assert_eq!(path, PathBuf::from("sample-project"));
+    assert_eq!(options.root_path, PathBuf::from("sample-project"));
+    assert_eq!(options.format, OutputFormat::Markdown);
 }
 #[test]
 fn missing_argument_uses_current_directory() {
-    let path = must(
-        input_path_from_args([OsString::from("proj2md")]),
+    let options = must(
+        parse_args([OsString::from("proj2md")]),
         "解析缺省路径参数失败",
     );
-    assert!(path.is_dir());
+    assert!(options.root_path.is_dir());
 }
 #[test]
 fn extra_arguments_are_rejected() {
     let err = must_err(
-        input_path_from_args([
+        parse_args([
             OsString::from("proj2md"),
             OsString::from("one"),
             OsString::from("two"),
@@ -29,3 +39,206 @@ fn extra_arguments_are_rejected() {
     );
     assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
 }
+#[test]
+fn format_option_selects_renderer() {
+    let options = must(
+        parse_args([
+            OsString::from("proj2md"),
+            OsString::from("--format"),
+            OsString::from("json"),
+            OsString::from("sample-project"),
+        ]),
+        "解析输出格式失败",
+    );
+    assert_eq!(options.format, OutputFormat::Json);
+    assert_eq!(options.root_path, PathBuf::from("sample-project"));
+}
+#[test]
+fn unknown_format_is_rejected() {
+    let err = must_err(
+        parse_args([
+            OsString::from("proj2md"),
+            OsString::from("--format"),
+            OsString::from("yaml"),
+        ]),
+        "未知输出格式必须报错",
+    );
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+}
+#[test]
+fn command_line_overrides_config_arguments() {
+    let options = must(
+        parse_args_with_config(
+            [
+                OsString::from("proj2md"),
+                OsString::from("--format"),
+                OsString::from("xml"),
+            ],
+            vec![
+                OsString::from("--format"),
+                OsString::from("json"),
+                OsString::from("--template"),
+                OsString::from("bundle.tmpl"),
+            ],
+        ),
+        "合并配置参数失败",
+    );
+    assert_eq!(options.format, OutputFormat::Xml);
+    assert_eq!(options.template_path, Some(PathBuf::from("bundle.tmpl")));
+}
+#[test]
+fn unknown_options_and_config_paths_are_rejected() {
+    let unknown = must_err(
+        parse_args([OsString::from("proj2md"), OsString::from("--bogus")]),
+        "未知选项必须报错",
+    );
+    assert_eq!(
+        unknown.to_string(),
+        tr(Message::UnknownOption, &[&"--bogus"])
+    );
+    let positional = must_err(
+        parse_args_with_config([OsString::from("proj2md")], vec![OsString::from("dir")]),
+        "配置中的路径参数必须报错",
+    );
+    assert_eq!(positional.kind(), io::ErrorKind::InvalidInput);
+}
+#[test]
+fn tree_and_size_options_are_parsed() {
+    let options = must(
+        parse_args([
+            OsString::from("proj2md"),
+            OsString::from("--tree-style"),
+            OsString::from("ascii"),
+            OsString::from("--tree-annotations"),
+            OsString::from("size,lines"),
+            OsString::from("--tree-dir-totals"),
+            OsString::from("--max-file-size"),
+            OsString::from("512k"),
+            OsString::from("--tree-collapse"),
+            OsString::from("50"),
+            OsString::from("--show-ignored-dirs"),
+        ]),
+        "解析目录树选项失败",
+    );
+    assert_eq!(options.tree.style, TreeStyle::Ascii);
+    assert!(options.tree.annotations.contains(TreeAnnotation::Lines));
+    assert!(options.tree.directory_totals);
+    assert_eq!(options.max_file_size, Some(512 * 1024));
+    assert_eq!(options.tree.collapse_threshold, Some(50));
+    assert!(options.show_ignored_directories);
+    assert_eq!(size_bytes("2MiB"), Some(2 * 1024 * 1024));
+    assert_eq!(size_bytes("12 kb"), Some(12 * 1024));
+    assert_eq!(size_bytes("1.5m"), None);
+}
+#[test]
+fn resolved_options_describe_effective_settings() {
+    let options = must(
+        parse_args_with_config(
+            [
+                OsString::from("proj2md"),
+                OsString::from("--front-matter"),
+                OsString::from("--select"),
+                OsString::from("src/**"),
+            ],
+            vec![OsString::from("--format"), OsString::from("json")],
+        ),
+        "解析元数据选项失败",
+    );
+    assert!(options.front_matter);
+    let resolved = resolved_options(&options);
+    let format = resolved.iter().find(|option| option.name == "format");
+    assert_eq!(
+        format.map(|option| option.values.clone()),
+        Some(vec!["json".to_owned()])
+    );
+    let select = resolved.iter().find(|option| option.name == "select");
+    assert_eq!(
+        select.map(|option| (option.repeated, option.values.len())),
+        Some((true, 1))
+    );
+}
+#[test]
+fn language_flags_split_document_and_console() {
+    let options = must(
+        parse_args([
+            OsString::from("proj2md"),
+            OsString::from("--lang"),
+            OsString::from("en_US.UTF-8"),
+            OsString::from("--console-lang"),
+            OsString::from("zh-CN"),
+        ]),
+        "解析语言参数失败",
+    );
+    assert_eq!(document_locale(&options), Locale::En);
+    assert_eq!(console_locale(&options), Locale::ZhCn);
+    let err = must_err(
+        parse_args([
+            OsString::from("proj2md"),
+            OsString::from("--lang"),
+            OsString::from("fr"),
+        ]),
+        "不支持的语言必须报错",
+    );
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+}
+#[test]
+fn verbosity_and_log_format_flags_are_parsed() {
+    let options = must(
+        parse_args([
+            OsString::from("proj2md"),
+            OsString::from("-v"),
+            OsString::from("--verbose"),
+            OsString::from("--log-format"),
+            OsString::from("jsonl"),
+        ]),
+        "解析日志参数失败",
+    );
+    assert_eq!(options.log_level, LogLevel::Trace);
+    assert_eq!(options.log_format, LogFormat::Jsonl);
+    let quiet = must(
+        parse_args([OsString::from("proj2md"), OsString::from("-q")]),
+        "解析静默参数失败",
+    );
+    assert_eq!(quiet.log_level, LogLevel::Quiet);
+}
+#[test]
+fn dry_run_and_explain_select_commands() {
+    let dry_run = must(
+        parse_args([OsString::from("proj2md"), OsString::from("--dry-run")]),
+        "解析 --dry-run 失败",
+    );
+    assert_eq!(dry_run.command, Command::DryRun);
+    let explain = must(
+        parse_args([
+            OsString::from("proj2md"),
+            OsString::from("explain"),
+            OsString::from("src/main.rs"),
+        ]),
+        "解析 explain 失败",
+    );
+    assert_eq!(
+        explain.command,
+        Command::Explain(PathBuf::from("src/main.rs"))
+    );
+}
+#[test]
+fn revision_rejects_working_tree_options() {
+    let cases: [(&[&str], &str); 5] = [
+        (&["--tracked-only"], "--tracked-only"),
+        (&["--include-untracked"], "--include-untracked"),
+        (&["--staged"], "--staged"),
+        (&["--changed-since", "main"], "--changed-since"),
+        (&["explain", "src/main.rs"], "explain"),
+    ];
+    for (extra, name) in cases {
+        let mut args = vec![
+            OsString::from("proj2md"),
+            OsString::from("--rev"),
+            OsString::from("v1"),
+        ];
+        args.extend(extra.iter().map(OsString::from));
+        let err = must_err(parse_args(args), "--rev 与工作区选项组合必须报错");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert_eq!(err.to_string(), tr(Message::RevisionConflict, &[&name]));
+    }
+}
@@ -1,4 +1,6 @@
-use crate::errors::AppResult;
+use crate::errors::{AppResult, FailureKind};
+#[cfg(not(target_os = "windows"))]
+use crate::i18n::{Message, tr};
 use std::path::Path;
 #[cfg(target_os = "windows")]
 #[path = "clipboard/win32.rs"]
@@ -9,16 +11,14 @@ mod windows;
 pub(crate) fn copy_file_to_clipboard(file_path: &Path) -> AppResult<()> {
     #[cfg(target_os = "windows")]
     {
-        windows::copy_file_to_clipboard(file_path)?;
-        Ok(())
+        windows::copy_file_to_clipboard(file_path).map_err(|err| FailureKind::Clipboard.wrap(err))
     }
     #[cfg(not(target_os = "windows"))]
     {
-        let _ = file_path;
-        Err(std::io::Error::new(
+        let _: &Path = file_path;
+        Err(FailureKind::Clipboard.wrap(std::io::Error::new(
             std::io::ErrorKind::Unsupported,
-            "当前平台暂不支持复制到剪贴板",
-        )
-        .into())
+            tr(Message::ClipboardUnsupported, &[]),
+        )))
     }
 }
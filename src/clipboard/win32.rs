@@ -1,3 +1,4 @@
+use crate::i18n::{Message, tr};
 use core::{ffi::c_void, ptr::null_mut};
 use std::io;
 const CF_HDROP: u32 = 15;
@@ -25,7 +26,7 @@ unsafe extern "system" {
 pub(super) fn allocate_global_memory(size: usize) -> io::Result<Hglobal> {
     let memory = unsafe { GlobalAlloc(GMEM_MOVEABLE, size) };
     if memory.is_null() {
-        Err(last_os_error("分配全局内存失败"))
+        Err(last_os_error(Message::GlobalAllocFailed))
     } else {
         Ok(memory)
     }
@@ -33,7 +34,7 @@ pub(super) fn allocate_global_memory(size: usize) -> io::Result<Hglobal> {
 pub(super) fn lock_global_memory(memory: Hglobal) -> io::Result<*mut u8> {
     let pointer = unsafe { GlobalLock(memory) }.cast::<u8>();
     if pointer.is_null() {
-        Err(last_os_error("锁定全局内存失败"))
+        Err(last_os_error(Message::GlobalLockFailed))
     } else {
         Ok(pointer)
     }
@@ -42,7 +43,7 @@ pub(super) fn unlock_global_memory(memory: Hglobal) -> io::Result<()> {
     if unsafe { GlobalUnlock(memory) } == 0_i32 {
         let code = unsafe { GetLastError() };
         if code != 0 {
-            return Err(win32_error("解锁全局内存失败", code));
+            return Err(win32_error(Message::GlobalUnlockFailed, code));
         }
     }
     Ok(())
@@ -52,12 +53,12 @@ pub(super) fn free_global_memory(memory: Hglobal) -> io::Result<()> {
     if result.is_null() {
         Ok(())
     } else {
-        Err(last_os_error("释放全局内存失败"))
+        Err(last_os_error(Message::GlobalFreeFailed))
     }
 }
 pub(super) fn set_clipboard_file_drop(memory: Hglobal) -> io::Result<()> {
     if unsafe { OpenClipboard(null_mut()) } == 0_i32 {
-        return Err(last_os_error("打开剪贴板失败"));
+        return Err(last_os_error(Message::OpenClipboardFailed));
     }
     let operation_result = empty_clipboard_and_set_file(memory);
     let close_result = close_clipboard();
@@ -65,17 +66,17 @@ pub(super) fn set_clipboard_file_drop(memory: Hglobal) -> io::Result<()> {
 }
 fn empty_clipboard_and_set_file(memory: Hglobal) -> io::Result<()> {
     if unsafe { EmptyClipboard() } == 0_i32 {
-        return Err(last_os_error("清空剪贴板失败"));
+        return Err(last_os_error(Message::EmptyClipboardFailed));
     }
     if unsafe { SetClipboardData(CF_HDROP, memory.cast()) }.is_null() {
-        Err(last_os_error("写入剪贴板失败"))
+        Err(last_os_error(Message::SetClipboardFailed))
     } else {
         Ok(())
     }
 }
 fn close_clipboard() -> io::Result<()> {
     if unsafe { CloseClipboard() } == 0_i32 {
-        Err(last_os_error("关闭剪贴板失败"))
+        Err(last_os_error(Message::CloseClipboardFailed))
     } else {
         Ok(())
     }
@@ -93,17 +94,18 @@ fn merge_clipboard_results(
         ))),
     }
 }
-fn last_os_error(action: &str) -> io::Error {
+fn last_os_error(action: Message) -> io::Error {
     let code = unsafe { GetLastError() };
     if code == 0 {
-        io::Error::other(action.to_owned())
+        io::Error::other(tr(action, &[]))
     } else {
         win32_error(action, code)
     }
 }
-fn win32_error(action: &str, code: u32) -> io::Error {
+fn win32_error(action: Message, code: u32) -> io::Error {
+    let action = tr(action, &[]);
     i32::try_from(code).map_or_else(
-        |_| io::Error::other(format!("{action}: Win32 错误码 {code}")),
+        |_| io::Error::other(tr(Message::Win32ErrorCode, &[&action, &code])),
         |raw_code| {
             io::Error::other(format!(
                 "{action}: {}",
@@ -1,4 +1,5 @@
 use super::win32;
+use crate::i18n::{Message, tr};
 use core::{mem::size_of, ptr};
 use std::{io, os::windows::ffi::OsStrExt as _, path::Path};
 type Bool = i32;
@@ -46,17 +47,23 @@ fn build_file_drop_payload(file_path: &Path) -> io::Result<Vec<u8>> {
     let header_offset = u32::try_from(header_size).map_err(|err| {
         io::Error::new(
             io::ErrorKind::InvalidData,
-            format!("DROPFILES 头部大小超出 Win32 范围: {err}"),
+            tr(Message::DropFilesHeaderTooLarge, &[&err]),
         )
     })?;
     let path_bytes = wide_path
         .len()
         .checked_mul(size_of::<u16>())
         .ok_or_else(|| {
-            io::Error::new(io::ErrorKind::InvalidData, "计算路径缓冲区大小时发生溢出")
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                tr(Message::PathBufferOverflow, &[]),
+            )
         })?;
     let total_size = header_size.checked_add(path_bytes).ok_or_else(|| {
-        io::Error::new(io::ErrorKind::InvalidData, "计算剪贴板数据大小时发生溢出")
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            tr(Message::ClipboardSizeOverflow, &[]),
+        )
     })?;
     let mut payload = vec![0_u8; total_size];
     let header = DropFiles {
@@ -83,7 +90,10 @@ fn build_file_drop_payload(file_path: &Path) -> io::Result<Vec<u8>> {
 }
 fn free_memory_after_error(memory: win32::Hglobal, err: io::Error) -> io::Error {
     if let Err(free_err) = win32::free_global_memory(memory) {
-        io::Error::other(format!("{err}; 释放全局内存失败: {free_err}"))
+        io::Error::other(format!(
+            "{err}; {}: {free_err}",
+            tr(Message::GlobalFreeFailed, &[])
+        ))
     } else {
         err
     }
@@ -1,4 +1,292 @@
+use crate::i18n::{Message, tr};
+use core::fmt::Display;
+use std::{
+    ffi::OsString,
+    fs, io,
+    path::{Component, Path, PathBuf},
+};
 pub(crate) const OUTPUT_FILENAME: &str = "project.md";
-pub(crate) const EXTRA_EXCLUDED_FILES: [&str; 2] = ["LICENSE", "README.md"];
+pub(crate) const DEFAULT_EXCLUSIONS: [&str; 2] = ["/LICENSE", "/README.md"];
 pub(crate) const BINARY_SCAN_LIMIT: usize = 8192;
+pub(crate) const SIGNATURE_SCAN_LIMIT: u64 = 1024;
+pub(crate) const OUTPUT_EXTENSIONS: [&str; 4] = ["md", "markdown", "json", "xml"];
 pub(crate) const BINARY_CONTROL_PERCENT: usize = 30;
+pub(crate) const CONFIG_FILENAME: &str = ".proj2md.toml";
+const CONFIG_PATH_KEYS: [&str; 1] = ["template"];
+const PROJECT_CONFIG_KEYS: [&str; 32] = [
+    "console-lang",
+    "document-lang",
+    "dry-run",
+    "error-format",
+    "exclude",
+    "format",
+    "front-matter",
+    "include-generated",
+    "include-untracked",
+    "lang",
+    "language",
+    "log-format",
+    "max-file-size",
+    "mode",
+    "modified-since",
+    "modified-within",
+    "permalink",
+    "permalink-lines",
+    "quiet",
+    "select",
+    "show-ignored-dirs",
+    "sort",
+    "submodules",
+    "summary",
+    "template",
+    "touched-within",
+    "tracked-only",
+    "tree-annotations",
+    "tree-collapse",
+    "tree-dir-totals",
+    "tree-style",
+    "verbose",
+];
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum ConfigSource {
+    Explicit,
+    Project,
+}
+pub(crate) fn resolve_config_path(
+    root_path: &Path,
+    explicit_path: Option<&Path>,
+) -> Option<(PathBuf, ConfigSource)> {
+    explicit_path.map_or_else(
+        || {
+            let default_path = root_path.join(CONFIG_FILENAME);
+            default_path
+                .is_file()
+                .then_some((default_path, ConfigSource::Project))
+        },
+        |path| Some((path.to_path_buf(), ConfigSource::Explicit)),
+    )
+}
+pub(crate) fn load_config_arguments(
+    config_path: &Path,
+    source: ConfigSource,
+) -> io::Result<Vec<OsString>> {
+    let text = fs::read_to_string(config_path).map_err(|err| {
+        io::Error::new(
+            err.kind(),
+            tr(Message::ReadConfigFailed, &[&config_path.display(), &err]),
+        )
+    })?;
+    let base_dir = config_path.parent().unwrap_or_else(|| Path::new(""));
+    config_arguments(&text, base_dir, source)
+        .map_err(|err| io::Error::new(err.kind(), format!("{}: {err}", config_path.display())))
+}
+fn config_arguments(
+    text: &str,
+    base_dir: &Path,
+    source: ConfigSource,
+) -> io::Result<Vec<OsString>> {
+    let mut arguments = Vec::new();
+    let mut section: Option<String> = None;
+    for (index, raw_line) in text.lines().enumerate() {
+        let line_number = index.saturating_add(1);
+        let line = strip_comment(raw_line).trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(name) = line
+            .strip_prefix('[')
+            .and_then(|rest| rest.strip_suffix(']'))
+        {
+            section = Some(name.trim().to_owned());
+            continue;
+        }
+        let (raw_key, raw_value) = line
+            .split_once('=')
+            .ok_or_else(|| config_error(line_number, Message::ConfigMissingEquals))?;
+        let key = unquote_key(raw_key.trim());
+        let values = parse_values(raw_value.trim())
+            .ok_or_else(|| config_error(line_number, Message::ConfigInvalidValue))?;
+        let option = section.as_deref().unwrap_or(key);
+        if source == ConfigSource::Project && !PROJECT_CONFIG_KEYS.contains(&option) {
+            return Err(line_error(
+                line_number,
+                &tr(Message::ConfigKeyUntrusted, &[&option]),
+            ));
+        }
+        push_arguments(&mut arguments, section.as_deref(), key, values, base_dir)
+            .map_err(|err| line_error(line_number, &err))?;
+    }
+    Ok(arguments)
+}
+fn push_arguments(
+    arguments: &mut Vec<OsString>,
+    section: Option<&str>,
+    key: &str,
+    values: Vec<ConfigValue>,
+    base_dir: &Path,
+) -> io::Result<()> {
+    let option = section.unwrap_or(key);
+    for value in values {
+        let text = match value {
+            ConfigValue::Bool(true) if section.is_none() => {
+                arguments.push(format!("--{key}").into());
+                continue;
+            }
+            ConfigValue::Bool(false) if section.is_none() => continue,
+            found @ (ConfigValue::Bool(_) | ConfigValue::Text(_)) => found.into_text(),
+        };
+        let resolved: OsString = if CONFIG_PATH_KEYS.contains(&option) {
+            config_relative_path(base_dir, &text)?.into_os_string()
+        } else {
+            text.into()
+        };
+        arguments.push(format!("--{option}").into());
+        arguments.push(if section.is_some() {
+            let mut entry = OsString::from(format!("{key}="));
+            entry.push(resolved);
+            entry
+        } else {
+            resolved
+        });
+    }
+    Ok(())
+}
+fn config_relative_path(base_dir: &Path, text: &str) -> io::Result<PathBuf> {
+    let outside = || {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            tr(Message::ConfigPathOutside, &[&text]),
+        )
+    };
+    let relative = Path::new(text);
+    if relative
+        .components()
+        .any(|component| !matches!(component, Component::Normal(_) | Component::CurDir))
+    {
+        return Err(outside());
+    }
+    let path = base_dir.join(relative);
+    if let (Ok(base), Ok(target)) = (fs::canonicalize(base_dir), fs::canonicalize(&path))
+        && !target.starts_with(base)
+    {
+        return Err(outside());
+    }
+    Ok(path)
+}
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum ConfigValue {
+    Bool(bool),
+    Text(String),
+}
+impl ConfigValue {
+    fn into_text(self) -> String {
+        match self {
+            Self::Bool(flag) => flag.to_string(),
+            Self::Text(text) => text,
+        }
+    }
+}
+fn parse_values(raw_value: &str) -> Option<Vec<ConfigValue>> {
+    if let Some(inner) = raw_value
+        .strip_prefix('[')
+        .and_then(|rest| rest.strip_suffix(']'))
+    {
+        return split_array(inner)
+            .into_iter()
+            .map(|item| parse_scalar(item.trim()))
+            .collect();
+    }
+    parse_scalar(raw_value).map(|value| vec![value])
+}
+fn parse_scalar(raw_value: &str) -> Option<ConfigValue> {
+    match raw_value {
+        "true" => return Some(ConfigValue::Bool(true)),
+        "false" => return Some(ConfigValue::Bool(false)),
+        "" => return None,
+        _ => {}
+    }
+    if let Some(inner) = raw_value
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+    {
+        return unescape_basic(inner).map(ConfigValue::Text);
+    }
+    if let Some(inner) = raw_value
+        .strip_prefix('\'')
+        .and_then(|rest| rest.strip_suffix('\''))
+    {
+        return Some(ConfigValue::Text(inner.to_owned()));
+    }
+    Some(ConfigValue::Text(raw_value.to_owned()))
+}
+fn unescape_basic(inner: &str) -> Option<String> {
+    let mut text = String::with_capacity(inner.len());
+    let mut characters = inner.chars();
+    while let Some(character) = characters.next() {
+        if character != '\\' {
+            text.push(character);
+            continue;
+        }
+        match characters.next()? {
+            'n' => text.push('\n'),
+            't' => text.push('\t'),
+            '"' => text.push('"'),
+            '\\' => text.push('\\'),
+            _ => return None,
+        }
+    }
+    Some(text)
+}
+fn split_array(inner: &str) -> Vec<&str> {
+    let mut items = Vec::new();
+    let mut quote: Option<char> = None;
+    let mut start = 0_usize;
+    for (offset, character) in inner.char_indices() {
+        match (quote, character) {
+            (None, '"' | '\'') => quote = Some(character),
+            (Some(open), _) if open == character => quote = None,
+            (None, ',') => {
+                items.extend(inner.get(start..offset));
+                start = offset.saturating_add(1);
+            }
+            _ => {}
+        }
+    }
+    items.extend(inner.get(start..));
+    items.retain(|item| !item.trim().is_empty());
+    items
+}
+fn unquote_key(key: &str) -> &str {
+    for quote in ['"', '\''] {
+        if let Some(inner) = key
+            .strip_prefix(quote)
+            .and_then(|rest| rest.strip_suffix(quote))
+        {
+            return inner;
+        }
+    }
+    key
+}
+fn strip_comment(line: &str) -> &str {
+    let mut quote: Option<char> = None;
+    for (offset, character) in line.char_indices() {
+        match (quote, character) {
+            (None, '"' | '\'') => quote = Some(character),
+            (Some(open), _) if open == character => quote = None,
+            (None, '#') => return line.get(..offset).unwrap_or(line),
+            _ => {}
+        }
+    }
+    line
+}
+fn config_error(line_number: usize, reason: Message) -> io::Error {
+    line_error(line_number, &tr(reason, &[]))
+}
+fn line_error(line_number: usize, reason: &dyn Display) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        tr(Message::ConfigLineError, &[&line_number, reason]),
+    )
+}
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,136 @@
+use super::{ConfigSource, config_arguments, resolve_config_path};
+use crate::{
+    i18n::{Message, tr},
+    test_support::{TestDir, must, must_err},
+};
+use std::{ffi::OsString, io, path::Path};
+fn arguments(text: &str) -> Vec<OsString> {
+    must(
+        config_arguments(text, Path::new("base"), ConfigSource::Explicit),
+        "解析配置失败",
+    )
+}
+#[test]
+fn top_level_keys_become_long_options() {
+    let parsed =
+        arguments("# comment\nformat = \"json\" # trailing\n\nverbose = true\nquiet = false\n");
+    assert_eq!(
+        parsed,
+        [
+            OsString::from("--format"),
+            OsString::from("json"),
+            OsString::from("--verbose"),
+        ]
+    );
+}
+#[test]
+fn path_keys_are_resolved_against_config_directory() {
+    let parsed = arguments("template = 'bundle.tmpl'\n");
+    assert_eq!(
+        parsed,
+        [
+            OsString::from("--template"),
+            Path::new("base").join("bundle.tmpl").into_os_string(),
+        ]
+    );
+}
+#[test]
+fn path_sections_resolve_entry_values() {
+    let parsed = arguments("[template]\nbundle = 'bundle.tmpl'\n");
+    let mut entry = OsString::from("bundle=");
+    entry.push(Path::new("base").join("bundle.tmpl"));
+    assert_eq!(parsed, [OsString::from("--template"), entry]);
+}
+#[test]
+fn arrays_repeat_the_option() {
+    let parsed = arguments("exclude = [\"a,b\", 'c']\n");
+    assert_eq!(
+        parsed,
+        [
+            OsString::from("--exclude"),
+            OsString::from("a,b"),
+            OsString::from("--exclude"),
+            OsString::from("c"),
+        ]
+    );
+}
+#[test]
+fn section_entries_become_key_value_options() {
+    let parsed = arguments("[language]\n\"*.h\" = \"cpp\"\n'#!deno' = 'typescript'\n");
+    assert_eq!(
+        parsed,
+        [
+            OsString::from("--language"),
+            OsString::from("*.h=cpp"),
+            OsString::from("--language"),
+            OsString::from("#!deno=typescript"),
+        ]
+    );
+}
+#[test]
+fn malformed_lines_report_line_number() {
+    let err = must_err(
+        config_arguments(
+            "format = \"json\"\nbroken\n",
+            Path::new(""),
+            ConfigSource::Explicit,
+        ),
+        "缺少等号必须报错",
+    );
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert_eq!(
+        err.to_string(),
+        tr(
+            Message::ConfigLineError,
+            &[&2_usize, &tr(Message::ConfigMissingEquals, &[])]
+        )
+    );
+}
+#[test]
+fn default_config_is_found_in_project_root() {
+    let dir = must(TestDir::new("config-default"), "创建测试目录失败");
+    assert_eq!(resolve_config_path(dir.path(), None), None);
+    let config = must(
+        dir.write_str(".proj2md.toml", "format = \"xml\"\n"),
+        "写入配置文件失败",
+    );
+    assert_eq!(
+        resolve_config_path(dir.path(), None),
+        Some((config, ConfigSource::Project))
+    );
+}
+#[test]
+fn project_config_cannot_set_git_references_or_escape_its_directory() {
+    let err = must_err(
+        config_arguments(
+            "format = \"json\"\nchanged-since = \"--output=x\"\n",
+            Path::new(""),
+            ConfigSource::Project,
+        ),
+        "项目配置设置 changed-since 必须报错",
+    );
+    assert_eq!(
+        err.to_string(),
+        tr(
+            Message::ConfigLineError,
+            &[
+                &2_usize,
+                &tr(Message::ConfigKeyUntrusted, &[&"changed-since"])
+            ]
+        )
+    );
+    for template in [
+        "template = '/etc/passwd'",
+        "template = '../outside.tmpl'",
+        "[template]\nbundle = '../outside.tmpl'",
+    ] {
+        must_err(
+            config_arguments(
+                &format!("{template}\n"),
+                Path::new("base"),
+                ConfigSource::Explicit,
+            ),
+            "模板路径越界必须报错",
+        );
+    }
+}
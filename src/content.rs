@@ -1,12 +1,41 @@
 use crate::{
     config::{BINARY_CONTROL_PERCENT, BINARY_SCAN_LIMIT},
-    errors::AppResult,
+    errors::{AppResult, FailureKind},
+    i18n::{Locale, Message, tr, tr_in},
+    inventory::{FileEntry, Omission},
+    revision::read_object,
 };
 use chardetng::{EncodingDetector, Iso2022JpDetection, Utf8Detection};
 use encoding_rs::Encoding;
 use std::{fs, io, path::Path};
-pub(crate) const BINARY_MARKER: &str = "(二进制文件)";
-pub(crate) const DECODE_FAILURE_MARKER: &str = "(解码失败)";
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct FileContent {
+    pub(crate) text: String,
+    pub(crate) encoding: Option<&'static str>,
+    pub(crate) omission: Option<Omission>,
+}
+impl FileContent {
+    fn decoded(text: String, encoding: &'static Encoding) -> Self {
+        Self {
+            text,
+            encoding: Some(encoding.name()),
+            omission: None,
+        }
+    }
+    pub(crate) fn omitted(omission: Omission, locale: Locale) -> Self {
+        let marker = match omission {
+            Omission::Binary => Message::BinaryMarker,
+            Omission::TooLarge => Message::TooLargeMarker,
+            Omission::Undecodable => Message::DecodeFailureMarker,
+            Omission::Excluded => Message::ExcludedMarker,
+        };
+        Self {
+            text: tr_in(locale, marker, &[]),
+            encoding: None,
+            omission: Some(omission),
+        }
+    }
+}
 pub(crate) fn is_binary(bytes: &[u8]) -> io::Result<bool> {
     if bytes.is_empty() {
         return Ok(false);
@@ -19,55 +48,115 @@ pub(crate) fn is_binary(bytes: &[u8]) -> io::Result<bool> {
         }
         if byte < 0x09 || (byte > 0x0D && byte < 0x20) {
             control = control.checked_add(1).ok_or_else(|| {
-                io::Error::new(io::ErrorKind::InvalidData, "统计控制字符时发生溢出")
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    tr(Message::ControlCountOverflow, &[]),
+                )
             })?;
         }
     }
-    let control_scaled = control
-        .checked_mul(100)
-        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "计算控制字符比例时发生溢出"))?;
+    let control_scaled = control.checked_mul(100).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            tr(Message::ControlRatioOverflow, &[]),
+        )
+    })?;
     let total_scaled = sample_len
         .checked_mul(BINARY_CONTROL_PERCENT)
-        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "计算字节总数比例时发生溢出"))?;
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                tr(Message::TotalRatioOverflow, &[]),
+            )
+        })?;
     Ok(control_scaled > total_scaled)
 }
-pub(crate) fn read_file_content(path: &Path) -> AppResult<String> {
-    let bytes = fs::read(path)
-        .map_err(|err| io::Error::new(err.kind(), format!("读取文件失败: {}", path.display())))?;
-    if let Some(text) = decode_with_bom(path, &bytes)? {
-        return Ok(text);
+pub(crate) fn load_file_content(file: &FileEntry, locale: Locale) -> AppResult<FileContent> {
+    if let Some(omission) = file.omission {
+        return Ok(FileContent::omitted(omission, locale));
     }
-    if is_binary(&bytes)? {
-        return Ok(BINARY_MARKER.to_owned());
+    if let Some(object) = file.git_object.as_deref() {
+        let bytes =
+            read_object(&file.absolute_path, object).map_err(|err| FailureKind::Git.wrap(err))?;
+        return decode_bytes(&file.absolute_path, &bytes, None, locale)
+            .map_err(|err| FailureKind::Read.wrap(err));
     }
-    if let Ok(text) = core::str::from_utf8(&bytes) {
-        return Ok(text.to_owned());
+    read_file_content(
+        &file.absolute_path,
+        file.declared_encoding.as_deref(),
+        locale,
+    )
+}
+pub(crate) fn read_file_content(
+    path: &Path,
+    declared_encoding: Option<&str>,
+    locale: Locale,
+) -> AppResult<FileContent> {
+    decode_file(path, declared_encoding, locale).map_err(|err| FailureKind::Read.wrap(err))
+}
+fn decode_file(
+    path: &Path,
+    declared_encoding: Option<&str>,
+    locale: Locale,
+) -> io::Result<FileContent> {
+    let bytes = fs::read(path).map_err(|err| {
+        io::Error::new(err.kind(), tr(Message::ReadFileFailed, &[&path.display()]))
+    })?;
+    decode_bytes(path, &bytes, declared_encoding, locale)
+}
+fn decode_bytes(
+    path: &Path,
+    bytes: &[u8],
+    declared_encoding: Option<&str>,
+    locale: Locale,
+) -> io::Result<FileContent> {
+    if let Some(content) = decode_with_bom(path, bytes)? {
+        return Ok(content);
+    }
+    if let Some(encoding) =
+        declared_encoding.and_then(|label| Encoding::for_label(label.as_bytes()))
+    {
+        let (text, had_errors) = encoding.decode_without_bom_handling(bytes);
+        return Ok(if had_errors {
+            FileContent::omitted(Omission::Undecodable, locale)
+        } else {
+            FileContent::decoded(text.into_owned(), encoding)
+        });
+    }
+    if is_binary(bytes)? {
+        return Ok(FileContent::omitted(Omission::Binary, locale));
+    }
+    if let Ok(text) = core::str::from_utf8(bytes) {
+        return Ok(FileContent::decoded(text.to_owned(), encoding_rs::UTF_8));
     }
     let mut detector = EncodingDetector::new(Iso2022JpDetection::Allow);
-    detector.feed(&bytes, true);
+    detector.feed(bytes, true);
     let encoding = detector.guess(None, Utf8Detection::Allow);
-    let (text, _, had_errors) = encoding.decode(&bytes);
+    let (text, _, had_errors) = encoding.decode(bytes);
     if had_errors {
-        Ok(DECODE_FAILURE_MARKER.to_owned())
+        Ok(FileContent::omitted(Omission::Undecodable, locale))
     } else {
-        Ok(text.into_owned())
+        Ok(FileContent::decoded(text.into_owned(), encoding))
     }
 }
-fn decode_with_bom(path: &Path, bytes: &[u8]) -> io::Result<Option<String>> {
+pub(crate) fn count_lines(text: &str) -> usize {
+    text.lines().count()
+}
+fn decode_with_bom(path: &Path, bytes: &[u8]) -> io::Result<Option<FileContent>> {
     let Some((encoding, bom_len)) = Encoding::for_bom(bytes) else {
         return Ok(None);
     };
     let bytes_no_bom = bytes.get(bom_len..).ok_or_else(|| {
         io::Error::new(
             io::ErrorKind::InvalidData,
-            format!("BOM 长度异常: {}", path.display()),
+            tr(Message::InvalidBomLength, &[&path.display()]),
         )
     })?;
     let (text, _, had_errors) = encoding.decode(bytes_no_bom);
     if had_errors {
         Ok(None)
     } else {
-        Ok(Some(text.into_owned()))
+        Ok(Some(FileContent::decoded(text.into_owned(), encoding)))
     }
 }
 #[cfg(test)]
@@ -1,5 +1,9 @@
-use super::{BINARY_MARKER, is_binary, read_file_content};
-use crate::test_support::{TestDir, must, must_err};
+use super::{FileContent, is_binary, load_file_content, read_file_content};
+use crate::{
+    i18n::{Locale, Message, tr},
+    inventory::{FileEntry, Omission},
+    test_support::{TestDir, must, must_err},
+};
 #[test]
 fn empty_file_is_not_binary() {
     assert!(!must(is_binary(&[]), "检测空文件失败"));
@@ -26,8 +30,12 @@ fn read_file_content_reads_utf8() {
         dir.write_str("note.txt", "hello\n世界"),
         "写入 UTF-8 文件失败",
     );
-    let content = must(read_file_content(&file), "读取 UTF-8 文件失败");
-    assert_eq!(content, "hello\n世界");
+    let content = must(
+        read_file_content(&file, None, Locale::ZhCn),
+        "读取 UTF-8 文件失败",
+    );
+    assert_eq!(content.text, "hello\n世界");
+    assert_eq!(content.encoding, Some("UTF-8"));
 }
 #[test]
 fn read_file_content_removes_utf8_bom() {
@@ -36,8 +44,11 @@ fn read_file_content_removes_utf8_bom() {
         dir.write_bytes("note.txt", &[0xEF, 0xBB, 0xBF, b'o', b'k']),
         "写入 UTF-8 BOM 文件失败",
     );
-    let content = must(read_file_content(&file), "读取 UTF-8 BOM 文件失败");
-    assert_eq!(content, "ok");
+    let content = must(
+        read_file_content(&file, None, Locale::ZhCn),
+        "读取 UTF-8 BOM 文件失败",
+    );
+    assert_eq!(content.text, "ok");
 }
 #[test]
 fn read_file_content_decodes_utf16le_bom() {
@@ -46,8 +57,12 @@ fn read_file_content_decodes_utf16le_bom() {
         dir.write_bytes("note.txt", &[0xFF, 0xFE, b'H', 0, b'i', 0]),
         "写入 UTF-16LE 文件失败",
     );
-    let content = must(read_file_content(&file), "读取 UTF-16LE 文件失败");
-    assert_eq!(content, "Hi");
+    let content = must(
+        read_file_content(&file, None, Locale::ZhCn),
+        "读取 UTF-16LE 文件失败",
+    );
+    assert_eq!(content.text, "Hi");
+    assert_eq!(content.encoding, Some("UTF-16LE"));
 }
 #[test]
 fn read_file_content_marks_binary_files() {
@@ -56,15 +71,63 @@ fn read_file_content_marks_binary_files() {
         dir.write_bytes("image.bin", &[0, 1, 2, 3]),
         "写入二进制文件失败",
     );
-    let content = must(read_file_content(&file), "读取二进制文件失败");
-    assert_eq!(content, BINARY_MARKER);
+    let content = must(
+        read_file_content(&file, None, Locale::ZhCn),
+        "读取二进制文件失败",
+    );
+    assert_eq!(content.text, "(二进制文件)");
+    assert_eq!(content.omission, Some(Omission::Binary));
 }
 #[test]
 fn read_file_content_reports_missing_file() {
     let dir = must(TestDir::new("missing-file"), "创建测试目录失败");
+    let missing = dir.path().join("missing.txt");
     let err = must_err(
-        read_file_content(&dir.path().join("missing.txt")),
+        read_file_content(&missing, None, Locale::ZhCn),
         "缺失文件必须报错",
     );
-    assert!(err.to_string().contains("读取文件失败"));
+    assert!(
+        err.to_string()
+            .starts_with(&tr(Message::ReadFileFailed, &[&missing.display()]))
+    );
+}
+#[test]
+fn load_file_content_skips_reading_omitted_files() {
+    let dir = must(TestDir::new("too-large"), "创建测试目录失败");
+    let file = FileEntry {
+        absolute_path: dir.path().join("missing.bin"),
+        relative_path: "missing.bin".to_owned(),
+        code_block_language: String::new(),
+        size: 0,
+        omission: Some(Omission::TooLarge),
+        git_object: None,
+        untracked: false,
+        declared_encoding: None,
+        permalink: None,
+    };
+    let content = must(load_file_content(&file, Locale::En), "加载省略文件失败");
+    assert_eq!(
+        content,
+        FileContent::omitted(Omission::TooLarge, Locale::En)
+    );
+    assert_eq!(content.text, "(file too large, omitted)");
+}
+#[test]
+fn read_file_content_honours_declared_encoding() {
+    let dir = must(TestDir::new("declared-encoding"), "创建测试目录失败");
+    let file = must(
+        dir.write_bytes("utf16.txt", &[0x68, 0x00, 0x69, 0x00]),
+        "写入 UTF-16 文件失败",
+    );
+    let declared = must(
+        read_file_content(&file, Some("UTF-16LE"), Locale::ZhCn),
+        "按声明编码读取失败",
+    );
+    assert_eq!(declared.text, "hi");
+    assert_eq!(declared.encoding, Some("UTF-16LE"));
+    let sniffed = must(
+        read_file_content(&file, None, Locale::ZhCn),
+        "按探测读取失败",
+    );
+    assert_eq!(sniffed.omission, Some(Omission::Binary));
 }
@@ -0,0 +1,60 @@
+use crate::{
+    i18n::{Locale, Message, tr_in},
+    inventory::ProjectInventory,
+    tokens::estimate_tokens_from_size,
+    tree::{format_size, group_digits, omission_label},
+};
+pub(crate) fn dry_run_lines(inventory: &ProjectInventory, locale: Locale) -> Vec<String> {
+    let mut total_bytes = 0_u64;
+    let mut total_tokens = 0_usize;
+    let mut lines: Vec<String> = inventory
+        .content_files
+        .iter()
+        .map(|file| {
+            let tokens = if file.omission.is_none() {
+                estimate_tokens_from_size(file.size)
+            } else {
+                0
+            };
+            total_bytes = total_bytes.saturating_add(file.size);
+            total_tokens = total_tokens.saturating_add(tokens);
+            let mut line = format!(
+                "{} ({}, {})",
+                file.relative_path,
+                format_size(file.size),
+                tr_in(locale, Message::TokensCount, &[&group_digits(tokens)])
+            );
+            if file.untracked {
+                line.push(' ');
+                line.push_str(&tr_in(locale, Message::UntrackedMarker, &[]));
+            }
+            if let Some(omission) = file.omission {
+                line.push(' ');
+                line.push_str(&tr_in(
+                    locale,
+                    Message::OmittedMarker,
+                    &[&omission_label(omission, locale)],
+                ));
+            }
+            line
+        })
+        .collect();
+    lines.extend(
+        inventory
+            .deleted_files
+            .iter()
+            .map(|path| format!("{path} {}", tr_in(locale, Message::DeletedMarker, &[]))),
+    );
+    lines.push(tr_in(
+        locale,
+        Message::DryRunTotal,
+        &[
+            &group_digits(inventory.content_files.len()),
+            &format_size(total_bytes),
+            &group_digits(total_tokens),
+        ],
+    ));
+    lines
+}
+#[cfg(test)]
+mod tests;
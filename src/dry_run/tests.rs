@@ -0,0 +1,37 @@
+use super::dry_run_lines;
+use crate::{
+    i18n::Locale,
+    inventory::{InventoryOptions, collect_project_inventory},
+    test_support::{TestDir, must},
+};
+#[test]
+fn lists_content_files_with_size_and_token_estimate() {
+    let dir = must(TestDir::new("dry-run-list"), "创建测试目录失败");
+    must(dir.write_str("a.rs", "12345678"), "写入 a.rs 失败");
+    must(dir.write_str("big.rs", &"x".repeat(64)), "写入 big.rs 失败");
+    let options = InventoryOptions {
+        max_file_size: Some(32),
+        ..InventoryOptions::default()
+    };
+    let inventory = must(
+        collect_project_inventory(dir.path(), &options),
+        "收集项目清单失败",
+    );
+    let lines = dry_run_lines(&inventory, Locale::En);
+    assert_eq!(lines.len(), 3_usize);
+    assert!(
+        lines
+            .iter()
+            .any(|line| line.starts_with("a.rs (8 B, ~2 tokens"))
+    );
+    assert!(
+        lines
+            .iter()
+            .any(|line| line.starts_with("big.rs (64 B, ~0 tokens")),
+        "{lines:?}"
+    );
+    assert_eq!(
+        lines.last().map(String::as_str),
+        Some("2 files, 72 B, ~2 tokens")
+    );
+}
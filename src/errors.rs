@@ -1 +1,127 @@
-pub(crate) type AppResult<T> = Result<T, Box<dyn core::error::Error + Send + Sync + 'static>>;
+use crate::{
+    i18n::{Message, tr},
+    json::escape_json,
+};
+use core::{cell::Cell, fmt};
+use std::io;
+pub type AppResult<T> = Result<T, AppError>;
+thread_local! {
+    static ERROR_FORMAT: Cell<u8> = const { Cell::new(0) };
+}
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum FailureKind {
+    Cli,
+    Path,
+    Walk,
+    Read,
+    Output,
+    Clipboard,
+    Git,
+}
+impl FailureKind {
+    #[inline]
+    #[must_use]
+    pub const fn exit_code(self) -> u8 {
+        match self {
+            Self::Cli => 2,
+            Self::Path => 3,
+            Self::Walk => 4,
+            Self::Read => 5,
+            Self::Output => 6,
+            Self::Clipboard => 7,
+            Self::Git => 8,
+        }
+    }
+    #[inline]
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Cli => "cli",
+            Self::Path => "path",
+            Self::Walk => "walk",
+            Self::Read => "read",
+            Self::Output => "output",
+            Self::Clipboard => "clipboard",
+            Self::Git => "git",
+        }
+    }
+    pub(crate) const fn wrap(self, source: io::Error) -> AppError {
+        AppError { kind: self, source }
+    }
+}
+#[derive(Debug)]
+pub struct AppError {
+    kind: FailureKind,
+    source: io::Error,
+}
+impl AppError {
+    #[inline]
+    #[must_use]
+    pub const fn kind(&self) -> FailureKind {
+        self.kind
+    }
+    #[inline]
+    #[must_use]
+    pub const fn exit_code(&self) -> u8 {
+        self.kind.exit_code()
+    }
+    #[inline]
+    #[must_use]
+    pub const fn io_error(&self) -> &io::Error {
+        &self.source
+    }
+}
+impl fmt::Display for AppError {
+    #[inline]
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.source, formatter)
+    }
+}
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub(crate) enum ErrorFormat {
+    #[default]
+    Text,
+    Json,
+}
+impl ErrorFormat {
+    pub(crate) fn parse(name: &str) -> Option<Self> {
+        match name {
+            "text" => Some(Self::Text),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+    const fn code(self) -> u8 {
+        match self {
+            Self::Text => 0,
+            Self::Json => 1,
+        }
+    }
+}
+pub(crate) fn set_error_format(format: ErrorFormat) {
+    ERROR_FORMAT.set(format.code());
+}
+#[inline]
+#[must_use]
+pub fn error_report(err: &AppError) -> String {
+    let format = if ERROR_FORMAT.get() == ErrorFormat::Json.code() {
+        ErrorFormat::Json
+    } else {
+        ErrorFormat::Text
+    };
+    format_report(err, format)
+}
+fn format_report(err: &AppError, format: ErrorFormat) -> String {
+    match format {
+        ErrorFormat::Text => tr(Message::ErrorPrefix, &[err]),
+        ErrorFormat::Json => format!(
+            "{{\"error\": {{\"kind\": \"{}\", \"exit_code\": {}, \"message\": \"{}\"}}}}",
+            err.kind.name(),
+            err.exit_code(),
+            escape_json(&err.to_string())
+        ),
+    }
+}
+#[cfg(test)]
+mod tests;
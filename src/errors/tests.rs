@@ -0,0 +1,51 @@
+use super::{ErrorFormat, FailureKind, format_report};
+use crate::{
+    app::run,
+    i18n::{Message, tr},
+    test_support::{TestDir, must, must_err},
+};
+use std::{ffi::OsString, io};
+#[test]
+fn failure_kinds_have_distinct_exit_codes() {
+    let kinds = [
+        FailureKind::Cli,
+        FailureKind::Path,
+        FailureKind::Walk,
+        FailureKind::Read,
+        FailureKind::Output,
+        FailureKind::Clipboard,
+        FailureKind::Git,
+    ];
+    let codes: Vec<u8> = kinds.iter().map(|kind| kind.exit_code()).collect();
+    assert_eq!(codes, [2, 3, 4, 5, 6, 7, 8]);
+}
+#[test]
+fn run_classifies_failures_by_phase() {
+    let dir = must(TestDir::new("errors-phase"), "创建测试目录失败");
+    let unknown = must_err(
+        run([OsString::from("proj2md"), OsString::from("--bogus")]),
+        "未知选项必须报错",
+    );
+    assert_eq!(unknown.kind(), FailureKind::Cli);
+    let missing = must_err(
+        run([
+            OsString::from("proj2md"),
+            dir.path().join("missing").into_os_string(),
+        ]),
+        "缺失根路径必须报错",
+    );
+    assert_eq!(missing.kind(), FailureKind::Path);
+    assert_eq!(missing.exit_code(), 3);
+}
+#[test]
+fn reports_follow_the_error_format() {
+    let err = FailureKind::Read.wrap(io::Error::other("bad \"path\""));
+    assert_eq!(
+        format_report(&err, ErrorFormat::Json),
+        "{\"error\": {\"kind\": \"read\", \"exit_code\": 5, \"message\": \"bad \\\"path\\\"\"}}"
+    );
+    assert_eq!(
+        format_report(&err, ErrorFormat::Text),
+        tr(Message::ErrorPrefix, &[&"bad \"path\""])
+    );
+}
@@ -0,0 +1,327 @@
+use crate::{
+    attributes::{Attributes, GeneratedFiles},
+    changes::{changed_files, tracked_files},
+    content::read_file_content,
+    errors::{AppResult, FailureKind},
+    file_list::read_file_list,
+    gitmodules::{SubmoduleMode, load_submodules},
+    history::file_activity,
+    i18n::{Locale, Message, tr},
+    ignore_rules::IgnoreRules,
+    inventory::{
+        InventoryOptions, Omission, build_exclusions, build_selection, is_previous_output,
+        output_relative_path,
+    },
+    logging::{Decision, LogEvent, LogFormat, Rule, format_event},
+    modified::describe_time,
+    paths,
+    tree::format_size,
+};
+use ignore::{
+    Match,
+    gitignore::{Gitignore, Glob},
+};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct Explanation {
+    pub(crate) path: String,
+    pub(crate) decision: Decision,
+    pub(crate) details: Vec<String>,
+}
+pub(crate) fn explain_path(
+    root_path: &Path,
+    target: &Path,
+    options: &InventoryOptions,
+) -> AppResult<Explanation> {
+    let (root, absolute, relative) =
+        resolve_target(root_path, target).map_err(|err| FailureKind::Path.wrap(err))?;
+    let path = paths::relative_path_text(&relative).map_err(|err| FailureKind::Path.wrap(err))?;
+    let mut details = Vec::new();
+    let exclusions = build_exclusions(root_path, &options.exclusions)
+        .map_err(|err| FailureKind::Cli.wrap(err))?;
+    let walked = match options.files_from.as_deref() {
+        Some(source) => explain_listed(source, root_path, &relative, &mut details)
+            .map_err(|err| FailureKind::Read.wrap(err))?,
+        None => explain_walk(&root, &absolute, &relative, &mut details)
+            .map_err(|err| FailureKind::Walk.wrap(err))?,
+    };
+    if let Some(decision) =
+        walked.or_else(|| explain_exclusion(&exclusions, &absolute, &relative, &mut details))
+    {
+        return Ok(Explanation {
+            path,
+            decision,
+            details,
+        });
+    }
+    if options.files_from.is_none()
+        && let Some(decision) =
+            explain_submodule(root_path, &relative, options.submodules, &mut details)
+                .map_err(|err| FailureKind::Walk.wrap(err))?
+    {
+        return Ok(Explanation {
+            path,
+            decision,
+            details,
+        });
+    }
+    let output_path = output_relative_path(root_path, options.output_path.as_deref());
+    if absolute.is_file()
+        && is_previous_output(&absolute, &relative, output_path.as_deref())
+            .map_err(|err| FailureKind::Read.wrap(err))?
+    {
+        details.push(tr(Message::ExplainGenerated, &[]));
+        let decision = Decision::Excluded(Rule::Generated);
+        return Ok(Explanation {
+            path,
+            decision,
+            details,
+        });
+    }
+    let decision = if absolute.is_dir() {
+        Decision::Included
+    } else {
+        explain_file(root_path, &absolute, &relative, options, &mut details)?
+    };
+    Ok(Explanation {
+        path,
+        decision,
+        details,
+    })
+}
+pub(crate) fn explanation_lines(explanation: &Explanation, locale: Locale) -> Vec<String> {
+    let verdict = LogEvent::Path {
+        path: &explanation.path,
+        decision: explanation.decision,
+    };
+    let mut lines = vec![format_event(verdict, LogFormat::Text, locale)];
+    lines.extend(
+        explanation
+            .details
+            .iter()
+            .map(|detail| format!("    {detail}")),
+    );
+    lines
+}
+fn resolve_target(root_path: &Path, target: &Path) -> io::Result<(PathBuf, PathBuf, PathBuf)> {
+    let joined = root_path.join(target);
+    if !joined.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            tr(Message::PathNotFound, &[&target.display()]),
+        ));
+    }
+    let root = fs::canonicalize(root_path)?;
+    let absolute = fs::canonicalize(&joined)?;
+    let relative = absolute
+        .strip_prefix(&root)
+        .ok()
+        .map(Path::to_path_buf)
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                tr(Message::ExplainOutsideRoot, &[&target.display()]),
+            )
+        })?;
+    Ok((root, absolute, relative))
+}
+fn explain_walk(
+    root: &Path,
+    absolute: &Path,
+    relative: &Path,
+    details: &mut Vec<String>,
+) -> io::Result<Option<Decision>> {
+    let rules = IgnoreRules::load(absolute.parent().unwrap_or(root))?;
+    let mut candidate = root.to_path_buf();
+    for component in relative.components() {
+        candidate.push(component);
+        let is_dir = candidate != absolute || absolute.is_dir();
+        match rules.matched(&candidate, is_dir) {
+            Match::Ignore(glob) => {
+                let line = rules.matched_line(&candidate, is_dir)?;
+                details.push(describe_rule(Message::ExplainMatchedRule, root, glob, line));
+                return Ok(Some(Decision::Excluded(Rule::Gitignore)));
+            }
+            Match::Whitelist(glob) => {
+                let line = rules.matched_line(&candidate, is_dir)?;
+                details.push(describe_rule(Message::ExplainReincluded, root, glob, line));
+            }
+            Match::None => {
+                let name = component.as_os_str().to_string_lossy();
+                if name.starts_with('.') {
+                    details.push(tr(Message::ExplainHidden, &[&name]));
+                    return Ok(Some(Decision::Excluded(Rule::Hidden)));
+                }
+            }
+        }
+    }
+    Ok(None)
+}
+fn explain_listed(
+    source: &Path,
+    root_path: &Path,
+    relative: &Path,
+    details: &mut Vec<String>,
+) -> io::Result<Option<Decision>> {
+    let listed = read_file_list(source, root_path)?;
+    if listed.iter().any(|path| path.starts_with(relative)) {
+        return Ok(None);
+    }
+    details.push(tr(Message::ExplainUnlisted, &[&source.display()]));
+    Ok(Some(Decision::Excluded(Rule::Unlisted)))
+}
+fn explain_exclusion(
+    exclusions: &Gitignore,
+    absolute: &Path,
+    relative: &Path,
+    details: &mut Vec<String>,
+) -> Option<Decision> {
+    match exclusions.matched_path_or_any_parents(relative, absolute.is_dir()) {
+        Match::Ignore(glob) => {
+            details.push(tr(Message::ExplainBuiltin, &[&glob.original()]));
+            Some(Decision::Excluded(Rule::Builtin))
+        }
+        Match::None | Match::Whitelist(_) => None,
+    }
+}
+fn explain_submodule(
+    root_path: &Path,
+    relative: &Path,
+    mode: SubmoduleMode,
+    details: &mut Vec<String>,
+) -> io::Result<Option<Decision>> {
+    let submodules = load_submodules(root_path)?;
+    let Some(submodule_path) = submodules.keys().find(|path| match mode {
+        SubmoduleMode::Mark => relative.starts_with(path) && relative != path.as_path(),
+        SubmoduleMode::Skip => relative.starts_with(path),
+        SubmoduleMode::Recurse => false,
+    }) else {
+        return Ok(None);
+    };
+    details.push(tr(Message::ExplainSubmodule, &[&submodule_path.display()]));
+    Ok(Some(Decision::Excluded(Rule::Submodule)))
+}
+fn explain_file(
+    root_path: &Path,
+    absolute: &Path,
+    relative: &Path,
+    options: &InventoryOptions,
+    details: &mut Vec<String>,
+) -> AppResult<Decision> {
+    let relative_text =
+        paths::relative_path_text(relative).map_err(|err| FailureKind::Path.wrap(err))?;
+    if let Some(tracking) = options.tracking {
+        let tracked =
+            tracked_files(root_path, tracking).map_err(|err| FailureKind::Git.wrap(err))?;
+        if !tracked.tracked.contains(&relative_text) {
+            if !tracked.untracked.contains(&relative_text) {
+                details.push(tr(Message::ExplainUntracked, &[]));
+                return Ok(Decision::Excluded(Rule::Untracked));
+            }
+            details.push(tr(Message::UntrackedMarker, &[]));
+        }
+    }
+    let selection = build_selection(root_path, &options.content_selection)
+        .map_err(|err| FailureKind::Cli.wrap(err))?;
+    if selection.is_some_and(|matcher| !matcher.matched(relative, false).is_whitelist()) {
+        details.push(tr(Message::ExplainNotSelected, &[]));
+        return Ok(Decision::TreeOnly(Rule::Select));
+    }
+    if let Some(scope) = options.changes.as_ref() {
+        let changes = changed_files(root_path, scope).map_err(|err| FailureKind::Git.wrap(err))?;
+        if !changes.changed.contains(&relative_text) {
+            details.push(tr(Message::ExplainUnchanged, &[&scope.describe()]));
+            return Ok(Decision::TreeOnly(Rule::Unchanged));
+        }
+    }
+    if let Some(window) = options.touched {
+        let activity = file_activity(root_path, options.revision.as_deref(), Some(window))
+            .map_err(|err| FailureKind::Git.wrap(err))?;
+        if !activity.contains_key(&relative_text) {
+            details.push(tr(Message::ExplainUntouched, &[&window.describe()]));
+            return Ok(Decision::TreeOnly(Rule::Untouched));
+        }
+    }
+    if let Some(window) = options.modified {
+        let cutoff = window.cutoff(SystemTime::now());
+        let modified = fs::metadata(absolute)
+            .and_then(|metadata| metadata.modified())
+            .ok();
+        if let Some(mtime) = modified.filter(|&mtime| mtime < cutoff) {
+            details.push(tr(
+                Message::ExplainStale,
+                &[&describe_time(mtime), &describe_time(cutoff)],
+            ));
+            return Ok(Decision::TreeOnly(Rule::Stale));
+        }
+    }
+    let mut attributes = Attributes::default();
+    attributes
+        .load_ancestors(root_path, relative)
+        .map_err(|err| FailureKind::Read.wrap(err))?;
+    let file_attributes = attributes.lookup(root_path, relative);
+    if options.generated_files == GeneratedFiles::Omit
+        && let Some(label) = file_attributes.linguist_label()
+    {
+        details.push(tr(Message::ExplainLinguist, &[&label]));
+        return Ok(Decision::TreeOnly(Rule::Linguist));
+    }
+    let size = fs::metadata(absolute)
+        .map_err(|err| {
+            FailureKind::Walk.wrap(io::Error::other(tr(
+                Message::MetadataFailed,
+                &[&absolute.display(), &err],
+            )))
+        })?
+        .len();
+    if let Some(limit) = options.max_file_size.filter(|&limit| size > limit) {
+        details.push(tr(
+            Message::ExplainTooLarge,
+            &[&format_size(size), &format_size(limit)],
+        ));
+        return Ok(Decision::TreeOnly(Rule::Size));
+    }
+    if file_attributes.binary {
+        details.push(tr(Message::ExplainAttributeBinary, &[]));
+        return Ok(Decision::TreeOnly(Rule::Binary));
+    }
+    let content = read_file_content(
+        absolute,
+        file_attributes.encoding.as_deref(),
+        Locale::default(),
+    )?;
+    Ok(match (content.omission, content.encoding) {
+        (Some(Omission::Binary), _) => {
+            details.push(tr(Message::ExplainBinary, &[]));
+            Decision::TreeOnly(Rule::Binary)
+        }
+        (Some(Omission::TooLarge | Omission::Undecodable | Omission::Excluded), _)
+        | (None, None) => {
+            details.push(tr(Message::ExplainUndecodable, &[]));
+            Decision::TreeOnly(Rule::Undecodable)
+        }
+        (None, Some(encoding)) => {
+            details.push(tr(Message::ExplainDecoded, &[&encoding]));
+            Decision::Included
+        }
+    })
+}
+fn describe_rule(message: Message, root: &Path, glob: &Glob, line: Option<usize>) -> String {
+    let file = glob.from().map_or_else(String::new, |path| {
+        path.strip_prefix(root)
+            .unwrap_or(path)
+            .display()
+            .to_string()
+    });
+    let location = line.map_or_else(
+        || file.clone(),
+        |number| tr(Message::ExplainRuleLine, &[&file, &number]),
+    );
+    tr(message, &[&location, &glob.original()])
+}
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,140 @@
+use super::explain_path;
+use crate::{
+    i18n::{Message, tr},
+    inventory::InventoryOptions,
+    logging::{Decision, Rule},
+    test_support::{TestDir, must, must_err},
+};
+use std::path::Path;
+fn explain(dir: &TestDir, target: &str, options: &InventoryOptions) -> (Decision, Vec<String>) {
+    let explanation = must(
+        explain_path(dir.path(), Path::new(target), options),
+        "解释路径失败",
+    );
+    (explanation.decision, explanation.details)
+}
+#[test]
+fn gitignore_rule_is_reported_with_its_source() {
+    let dir = must(TestDir::new("explain-gitignore"), "创建测试目录失败");
+    must(
+        dir.write_str(".gitignore", "*.log\ntarget/\n"),
+        "写入 .gitignore 失败",
+    );
+    must(dir.write_str("target/out.rs", "x"), "写入 out.rs 失败");
+    let (decision, details) = explain(&dir, "target/out.rs", &InventoryOptions::default());
+    assert_eq!(decision, Decision::Excluded(Rule::Gitignore));
+    assert!(
+        details
+            .iter()
+            .any(|detail| detail.starts_with(".gitignore") && detail.ends_with("target/")),
+        "{details:?}"
+    );
+    assert!(details.iter().any(|detail| detail.contains('2')));
+}
+#[test]
+fn matched_rule_line_comes_from_the_matcher() {
+    let dir = must(TestDir::new("explain-rule-line"), "创建测试目录失败");
+    must(
+        dir.write_str(".gitignore", "*.log\ntarget/\n*.log\n"),
+        "写入 .gitignore 失败",
+    );
+    must(dir.write_str(".ignore", "!keep.log\n"), "写入 .ignore 失败");
+    must(dir.write_str("debug.log", "x"), "写入 debug.log 失败");
+    must(dir.write_str("keep.log", "x"), "写入 keep.log 失败");
+    let (decision, details) = explain(&dir, "debug.log", &InventoryOptions::default());
+    assert_eq!(decision, Decision::Excluded(Rule::Gitignore));
+    let location = tr(Message::ExplainRuleLine, &[&".gitignore", &3_usize]);
+    assert_eq!(
+        details,
+        [tr(Message::ExplainMatchedRule, &[&location, &"*.log"])]
+    );
+    let (_, kept) = explain(&dir, "keep.log", &InventoryOptions::default());
+    let reincluded = tr(Message::ExplainRuleLine, &[&".ignore", &1_usize]);
+    assert_eq!(
+        kept.first(),
+        Some(&tr(
+            Message::ExplainReincluded,
+            &[&reincluded, &"!keep.log"]
+        ))
+    );
+}
+#[test]
+fn hidden_and_builtin_paths_are_excluded() {
+    let dir = must(TestDir::new("explain-hidden"), "创建测试目录失败");
+    must(dir.write_str(".env", "KEY=1"), "写入 .env 失败");
+    must(dir.write_str("LICENSE", "MIT"), "写入 LICENSE 失败");
+    let options = InventoryOptions::default();
+    assert_eq!(
+        explain(&dir, ".env", &options).0,
+        Decision::Excluded(Rule::Hidden)
+    );
+    assert_eq!(
+        explain(&dir, "LICENSE", &options).0,
+        Decision::Excluded(Rule::Builtin)
+    );
+}
+#[test]
+fn selection_and_size_limit_keep_files_tree_only() {
+    let dir = must(TestDir::new("explain-tree-only"), "创建测试目录失败");
+    must(
+        dir.write_str("src/main.rs", "fn main() {}\n"),
+        "写入 main.rs 失败",
+    );
+    must(
+        dir.write_str("docs/guide.txt", "guide"),
+        "写入 guide.txt 失败",
+    );
+    let selected = InventoryOptions {
+        content_selection: vec!["src/**".to_owned()],
+        ..InventoryOptions::default()
+    };
+    assert_eq!(
+        explain(&dir, "docs/guide.txt", &selected).0,
+        Decision::TreeOnly(Rule::Select)
+    );
+    assert_eq!(
+        explain(&dir, "src/main.rs", &selected).0,
+        Decision::Included
+    );
+    let limited = InventoryOptions {
+        max_file_size: Some(4),
+        ..InventoryOptions::default()
+    };
+    assert_eq!(
+        explain(&dir, "src/main.rs", &limited).0,
+        Decision::TreeOnly(Rule::Size)
+    );
+}
+#[test]
+fn included_file_reports_its_encoding() {
+    let dir = must(TestDir::new("explain-included"), "创建测试目录失败");
+    must(
+        dir.write_str("lib.rs", "pub fn f() {}\n"),
+        "写入 lib.rs 失败",
+    );
+    must(
+        dir.write_bytes("blob.rs", &[0, 1, 2, 0]),
+        "写入 blob.rs 失败",
+    );
+    let options = InventoryOptions::default();
+    let (decision, details) = explain(&dir, "lib.rs", &options);
+    assert_eq!(decision, Decision::Included);
+    assert!(details.iter().any(|detail| detail.contains("UTF-8")));
+    assert_eq!(
+        explain(&dir, "blob.rs", &options).0,
+        Decision::TreeOnly(Rule::Binary)
+    );
+}
+#[test]
+fn missing_target_is_a_path_error() {
+    let dir = must(TestDir::new("explain-missing"), "创建测试目录失败");
+    let err = must_err(
+        explain_path(
+            dir.path(),
+            Path::new("nope.rs"),
+            &InventoryOptions::default(),
+        ),
+        "不存在的路径应当报错",
+    );
+    assert_eq!(err.exit_code(), 3);
+}
@@ -0,0 +1,75 @@
+use crate::i18n::{Message, tr};
+use alloc::collections::BTreeSet;
+use std::{
+    fs,
+    io::{self, Read as _},
+    path::{Component, Path, PathBuf},
+};
+const STDIN_SOURCE: &str = "-";
+pub(crate) fn read_file_list(source: &Path, root_path: &Path) -> io::Result<BTreeSet<PathBuf>> {
+    let read_failed = |err: io::Error| {
+        io::Error::new(
+            err.kind(),
+            tr(Message::ReadFileFailed, &[&source.display()]),
+        )
+    };
+    let bytes = if source == Path::new(STDIN_SOURCE) {
+        let mut buffer = Vec::new();
+        io::stdin()
+            .lock()
+            .read_to_end(&mut buffer)
+            .map_err(read_failed)?;
+        buffer
+    } else {
+        fs::read(source).map_err(read_failed)?
+    };
+    parse_file_list(root_path, &bytes)
+}
+pub(crate) fn parse_file_list(root_path: &Path, bytes: &[u8]) -> io::Result<BTreeSet<PathBuf>> {
+    let separator = if bytes.contains(&0) { b'\0' } else { b'\n' };
+    bytes
+        .split(|&byte| byte == separator)
+        .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+        .filter(|line| !line.is_empty())
+        .map(|line| listed_path(root_path, line))
+        .collect()
+}
+fn listed_path(root_path: &Path, line: &[u8]) -> io::Result<PathBuf> {
+    let invalid = || {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            tr(
+                Message::FileListPathInvalid,
+                &[&String::from_utf8_lossy(line)],
+            ),
+        )
+    };
+    let text = str::from_utf8(line).map_err(|_err| invalid())?;
+    let listed = Path::new(text);
+    let relative = if listed.is_absolute() {
+        let root = fs::canonicalize(root_path)?;
+        listed
+            .strip_prefix(&root)
+            .or_else(|_| listed.strip_prefix(root_path))
+            .map_err(|_err| invalid())?
+            .to_path_buf()
+    } else {
+        listed.to_path_buf()
+    };
+    let mut normalized = PathBuf::new();
+    for component in relative.components() {
+        match component {
+            Component::Normal(name) => normalized.push(name),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => {
+                return Err(invalid());
+            }
+        }
+    }
+    if normalized.as_os_str().is_empty() {
+        return Err(invalid());
+    }
+    Ok(normalized)
+}
+#[cfg(test)]
+mod tests;
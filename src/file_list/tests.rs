@@ -0,0 +1,37 @@
+use super::parse_file_list;
+use crate::test_support::{TestDir, must, must_err};
+use std::path::PathBuf;
+#[test]
+fn newline_and_nul_separated_lists_are_normalized() {
+    let dir = must(TestDir::new("file-list-parse"), "创建测试目录失败");
+    let lines = must(
+        parse_file_list(dir.path(), b"src/main.rs\r\n./README.md\n\nsrc/main.rs\n"),
+        "解析换行分隔的列表失败",
+    );
+    assert_eq!(
+        lines.into_iter().collect::<Vec<_>>(),
+        [PathBuf::from("README.md"), PathBuf::from("src/main.rs")]
+    );
+    let absolute = dir.path().join("lib.rs");
+    let mut nul_separated = b"notes\nfile.txt\0".to_vec();
+    nul_separated.extend_from_slice(absolute.to_string_lossy().as_bytes());
+    let records = must(
+        parse_file_list(dir.path(), &nul_separated),
+        "解析 NUL 分隔的列表失败",
+    );
+    assert_eq!(
+        records.into_iter().collect::<Vec<_>>(),
+        [PathBuf::from("lib.rs"), PathBuf::from("notes\nfile.txt")]
+    );
+}
+#[test]
+fn paths_outside_the_root_are_rejected() {
+    let dir = must(TestDir::new("file-list-outside"), "创建测试目录失败");
+    for line in ["../secret.txt", "/etc/passwd", "."] {
+        let err = must_err(
+            parse_file_list(dir.path(), line.as_bytes()),
+            "根目录之外的路径应当报错",
+        );
+        assert!(err.to_string().contains(line), "{err}");
+    }
+}
@@ -0,0 +1,237 @@
+use crate::{
+    errors::{AppResult, FailureKind},
+    git::{git_stdout, is_work_tree},
+    i18n::{Message, tr},
+    inventory::ProjectInventory,
+    json::escape_json,
+};
+use sha2::{Digest as _, Sha256};
+use std::{io, path::Path, time::SystemTime};
+pub(crate) const GENERATOR: &str = "proj2md";
+pub(crate) const SECONDS_PER_DAY: u64 = 86_400;
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct BundleMetadata {
+    pub version: String,
+    pub generated_at: String,
+    pub root_name: String,
+    pub options: Vec<ResolvedOption>,
+    pub file_count: usize,
+    pub total_bytes: u64,
+    pub content_hash: String,
+    pub git: Option<GitState>,
+}
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ResolvedOption {
+    pub name: String,
+    pub values: Vec<String>,
+    pub repeated: bool,
+}
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct GitState {
+    pub branch: String,
+    pub commit: String,
+    pub dirty: bool,
+}
+impl ResolvedOption {
+    pub(crate) fn single(name: &str, value: impl Into<String>) -> Self {
+        Self {
+            name: name.to_owned(),
+            values: vec![value.into()],
+            repeated: false,
+        }
+    }
+    pub(crate) fn list(name: &str, values: &[String]) -> Self {
+        Self {
+            name: name.to_owned(),
+            values: values.to_vec(),
+            repeated: true,
+        }
+    }
+}
+pub(crate) struct ContentDigest {
+    hasher: Sha256,
+}
+impl ContentDigest {
+    pub(crate) fn new() -> Self {
+        Self {
+            hasher: Sha256::new(),
+        }
+    }
+    pub(crate) fn add(&mut self, relative_path: &str, text: &str) {
+        self.hasher.update(relative_path.as_bytes());
+        self.hasher.update(b"\0");
+        self.hasher.update(text.as_bytes());
+        self.hasher.update(b"\0");
+    }
+    pub(crate) fn finish(self) -> String {
+        let mut digest = String::from("sha256:");
+        for byte in self.hasher.finalize() {
+            for nibble in [byte.wrapping_shr(4), byte & 0xf] {
+                digest.push(char::from_digit(u32::from(nibble), 16).unwrap_or('0'));
+            }
+        }
+        digest
+    }
+}
+pub(crate) fn bundle_metadata(
+    inventory: &ProjectInventory,
+    content_hash: String,
+    options: &[ResolvedOption],
+) -> AppResult<BundleMetadata> {
+    let seconds = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_err(|err| {
+            FailureKind::Output.wrap(io::Error::other(tr(Message::ClockBeforeEpoch, &[&err])))
+        })?
+        .as_secs();
+    Ok(BundleMetadata {
+        version: env!("CARGO_PKG_VERSION").to_owned(),
+        generated_at: format_timestamp(seconds),
+        root_name: inventory.root_name.clone(),
+        options: options.to_vec(),
+        file_count: inventory.content_files.len(),
+        total_bytes: inventory
+            .content_files
+            .iter()
+            .map(|file| file.size)
+            .fold(0, u64::saturating_add),
+        content_hash,
+        git: git_state(&inventory.root_path).map_err(|err| FailureKind::Git.wrap(err))?,
+    })
+}
+fn git_state(root_path: &Path) -> io::Result<Option<GitState>> {
+    if !is_work_tree(root_path)? {
+        return Ok(None);
+    }
+    let status = git_stdout(root_path, &["status", "--porcelain=v2", "--branch"])?;
+    Ok(parse_git_status(&status))
+}
+pub(crate) fn parse_git_status(status: &str) -> Option<GitState> {
+    let mut commit = None;
+    let mut branch = "";
+    let mut dirty = false;
+    for line in status.lines() {
+        if let Some(oid) = line.strip_prefix("# branch.oid ") {
+            commit = (oid != "(initial)").then_some(oid);
+        } else if let Some(head) = line.strip_prefix("# branch.head ") {
+            branch = if head == "(detached)" { "HEAD" } else { head };
+        } else if !line.starts_with("# ") {
+            dirty = true;
+        }
+    }
+    commit.map(|found| GitState {
+        branch: branch.to_owned(),
+        commit: found.to_owned(),
+        dirty,
+    })
+}
+pub(crate) fn format_timestamp(seconds: u64) -> String {
+    let days = seconds.checked_div(SECONDS_PER_DAY).unwrap_or(0);
+    let of_day = seconds.checked_rem(SECONDS_PER_DAY).unwrap_or(0);
+    let (year, month, day) = civil_from_days(days);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        of_day.checked_div(3600).unwrap_or(0),
+        of_day
+            .checked_rem(3600)
+            .unwrap_or(0)
+            .checked_div(60)
+            .unwrap_or(0),
+        of_day.checked_rem(60).unwrap_or(0)
+    )
+}
+pub(crate) fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let shifted = days.saturating_add(719_468);
+    let era = shifted.checked_div(146_097).unwrap_or(0);
+    let day_of_era = shifted.saturating_sub(era.saturating_mul(146_097));
+    let year_of_era = day_of_era
+        .saturating_sub(day_of_era.checked_div(1460).unwrap_or(0))
+        .saturating_add(day_of_era.checked_div(36_524).unwrap_or(0))
+        .saturating_sub(day_of_era.checked_div(146_096).unwrap_or(0))
+        .checked_div(365)
+        .unwrap_or(0);
+    let day_of_year = day_of_era.saturating_sub(
+        year_of_era
+            .saturating_mul(365)
+            .saturating_add(year_of_era.checked_div(4).unwrap_or(0))
+            .saturating_sub(year_of_era.checked_div(100).unwrap_or(0)),
+    );
+    let month_index = day_of_year
+        .saturating_mul(5)
+        .saturating_add(2)
+        .checked_div(153)
+        .unwrap_or(0);
+    let day = day_of_year
+        .saturating_sub(
+            month_index
+                .saturating_mul(153)
+                .saturating_add(2)
+                .checked_div(5)
+                .unwrap_or(0),
+        )
+        .saturating_add(1);
+    let month = if month_index < 10 {
+        month_index.saturating_add(3)
+    } else {
+        month_index.saturating_sub(9)
+    };
+    let year = year_of_era
+        .saturating_add(era.saturating_mul(400))
+        .saturating_add(u64::from(month <= 2));
+    (year, month, day)
+}
+pub(crate) fn has_generator_signature(head: &str) -> bool {
+    let mut lines = head.lines();
+    let first_line = lines.next();
+    let yaml_marker = format!("generator: {GENERATOR}");
+    let in_yaml = first_line == Some("---")
+        && lines
+            .take_while(|line| *line != "---")
+            .any(|line| line == yaml_marker);
+    in_yaml
+        || first_line == Some(format!("<!-- generator: {GENERATOR} -->").as_str())
+        || head.starts_with(&format!("{{\n  \"generator\": \"{GENERATOR}\""))
+        || head.contains(&format!("<project generator=\"{GENERATOR}\""))
+        || head.contains(&format!("<metadata generator=\"{GENERATOR}\""))
+        || head.contains(&format!("\"metadata\": {{\"generator\": \"{GENERATOR}\""))
+}
+pub(crate) fn front_matter_yaml(metadata: &BundleMetadata) -> String {
+    let mut lines = vec![
+        "---".to_owned(),
+        format!("generator: {GENERATOR}"),
+        format!("version: {}", yaml_string(&metadata.version)),
+        format!("generated_at: {}", metadata.generated_at),
+        format!("root: {}", yaml_string(&metadata.root_name)),
+        "options:".to_owned(),
+    ];
+    lines.extend(metadata.options.iter().map(|option| {
+        let value = if option.repeated {
+            let items: Vec<String> = option.values.iter().map(|item| yaml_string(item)).collect();
+            format!("[{}]", items.join(", "))
+        } else {
+            option
+                .values
+                .first()
+                .map_or_else(|| "null".to_owned(), |value| yaml_string(value))
+        };
+        format!("  {}: {value}", option.name)
+    }));
+    lines.push(format!("files: {}", metadata.file_count));
+    lines.push(format!("total_bytes: {}", metadata.total_bytes));
+    lines.push(format!("content_hash: {}", metadata.content_hash));
+    if let Some(git) = metadata.git.as_ref() {
+        lines.push("git:".to_owned());
+        lines.push(format!("  branch: {}", yaml_string(&git.branch)));
+        lines.push(format!("  commit: {}", git.commit));
+        lines.push(format!("  dirty: {}", git.dirty));
+    }
+    lines.push("---".to_owned());
+    lines.push(String::new());
+    lines.push(String::new());
+    lines.join("\n")
+}
+fn yaml_string(value: &str) -> String {
+    format!("\"{}\"", escape_json(value))
+}
+#[cfg(test)]
+mod tests;
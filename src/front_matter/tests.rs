@@ -0,0 +1,93 @@
+use super::{
+    BundleMetadata, ContentDigest, GitState, ResolvedOption, format_timestamp, front_matter_yaml,
+    has_generator_signature, parse_git_status,
+};
+#[test]
+fn timestamps_are_formatted_as_utc() {
+    assert_eq!(format_timestamp(0), "1970-01-01T00:00:00Z");
+    assert_eq!(format_timestamp(951_782_400), "2000-02-29T00:00:00Z");
+    assert_eq!(format_timestamp(1_792_329_296), "2026-10-18T13:14:56Z");
+}
+#[test]
+fn front_matter_lists_provenance_fields() {
+    let metadata = BundleMetadata {
+        version: "1.2.3".to_owned(),
+        generated_at: "2026-10-18T13:14:56Z".to_owned(),
+        root_name: "demo \"app\"".to_owned(),
+        options: vec![
+            ResolvedOption::single("format", "markdown"),
+            ResolvedOption::list("select", &["src/**".to_owned()]),
+        ],
+        file_count: 3,
+        total_bytes: 120,
+        content_hash: "sha256:abc".to_owned(),
+        git: Some(GitState {
+            branch: "main".to_owned(),
+            commit: "0123abcd".to_owned(),
+            dirty: true,
+        }),
+    };
+    assert_eq!(
+        front_matter_yaml(&metadata),
+        "---\ngenerator: proj2md\nversion: \"1.2.3\"\ngenerated_at: 2026-10-18T13:14:56Z\nroot: \"demo \\\"app\\\"\"\noptions:\n  format: \"markdown\"\n  select: [\"src/**\"]\nfiles: 3\ntotal_bytes: 120\ncontent_hash: sha256:abc\ngit:\n  branch: \"main\"\n  commit: 0123abcd\n  dirty: true\n---\n\n"
+    );
+}
+#[test]
+fn generator_signature_is_recognized_in_every_format() {
+    assert!(has_generator_signature(
+        "---\ngenerator: proj2md\nversion: \"1\"\n---\n"
+    ));
+    assert!(has_generator_signature(
+        "<?xml version=\"1.0\"?>\n<project root=\"x\">\n  <metadata generator=\"proj2md\" version=\"1\">"
+    ));
+    assert!(has_generator_signature(
+        "{\n  \"root\": \"x\",\n  \"metadata\": {\"generator\": \"proj2md\", \"version\": \"1\"}"
+    ));
+    assert!(has_generator_signature(
+        "<!-- generator: proj2md -->\n\n## 1. 目录结构\n"
+    ));
+    assert!(has_generator_signature(
+        "{\n  \"generator\": \"proj2md\",\n  \"root\": \"x\""
+    ));
+    assert!(has_generator_signature(
+        "<?xml version=\"1.0\"?>\n<project generator=\"proj2md\" root=\"x\">"
+    ));
+    assert!(!has_generator_signature(
+        "# project.md\n\ngenerator: proj2md\n"
+    ));
+    assert!(!has_generator_signature(
+        "---\ntitle: x\n---\ngenerator: proj2md\n"
+    ));
+}
+#[test]
+fn content_digest_hashes_paths_and_text() {
+    let mut digest = ContentDigest::new();
+    digest.add("a.txt", "alpha");
+    assert_eq!(
+        digest.finish(),
+        "sha256:e6cd287e7c3c64fdd5c53bfa5e107a8cf80c0eb58c6bcf939be77d61c7a76310"
+    );
+}
+#[test]
+fn git_status_reports_branch_commit_and_dirty_state() {
+    assert_eq!(
+        parse_git_status("# branch.oid 0123abcd\n# branch.head main\n? notes.txt\n"),
+        Some(GitState {
+            branch: "main".to_owned(),
+            commit: "0123abcd".to_owned(),
+            dirty: true,
+        })
+    );
+    assert_eq!(
+        parse_git_status("# branch.oid 0123abcd\n# branch.head (detached)\n"),
+        Some(GitState {
+            branch: "HEAD".to_owned(),
+            commit: "0123abcd".to_owned(),
+            dirty: false,
+        })
+    );
+    assert_eq!(
+        parse_git_status("# branch.oid (initial)\n# branch.head main\n"),
+        None
+    );
+}
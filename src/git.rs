@@ -0,0 +1,54 @@
+use crate::i18n::{Message, tr};
+use std::{io, path::Path, process::Command};
+pub(crate) fn git_stdout(root_path: &Path, args: &[&str]) -> io::Result<String> {
+    String::from_utf8(git_bytes(root_path, args)?).map_err(|err| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            tr(Message::GitOutputNotUtf8, &[&err]),
+        )
+    })
+}
+pub(crate) fn git_bytes(root_path: &Path, args: &[&str]) -> io::Result<Vec<u8>> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(root_path)
+        .args(args)
+        .output()
+        .map_err(|err| io::Error::new(err.kind(), tr(Message::GitFailed, &[&err])))?;
+    if !output.status.success() {
+        return Err(io::Error::other(tr(
+            Message::GitCommandFailed,
+            &[
+                &args.join(" "),
+                &String::from_utf8_lossy(&output.stderr).trim_end(),
+            ],
+        )));
+    }
+    Ok(output.stdout)
+}
+pub(crate) fn is_work_tree(root_path: &Path) -> io::Result<bool> {
+    match Command::new("git")
+        .arg("-C")
+        .arg(root_path)
+        .args(["rev-parse", "--is-inside-work-tree"])
+        .output()
+    {
+        Ok(output) => Ok(output.status.success() && output.stdout.starts_with(b"true")),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(false),
+        Err(err) => Err(io::Error::new(err.kind(), tr(Message::GitFailed, &[&err]))),
+    }
+}
+pub(crate) fn resolve_commit(root_path: &Path, reference: &str) -> io::Result<String> {
+    if reference.is_empty() || reference.starts_with('-') {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            tr(Message::InvalidGitReference, &[&reference]),
+        ));
+    }
+    let peeled = format!("{reference}^{{commit}}");
+    let commit = git_stdout(
+        root_path,
+        &["rev-parse", "--verify", "--end-of-options", &peeled],
+    )?;
+    Ok(commit.trim_end().to_owned())
+}
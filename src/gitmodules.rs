@@ -0,0 +1,120 @@
+use crate::{
+    git::{git_stdout, is_work_tree},
+    i18n::{Message, tr},
+};
+use alloc::collections::BTreeMap;
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+const GITMODULES_FILENAME: &str = ".gitmodules";
+const SUBMODULE_MODE: &str = "160000";
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Submodule {
+    pub url: String,
+    pub commit: Option<String>,
+}
+#[derive(Default)]
+struct PendingSection {
+    path: Option<String>,
+    url: Option<String>,
+}
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub(crate) enum SubmoduleMode {
+    #[default]
+    Mark,
+    Recurse,
+    Skip,
+}
+impl SubmoduleMode {
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        match value {
+            "mark" => Some(Self::Mark),
+            "recurse" => Some(Self::Recurse),
+            "skip" => Some(Self::Skip),
+            _ => None,
+        }
+    }
+    pub(crate) const fn name(self) -> &'static str {
+        match self {
+            Self::Mark => "mark",
+            Self::Recurse => "recurse",
+            Self::Skip => "skip",
+        }
+    }
+}
+pub(crate) fn load_submodules(root_path: &Path) -> io::Result<BTreeMap<PathBuf, Submodule>> {
+    let file = root_path.join(GITMODULES_FILENAME);
+    if !file.is_file() {
+        return Ok(BTreeMap::new());
+    }
+    let text = fs::read_to_string(&file).map_err(|err| {
+        io::Error::new(err.kind(), tr(Message::ReadFileFailed, &[&file.display()]))
+    })?;
+    let mut submodules: BTreeMap<PathBuf, Submodule> = parse_gitmodules(&text)
+        .into_iter()
+        .map(|(path, url)| {
+            let submodule = Submodule { url, commit: None };
+            (path.split('/').collect(), submodule)
+        })
+        .collect();
+    let mut args = vec!["ls-files", "--stage", "-z", "--"];
+    let paths: Vec<String> = submodules
+        .keys()
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect();
+    args.extend(paths.iter().map(String::as_str));
+    let stage = if is_work_tree(root_path)? {
+        git_stdout(root_path, &args)?
+    } else {
+        String::new()
+    };
+    for record in stage.split('\0') {
+        let Some((header, path)) = record.split_once('\t') else {
+            continue;
+        };
+        let mut fields = header.split_whitespace();
+        if let (Some(SUBMODULE_MODE), Some(commit)) = (fields.next(), fields.next())
+            && let Some(submodule) = submodules.get_mut(&path.split('/').collect::<PathBuf>())
+        {
+            submodule.commit = Some(commit.to_owned());
+        }
+    }
+    Ok(submodules)
+}
+pub(crate) fn parse_gitmodules(text: &str) -> Vec<(String, String)> {
+    let mut entries = Vec::new();
+    let mut current: Option<PendingSection> = None;
+    for raw_line in text.lines() {
+        let line = raw_line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if line.starts_with('[') {
+            entries.extend(current.take().and_then(PendingSection::finish));
+            if line.starts_with("[submodule") {
+                current = Some(PendingSection::default());
+            }
+            continue;
+        }
+        let (Some(section), Some((key, raw_value))) = (current.as_mut(), line.split_once('='))
+        else {
+            continue;
+        };
+        let value = raw_value.trim().trim_matches('"');
+        match key.trim() {
+            "path" => section.path = Some(value.trim_end_matches('/').to_owned()),
+            "url" => section.url = Some(value.to_owned()),
+            _ => {}
+        }
+    }
+    entries.extend(current.and_then(PendingSection::finish));
+    entries
+}
+impl PendingSection {
+    fn finish(self) -> Option<(String, String)> {
+        Some((self.path?, self.url.unwrap_or_default()))
+    }
+}
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,92 @@
+use super::{Submodule, SubmoduleMode, load_submodules, parse_gitmodules};
+use crate::{
+    inventory::{InventoryOptions, collect_project_inventory},
+    test_support::{TestDir, must},
+};
+use std::path::PathBuf;
+const PINNED: &str = "0123456789abcdef0123456789abcdef01234567";
+fn repository_with_submodule(name: &str) -> TestDir {
+    let dir = must(TestDir::new(name), "创建测试目录失败");
+    must(
+        dir.write_str(
+            ".gitmodules",
+            "[submodule \"dep\"]\n\tpath = libs/dep\n\turl = https://example.com/dep.git\n",
+        ),
+        "写入 .gitmodules 失败",
+    );
+    must(
+        dir.write_str("libs/dep/lib.rs", "pub fn dep() {}\n"),
+        "写入子模块文件失败",
+    );
+    must(
+        dir.write_str("src/main.rs", "fn main() {}\n"),
+        "写入 main.rs 失败",
+    );
+    must(dir.git(&["init", "-q"]), "初始化仓库失败");
+    must(
+        dir.git(&[
+            "update-index",
+            "--add",
+            "--cacheinfo",
+            &format!("160000,{PINNED},libs/dep"),
+        ]),
+        "登记子模块提交失败",
+    );
+    dir
+}
+fn tree_paths(dir: &TestDir, mode: SubmoduleMode) -> Vec<String> {
+    let options = InventoryOptions {
+        submodules: mode,
+        ..InventoryOptions::default()
+    };
+    let inventory = must(
+        collect_project_inventory(dir.path(), &options),
+        "收集项目清单失败",
+    );
+    let mut paths: Vec<_> = inventory
+        .tree_entries
+        .into_iter()
+        .map(|entry| entry.relative_path)
+        .collect();
+    paths.sort_unstable();
+    paths
+}
+#[test]
+fn gitmodules_sections_yield_paths_and_urls() {
+    let text = "[core]\n\tbare = false\n[submodule \"a\"]\n\turl = git@host:a.git\n\tpath = vendor/a/\n# note\n[submodule \"b\"]\n\tpath = \"b\"\n";
+    assert_eq!(
+        parse_gitmodules(text),
+        [
+            ("vendor/a".to_owned(), "git@host:a.git".to_owned()),
+            ("b".to_owned(), String::new()),
+        ]
+    );
+}
+#[test]
+fn submodules_carry_the_pinned_commit_from_the_index() {
+    let dir = repository_with_submodule("gitmodules-pinned");
+    let submodules = must(load_submodules(dir.path()), "读取子模块失败");
+    assert_eq!(
+        submodules.get(&["libs", "dep"].iter().collect::<PathBuf>()),
+        Some(&Submodule {
+            url: "https://example.com/dep.git".to_owned(),
+            commit: Some(PINNED.to_owned()),
+        })
+    );
+}
+#[test]
+fn submodule_modes_mark_recurse_or_skip() {
+    let dir = repository_with_submodule("gitmodules-modes");
+    assert_eq!(
+        tree_paths(&dir, SubmoduleMode::Mark),
+        ["libs", "libs/dep", "src", "src/main.rs"]
+    );
+    assert_eq!(
+        tree_paths(&dir, SubmoduleMode::Recurse),
+        ["libs", "libs/dep", "libs/dep/lib.rs", "src", "src/main.rs"]
+    );
+    assert_eq!(
+        tree_paths(&dir, SubmoduleMode::Skip),
+        ["libs", "src", "src/main.rs"]
+    );
+}
@@ -0,0 +1,138 @@
+use crate::{
+    changes::git_path_text,
+    git::{git_stdout, resolve_commit},
+    inventory::FileEntry,
+};
+use alloc::collections::BTreeMap;
+use std::{io, path::Path};
+const RECORD_SEPARATOR: char = '\u{1e}';
+const DEFAULT_CHURN_DAYS: u64 = 90;
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum HistoryOrder {
+    Recent,
+    Churn(u64),
+}
+impl HistoryOrder {
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        match value.split_once(':') {
+            None if value == "recent" => Some(Self::Recent),
+            None if value == "churn" => Some(Self::Churn(DEFAULT_CHURN_DAYS)),
+            Some(("churn", text)) => text.parse().ok().filter(|&days| days > 0).map(Self::Churn),
+            Some(_) | None => None,
+        }
+    }
+    pub(crate) fn describe(self) -> String {
+        match self {
+            Self::Recent => "recent".to_owned(),
+            Self::Churn(days) => format!("churn:{days}"),
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum HistoryWindow {
+    Commits(u64),
+    Days(u64),
+}
+impl HistoryWindow {
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        let window = match value.strip_suffix('d') {
+            Some(days) => Self::Days(days.parse().ok()?),
+            None => Self::Commits(value.parse().ok()?),
+        };
+        match window {
+            Self::Commits(0) | Self::Days(0) => None,
+            Self::Commits(_) | Self::Days(_) => Some(window),
+        }
+    }
+    pub(crate) fn describe(self) -> String {
+        match self {
+            Self::Commits(count) => count.to_string(),
+            Self::Days(days) => format!("{days}d"),
+        }
+    }
+    fn log_argument(self) -> String {
+        match self {
+            Self::Commits(count) => format!("--max-count={count}"),
+            Self::Days(days) => format!("--since={days}.days.ago"),
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub(crate) struct FileActivity {
+    pub(crate) last_commit: i64,
+    pub(crate) commits: u64,
+}
+pub(crate) fn file_activity(
+    root_path: &Path,
+    revision: Option<&str>,
+    window: Option<HistoryWindow>,
+) -> io::Result<BTreeMap<String, FileActivity>> {
+    let limit = window.map(HistoryWindow::log_argument);
+    let commit = revision
+        .map(|reference| resolve_commit(root_path, reference))
+        .transpose()?;
+    let mut args = vec![
+        "log",
+        "--format=%x1e%ct",
+        "--name-only",
+        "--no-renames",
+        "--relative",
+        "-z",
+    ];
+    args.extend(limit.as_deref());
+    args.extend(commit.as_deref());
+    args.push("--");
+    let log = git_stdout(root_path, &args)?;
+    let mut activity: BTreeMap<String, FileActivity> = BTreeMap::new();
+    for record in log
+        .split(RECORD_SEPARATOR)
+        .filter(|record| !record.is_empty())
+    {
+        let mut fields = record.split('\0');
+        let timestamp: i64 = fields
+            .next()
+            .and_then(|field| field.trim().parse().ok())
+            .unwrap_or_default();
+        for path in fields
+            .map(|field| field.trim_start_matches('\n'))
+            .filter(|field| !field.is_empty())
+        {
+            let entry = activity.entry(git_path_text(path)?).or_default();
+            entry.last_commit = entry.last_commit.max(timestamp);
+            entry.commits = entry.commits.saturating_add(1);
+        }
+    }
+    Ok(activity)
+}
+pub(crate) fn sort_by_history(
+    root_path: &Path,
+    revision: Option<&str>,
+    order: HistoryOrder,
+    files: &mut [FileEntry],
+) -> io::Result<()> {
+    match order {
+        HistoryOrder::Recent => {
+            let activity = file_activity(root_path, revision, None)?;
+            files.sort_by_key(|file| {
+                core::cmp::Reverse(
+                    activity
+                        .get(&file.relative_path)
+                        .map(|found| found.last_commit),
+                )
+            });
+        }
+        HistoryOrder::Churn(days) => {
+            let activity = file_activity(root_path, revision, Some(HistoryWindow::Days(days)))?;
+            files.sort_by_key(|file| {
+                core::cmp::Reverse(
+                    activity
+                        .get(&file.relative_path)
+                        .map(|found| (found.commits, found.last_commit)),
+                )
+            });
+        }
+    }
+    Ok(())
+}
+#[cfg(test)]
+mod tests;
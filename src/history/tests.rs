@@ -0,0 +1,81 @@
+use super::{HistoryOrder, HistoryWindow, file_activity};
+use crate::{
+    inventory::{InventoryOptions, collect_project_inventory},
+    test_support::{TestDir, must},
+};
+fn commit_files(dir: &TestDir, files: &[&str], round: usize) {
+    for file in files {
+        must(dir.write_str(file, &format!("{round}\n")), "写入文件失败");
+    }
+    must(dir.git(&["add", "-A"]), "暂存文件失败");
+    must(
+        dir.git(&["commit", "-q", "-m", &format!("round {round}")]),
+        "提交失败",
+    );
+}
+fn history_repository(name: &str) -> TestDir {
+    let dir = must(TestDir::new(name), "创建测试目录失败");
+    must(dir.git(&["init", "-q"]), "初始化仓库失败");
+    commit_files(&dir, &["busy.rs", "quiet.rs", "warm.rs"], 1);
+    commit_files(&dir, &["busy.rs", "warm.rs"], 2);
+    commit_files(&dir, &["busy.rs"], 3);
+    must(dir.write_str("scratch.rs", "0\n"), "写入 scratch.rs 失败");
+    dir
+}
+fn content_paths(dir: &TestDir, options: &InventoryOptions) -> Vec<String> {
+    must(
+        collect_project_inventory(dir.path(), options),
+        "收集项目清单失败",
+    )
+    .content_files
+    .into_iter()
+    .map(|file| file.relative_path)
+    .collect()
+}
+#[test]
+fn orders_and_windows_parse_from_cli_values() {
+    assert_eq!(HistoryOrder::parse("recent"), Some(HistoryOrder::Recent));
+    assert_eq!(HistoryOrder::parse("churn"), Some(HistoryOrder::Churn(90)));
+    assert_eq!(HistoryOrder::parse("churn:7"), Some(HistoryOrder::Churn(7)));
+    assert_eq!(HistoryOrder::parse("churn:0"), None);
+    assert_eq!(HistoryWindow::parse("20"), Some(HistoryWindow::Commits(20)));
+    assert_eq!(HistoryWindow::parse("14d"), Some(HistoryWindow::Days(14)));
+    assert_eq!(HistoryWindow::parse("0d"), None);
+    assert_eq!(HistoryWindow::parse("week"), None);
+}
+#[test]
+fn activity_counts_commits_per_file() {
+    let dir = history_repository("history-activity");
+    let activity = must(file_activity(dir.path(), None, None), "读取提交历史失败");
+    let counts: Vec<_> = activity
+        .iter()
+        .map(|(path, found)| (path.as_str(), found.commits))
+        .collect();
+    assert_eq!(counts, [("busy.rs", 3), ("quiet.rs", 1), ("warm.rs", 2)]);
+    let latest = must(
+        file_activity(dir.path(), None, Some(HistoryWindow::Commits(2))),
+        "读取最近提交失败",
+    );
+    assert_eq!(
+        latest.keys().map(String::as_str).collect::<Vec<_>>(),
+        ["busy.rs", "warm.rs"]
+    );
+}
+#[test]
+fn churn_order_and_touched_filter_reshape_contents() {
+    let dir = history_repository("history-inventory");
+    let churn = InventoryOptions {
+        order: Some(HistoryOrder::Churn(30)),
+        ..InventoryOptions::default()
+    };
+    assert_eq!(
+        content_paths(&dir, &churn),
+        ["busy.rs", "warm.rs", "quiet.rs", "scratch.rs"]
+    );
+    let recent = InventoryOptions {
+        order: Some(HistoryOrder::Recent),
+        touched: Some(HistoryWindow::Commits(1)),
+        ..InventoryOptions::default()
+    };
+    assert_eq!(content_paths(&dir, &recent), ["busy.rs"]);
+}
@@ -0,0 +1,615 @@
+use core::{cell::Cell, fmt::Display};
+use std::env;
+const LOCALE_UNSET: u8 = 0;
+const LOCALE_ENVIRONMENT_VARIABLES: [&str; 3] = ["LC_ALL", "LC_MESSAGES", "LANG"];
+thread_local! {
+    static CONSOLE_LOCALE: Cell<u8> = const { Cell::new(LOCALE_UNSET) };
+}
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub(crate) enum Locale {
+    #[default]
+    ZhCn,
+    En,
+}
+impl Locale {
+    pub(crate) fn parse(name: &str) -> Option<Self> {
+        let language = name
+            .split(['.', '@'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase()
+            .replace('_', "-");
+        match language.split('-').next() {
+            Some("zh") => Some(Self::ZhCn),
+            Some("en") => Some(Self::En),
+            _ => None,
+        }
+    }
+    pub(crate) fn from_environment() -> Self {
+        LOCALE_ENVIRONMENT_VARIABLES
+            .iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| Self::parse(&value))
+            .unwrap_or_default()
+    }
+    pub(crate) const fn name(self) -> &'static str {
+        match self {
+            Self::ZhCn => "zh-CN",
+            Self::En => "en",
+        }
+    }
+    const fn code(self) -> u8 {
+        match self {
+            Self::ZhCn => 1,
+            Self::En => 2,
+        }
+    }
+}
+pub(crate) fn set_console_locale(locale: Locale) {
+    CONSOLE_LOCALE.set(locale.code());
+}
+pub(crate) fn console_locale() -> Locale {
+    match CONSOLE_LOCALE.get() {
+        LOCALE_UNSET => Locale::from_environment(),
+        code if code == Locale::En.code() => Locale::En,
+        _ => Locale::ZhCn,
+    }
+}
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum Message {
+    GeneratingDocument,
+    CopiedToClipboard,
+    ErrorPrefix,
+    UnsupportedLocale,
+    PathNotFound,
+    PathNotDirectory,
+    MissingPathComponent,
+    InvalidUtf8Path,
+    SubjectRootPath,
+    SubjectRootName,
+    SubjectRelativePath,
+    SubjectExtension,
+    SubjectFileName,
+    SubjectDirectoryName,
+    CreateOutputDirFailed,
+    CreateOutputFileFailed,
+    GitFailed,
+    GitOutputNotUtf8,
+    GitCommandFailed,
+    GitLogLineInvalid,
+    GitTreeLineInvalid,
+    InvalidGitReference,
+    CommitLogHeading,
+    CommitLogEmpty,
+    DeletedHeading,
+    DeletedMarker,
+    UntrackedMarker,
+    SubmoduleMarker,
+    ExplainSubmodule,
+    InvalidSubmoduleMode,
+    GitRemoteMissing,
+    GitRemoteUnsupported,
+    InvalidPermalinkStyle,
+    InvalidHistoryOrder,
+    InvalidHistoryWindow,
+    ExplainUntouched,
+    InvalidModifiedWithin,
+    InvalidModifiedSince,
+    ExplainStale,
+    FileListPathInvalid,
+    ExplainUnlisted,
+    ExplainUntracked,
+    ExplainUnchanged,
+    WalkFailed,
+    RelativePathFailed,
+    FileTypeUnavailable,
+    MetadataFailed,
+    InvalidSelectPattern,
+    InvalidExcludePattern,
+    InvalidAttributesPattern,
+    ExclusionBuildFailed,
+    SelectionBuildFailed,
+    ReadDirFailed,
+    BinaryMarker,
+    DecodeFailureMarker,
+    ExcludedMarker,
+    TooLargeMarker,
+    ControlCountOverflow,
+    ControlRatioOverflow,
+    TotalRatioOverflow,
+    ReadFileFailed,
+    InvalidBomLength,
+    #[cfg(not(target_os = "windows"))]
+    ClipboardUnsupported,
+    #[cfg(target_os = "windows")]
+    GlobalAllocFailed,
+    #[cfg(target_os = "windows")]
+    GlobalLockFailed,
+    #[cfg(target_os = "windows")]
+    GlobalUnlockFailed,
+    #[cfg(target_os = "windows")]
+    GlobalFreeFailed,
+    #[cfg(target_os = "windows")]
+    OpenClipboardFailed,
+    #[cfg(target_os = "windows")]
+    EmptyClipboardFailed,
+    #[cfg(target_os = "windows")]
+    SetClipboardFailed,
+    #[cfg(target_os = "windows")]
+    CloseClipboardFailed,
+    #[cfg(target_os = "windows")]
+    Win32ErrorCode,
+    #[cfg(target_os = "windows")]
+    DropFilesHeaderTooLarge,
+    #[cfg(target_os = "windows")]
+    PathBufferOverflow,
+    #[cfg(target_os = "windows")]
+    ClipboardSizeOverflow,
+    ClockBeforeEpoch,
+    InvalidLanguageMapping,
+    ReadTemplateFailed,
+    TemplateError,
+    TemplateUnexpectedClose,
+    TemplateTagPosition,
+    TemplateUnclosedTag,
+    TemplateUnknownTag,
+    TemplateUnknownCollection,
+    TemplateUnclosedEach,
+    TemplateElseWithoutIf,
+    TemplateUnclosedIf,
+    TemplateUnknownVariable,
+    ReadConfigFailed,
+    ConfigLineError,
+    ConfigMissingEquals,
+    ConfigInvalidValue,
+    ConfigKeyUntrusted,
+    ConfigPathOutside,
+    UnknownOption,
+    PathInConfig,
+    SinglePathOnly,
+    RevisionConflict,
+    UnsupportedFormat,
+    UnsupportedErrorFormat,
+    UnsupportedMode,
+    UnsupportedTreeStyle,
+    UnsupportedTreeAnnotations,
+    InvalidCount,
+    InvalidSize,
+    MissingOptionValue,
+    OptionValueNotUtf8,
+    TreeIndexOutOfRange,
+    UnsupportedLogFormat,
+    LogIncluded,
+    LogExcluded,
+    LogTreeOnly,
+    LogBinary,
+    LogUndecodable,
+    LogDecoded,
+    LogPhase,
+    DryRunTotal,
+    ExplainRuleLine,
+    ExplainMatchedRule,
+    ExplainReincluded,
+    ExplainHidden,
+    ExplainBuiltin,
+    ExplainTooLarge,
+    ExplainNotSelected,
+    ExplainBinary,
+    ExplainAttributeBinary,
+    ExplainLinguist,
+    ExplainUndecodable,
+    ExplainDecoded,
+    ExplainOutsideRoot,
+    ExplainGenerated,
+    IgnoreFileFailed,
+    TreeHeading,
+    ContentsHeading,
+    SummaryHeading,
+    SummaryFiles,
+    SummaryBytes,
+    SummaryLines,
+    SummaryTokens,
+    SummaryOmitted,
+    SummaryEncodings,
+    SummaryLanguageHeader,
+    NoLanguage,
+    LargestFiles,
+    IgnoredMarker,
+    HiddenMarker,
+    LinesCount,
+    TokensCount,
+    OmittedMarker,
+    OmissionBinary,
+    OmissionTooLarge,
+    OmissionUndecodable,
+    OmissionExcluded,
+    NoExtension,
+    HiddenDirectories,
+    HiddenFiles,
+    HiddenSummary,
+    ListSeparator,
+}
+impl Message {
+    const fn patterns(self) -> [&'static str; 2] {
+        match self {
+            Self::GeneratingDocument => ["正在生成文档...", "Generating document..."],
+            Self::CopiedToClipboard => [
+                "文档文件已复制到剪贴板: {0}",
+                "Document file copied to clipboard: {0}",
+            ],
+            Self::ErrorPrefix => ["错误: {0}", "error: {0}"],
+            Self::UnsupportedLocale => ["不支持的语言: {0}", "unsupported language: {0}"],
+            Self::PathNotFound => ["路径不存在: {0}", "path does not exist: {0}"],
+            Self::PathNotDirectory => ["路径不是目录: {0}", "path is not a directory: {0}"],
+            Self::MissingPathComponent => ["无法获取{0}: {1}", "cannot determine {0}: {1}"],
+            Self::InvalidUtf8Path => ["{0}包含无效 UTF-8: {1}", "{0} contains invalid UTF-8: {1}"],
+            Self::SubjectRootPath => ["根路径", "root path"],
+            Self::SubjectRootName => ["根目录名", "root directory name"],
+            Self::SubjectRelativePath => ["相对路径", "relative path"],
+            Self::SubjectExtension => ["文件扩展名", "file extension"],
+            Self::SubjectFileName => ["文件名", "file name"],
+            Self::SubjectDirectoryName => ["目录名", "directory name"],
+            Self::CreateOutputDirFailed => [
+                "创建临时目录失败: {0}: {1}",
+                "failed to create temporary directory: {0}: {1}",
+            ],
+            Self::CreateOutputFileFailed => [
+                "创建输出文件失败: {0}: {1}",
+                "failed to create output file: {0}: {1}",
+            ],
+            Self::GitFailed => ["运行 git 失败: {0}", "failed to run git: {0}"],
+            Self::GitCommandFailed => ["git {0} 失败: {1}", "git {0} failed: {1}"],
+            Self::InvalidGitReference => [
+                "无效的 git 引用: {0}（不能以 - 开头）",
+                "invalid git reference: {0} (must not start with -)",
+            ],
+            Self::GitLogLineInvalid => [
+                "无法解析 git log 输出: {0}",
+                "could not parse git log output: {0}",
+            ],
+            Self::GitTreeLineInvalid => [
+                "无法解析 git ls-tree 输出: {0}",
+                "could not parse git ls-tree output: {0}",
+            ],
+            Self::CommitLogHeading => ["## 提交记录 ({0}..{1})", "## Commit Log ({0}..{1})"],
+            Self::CommitLogEmpty => ["(没有新的提交)", "(no new commits)"],
+            Self::DeletedHeading => ["## 3. 已删除的文件", "## 3. Deleted Files"],
+            Self::DeletedMarker => ["[已删除]", "[deleted]"],
+            Self::UntrackedMarker => ["[未跟踪]", "[untracked]"],
+            Self::SubmoduleMarker => ["[子模块: {0} @ {1}]", "[submodule: {0} @ {1}]"],
+            Self::ExplainSubmodule => ["位于子模块中: {0}", "inside submodule: {0}"],
+            Self::GitRemoteMissing => [
+                "仓库没有配置远程地址，无法生成永久链接",
+                "the repository has no remote to build permalinks from",
+            ],
+            Self::GitRemoteUnsupported => [
+                "无法从远程地址推断网页地址: {0}",
+                "cannot derive a web URL from remote: {0}",
+            ],
+            Self::InvalidPermalinkStyle => [
+                "无效的永久链接样式: {0}（可选 github、gitlab、gitea 或包含 {path} 的模板）",
+                "invalid permalink style: {0} (expected github, gitlab, gitea or a template containing {path})",
+            ],
+            Self::InvalidHistoryOrder => [
+                "无效的排序方式: {0}（可选 recent、churn 或 churn:<天数>）",
+                "invalid sort order: {0} (expected recent, churn or churn:<days>)",
+            ],
+            Self::InvalidHistoryWindow => [
+                "无效的提交范围: {0}（应为提交数，例如 20，或天数，例如 14d）",
+                "invalid history window: {0} (expected a commit count like 20 or days like 14d)",
+            ],
+            Self::ExplainUntouched => [
+                "不在最近的提交范围内: {0}",
+                "not touched within the history window: {0}",
+            ],
+            Self::InvalidModifiedWithin => [
+                "无效的时长: {0}（应为数字加单位 s、m、h、d 或 w，例如 3d）",
+                "invalid duration: {0} (expected a number with unit s, m, h, d or w, like 3d)",
+            ],
+            Self::InvalidModifiedSince => [
+                "无效的时间: {0}（应为 UTC 时间 2026-10-18、2026-10-18T13:14:56Z 或 Unix 秒数）",
+                "invalid timestamp: {0} (expected UTC time like 2026-10-18, 2026-10-18T13:14:56Z or Unix seconds)",
+            ],
+            Self::ExplainStale => ["修改时间 {0} 早于 {1}", "last modified at {0}, before {1}"],
+            Self::FileListPathInvalid => [
+                "文件列表中的路径无效: {0}（应为项目根目录内的相对路径）",
+                "invalid path in file list: {0} (expected a path relative to the project root)",
+            ],
+            Self::ExplainUnlisted => ["不在文件列表 {0} 中", "not listed in {0}"],
+            Self::InvalidSubmoduleMode => [
+                "无效的子模块处理方式: {0}（可选 mark、recurse、skip）",
+                "invalid submodule mode: {0} (expected mark, recurse or skip)",
+            ],
+            Self::ExplainUntracked => ["未被 git 跟踪", "not tracked by git"],
+            Self::ExplainUnchanged => {
+                ["不在 git 变更列表中: {0}", "not among the git changes: {0}"]
+            }
+            Self::GitOutputNotUtf8 => [
+                "git 输出不是有效的 UTF-8: {0}",
+                "git output is not valid UTF-8: {0}",
+            ],
+            Self::WalkFailed => ["遍历目录失败: {0}", "failed to walk directory: {0}"],
+            Self::RelativePathFailed => [
+                "无法计算相对路径: {0}: {1}",
+                "cannot compute relative path: {0}: {1}",
+            ],
+            Self::FileTypeUnavailable => {
+                ["无法获取文件类型: {0}", "cannot determine file type: {0}"]
+            }
+            Self::MetadataFailed => [
+                "读取文件元数据失败: {0}: {1}",
+                "failed to read file metadata: {0}: {1}",
+            ],
+            Self::InvalidSelectPattern => [
+                "无效的文件选择模式: {0}: {1}",
+                "invalid selection pattern: {0}: {1}",
+            ],
+            Self::SelectionBuildFailed => [
+                "构建文件选择规则失败: {0}",
+                "failed to build selection rules: {0}",
+            ],
+            Self::InvalidExcludePattern => [
+                "无效的排除模式: {0}: {1}",
+                "invalid exclusion pattern: {0}: {1}",
+            ],
+            Self::InvalidAttributesPattern => [
+                "无效的 .gitattributes 模式: {0}: {1}",
+                "invalid .gitattributes pattern: {0}: {1}",
+            ],
+            Self::ExclusionBuildFailed => [
+                "构建排除规则失败: {0}",
+                "failed to build exclusion rules: {0}",
+            ],
+            Self::ReadDirFailed => ["读取目录失败: {0}", "failed to read directory: {0}"],
+            Self::BinaryMarker => ["(二进制文件)", "(binary file)"],
+            Self::DecodeFailureMarker => ["(解码失败)", "(could not decode)"],
+            Self::ExcludedMarker => ["(已排除)", "(excluded)"],
+            Self::TooLargeMarker => ["(文件过大，已省略)", "(file too large, omitted)"],
+            Self::ControlCountOverflow => [
+                "统计控制字符时发生溢出",
+                "overflow while counting control characters",
+            ],
+            Self::ControlRatioOverflow => [
+                "计算控制字符比例时发生溢出",
+                "overflow while computing the control character ratio",
+            ],
+            Self::TotalRatioOverflow => [
+                "计算字节总数比例时发生溢出",
+                "overflow while computing the byte count ratio",
+            ],
+            Self::ReadFileFailed => ["读取文件失败: {0}", "failed to read file: {0}"],
+            Self::InvalidBomLength => ["BOM 长度异常: {0}", "unexpected BOM length: {0}"],
+            #[cfg(not(target_os = "windows"))]
+            Self::ClipboardUnsupported => [
+                "当前平台暂不支持复制到剪贴板",
+                "copying to the clipboard is not supported on this platform",
+            ],
+            #[cfg(target_os = "windows")]
+            Self::GlobalAllocFailed => ["分配全局内存失败", "failed to allocate global memory"],
+            #[cfg(target_os = "windows")]
+            Self::GlobalLockFailed => ["锁定全局内存失败", "failed to lock global memory"],
+            #[cfg(target_os = "windows")]
+            Self::GlobalUnlockFailed => ["解锁全局内存失败", "failed to unlock global memory"],
+            #[cfg(target_os = "windows")]
+            Self::GlobalFreeFailed => ["释放全局内存失败", "failed to free global memory"],
+            #[cfg(target_os = "windows")]
+            Self::OpenClipboardFailed => ["打开剪贴板失败", "failed to open the clipboard"],
+            #[cfg(target_os = "windows")]
+            Self::EmptyClipboardFailed => ["清空剪贴板失败", "failed to empty the clipboard"],
+            #[cfg(target_os = "windows")]
+            Self::SetClipboardFailed => ["写入剪贴板失败", "failed to write to the clipboard"],
+            #[cfg(target_os = "windows")]
+            Self::CloseClipboardFailed => ["关闭剪贴板失败", "failed to close the clipboard"],
+            #[cfg(target_os = "windows")]
+            Self::Win32ErrorCode => ["{0}: Win32 错误码 {1}", "{0}: Win32 error code {1}"],
+            #[cfg(target_os = "windows")]
+            Self::DropFilesHeaderTooLarge => [
+                "DROPFILES 头部大小超出 Win32 范围: {0}",
+                "DROPFILES header size exceeds the Win32 range: {0}",
+            ],
+            #[cfg(target_os = "windows")]
+            Self::PathBufferOverflow => [
+                "计算路径缓冲区大小时发生溢出",
+                "overflow while computing the path buffer size",
+            ],
+            #[cfg(target_os = "windows")]
+            Self::ClipboardSizeOverflow => [
+                "计算剪贴板数据大小时发生溢出",
+                "overflow while computing the clipboard data size",
+            ],
+            Self::ClockBeforeEpoch => [
+                "系统时间早于 1970 年: {0}",
+                "system clock is before 1970: {0}",
+            ],
+            Self::InvalidLanguageMapping => [
+                "语言映射格式应为 <键>=<语言>: {0}",
+                "language mapping must be <key>=<language>: {0}",
+            ],
+            Self::ReadTemplateFailed => [
+                "读取模板文件失败: {0}: {1}",
+                "failed to read template file: {0}: {1}",
+            ],
+            Self::TemplateError => ["模板错误: {0}", "template error: {0}"],
+            Self::TemplateUnexpectedClose => ["存在多余的结束标签", "unexpected closing tag"],
+            Self::TemplateTagPosition => ["标签位置异常", "invalid tag position"],
+            Self::TemplateUnclosedTag => ["标签缺少 '}}'", "tag is missing '}}'"],
+            Self::TemplateUnknownTag => ["未知模板标签: {{{0}}}", "unknown template tag: {{{0}}}"],
+            Self::TemplateUnknownCollection => {
+                ["未知模板集合: {0}", "unknown template collection: {0}"]
+            }
+            Self::TemplateUnclosedEach => [
+                "#each 缺少匹配的 {{/each}}",
+                "#each has no matching {{/each}}",
+            ],
+            Self::TemplateElseWithoutIf => [
+                "{{else}} 缺少匹配的 {{/if}}",
+                "{{else}} has no matching {{/if}}",
+            ],
+            Self::TemplateUnclosedIf => ["#if 缺少匹配的 {{/if}}", "#if has no matching {{/if}}"],
+            Self::TemplateUnknownVariable => {
+                ["未知模板变量: {0}", "unknown template variable: {0}"]
+            }
+            Self::ReadConfigFailed => [
+                "读取配置文件失败: {0}: {1}",
+                "failed to read config file: {0}: {1}",
+            ],
+            Self::ConfigLineError => ["配置文件第 {0} 行{1}", "config file line {0}: {1}"],
+            Self::ConfigMissingEquals => ["缺少 '='", "missing '='"],
+            Self::ConfigInvalidValue => ["无法解析取值", "cannot parse value"],
+            Self::ConfigKeyUntrusted => [
+                "不能在项目目录的配置文件中设置 {0}（请通过 --config 显式指定配置文件）",
+                "cannot set {0} in the project's config file (pass the config explicitly with --config)",
+            ],
+            Self::ConfigPathOutside => [
+                "的路径必须位于配置文件所在目录内: {0}",
+                "path must stay inside the config file's directory: {0}",
+            ],
+            Self::UnknownOption => ["未知选项: {0}", "unknown option: {0}"],
+            Self::PathInConfig => [
+                "配置文件中不能指定项目路径: {0}",
+                "the project path cannot be set in a config file: {0}",
+            ],
+            Self::SinglePathOnly => [
+                "只接受一个项目路径参数",
+                "only one project path is accepted",
+            ],
+            Self::RevisionConflict => [
+                "--rev 不能与 {0} 同时使用",
+                "--rev cannot be combined with {0}",
+            ],
+            Self::UnsupportedErrorFormat => {
+                ["不支持的错误输出格式: {0}", "unsupported error format: {0}"]
+            }
+            Self::UnsupportedFormat => ["不支持的输出格式: {0}", "unsupported output format: {0}"],
+            Self::UnsupportedMode => ["不支持的输出模式: {0}", "unsupported output mode: {0}"],
+            Self::UnsupportedTreeStyle => {
+                ["不支持的目录树样式: {0}", "unsupported tree style: {0}"]
+            }
+            Self::UnsupportedTreeAnnotations => [
+                "不支持的目录树标注: {0}",
+                "unsupported tree annotations: {0}",
+            ],
+            Self::InvalidCount => [
+                "{0} 的参数值不是有效的数量: {1}: {2}",
+                "{0} expects a count, got: {1}: {2}",
+            ],
+            Self::InvalidSize => [
+                "{0} 的参数值不是有效的大小: {1}",
+                "{0} expects a size, got: {1}",
+            ],
+            Self::MissingOptionValue => ["{0} 缺少参数值", "{0} requires a value"],
+            Self::OptionValueNotUtf8 => [
+                "{0} 的参数值包含无效 UTF-8: {1}",
+                "the value of {0} contains invalid UTF-8: {1}",
+            ],
+            Self::TreeIndexOutOfRange => ["目录树索引越界: {0}", "tree index out of range: {0}"],
+            Self::UnsupportedLogFormat => ["不支持的日志格式: {0}", "unsupported log format: {0}"],
+            Self::LogIncluded => ["包含: {0}", "included: {0}"],
+            Self::LogExcluded => ["排除: {0} (规则: {1})", "excluded: {0} (rule: {1})"],
+            Self::LogTreeOnly => ["仅目录树: {0} (规则: {1})", "tree only: {0} (rule: {1})"],
+            Self::LogBinary => ["二进制文件: {0}", "binary: {0}"],
+            Self::LogUndecodable => ["解码失败: {0}", "undecodable: {0}"],
+            Self::LogDecoded => ["已解码: {0} ({1})", "decoded: {0} ({1})"],
+            Self::LogPhase => ["阶段 {0}: {1} ms", "phase {0}: {1} ms"],
+            Self::DryRunTotal => [
+                "共 {0} 个文件, {1}, 约 {2} tokens",
+                "{0} files, {1}, ~{2} tokens",
+            ],
+            Self::ExplainRuleLine => ["{0} 第 {1} 行", "{0}:{1}"],
+            Self::ExplainMatchedRule => ["{0}: {1}", "{0}: {1}"],
+            Self::ExplainReincluded => ["被 {0} 重新包含: {1}", "re-included by {0}: {1}"],
+            Self::ExplainHidden => ["隐藏路径: {0}", "hidden path: {0}"],
+            Self::ExplainBuiltin => ["排除规则: {0}", "exclusion rule: {0}"],
+            Self::ExplainGenerated => [
+                "由 proj2md 生成的输出文件",
+                "output previously generated by proj2md",
+            ],
+            Self::ExplainTooLarge => [
+                "文件大小 {0} 超过上限 {1}",
+                "size {0} exceeds the limit of {1}",
+            ],
+            Self::ExplainNotSelected => ["不匹配任何 --select 模式", "matches no --select pattern"],
+            Self::ExplainBinary => ["内容被识别为二进制", "contents look binary"],
+            Self::ExplainAttributeBinary => [
+                ".gitattributes 标记为二进制",
+                "marked binary in .gitattributes",
+            ],
+            Self::ExplainLinguist => [".gitattributes 标记为 {0}", "marked {0} in .gitattributes"],
+            Self::ExplainUndecodable => {
+                ["无法识别文本编码", "no text encoding decodes the contents"]
+            }
+            Self::ExplainDecoded => ["以 {0} 解码", "decoded as {0}"],
+            Self::ExplainOutsideRoot => [
+                "路径不在项目目录内: {0}",
+                "path is outside the project: {0}",
+            ],
+            Self::IgnoreFileFailed => [
+                "读取忽略规则失败: {0}: {1}",
+                "failed to read ignore rules: {0}: {1}",
+            ],
+            Self::TreeHeading => ["## 1. 目录结构", "## 1. Directory Structure"],
+            Self::ContentsHeading => ["## 2. 文件内容", "## 2. File Contents"],
+            Self::SummaryHeading => ["## 项目概览", "## Project Summary"],
+            Self::SummaryFiles => ["- 文件数: {0}", "- Files: {0}"],
+            Self::SummaryBytes => ["- 总大小: {0}", "- Total size: {0}"],
+            Self::SummaryLines => ["- 总行数: {0}", "- Total lines: {0}"],
+            Self::SummaryTokens => ["- 估算 tokens: 约 {0}", "- Estimated tokens: ~{0}"],
+            Self::SummaryOmitted => ["- 已省略: {0}", "- Omitted: {0}"],
+            Self::SummaryEncodings => ["- 检测到的编码: {0}", "- Detected encodings: {0}"],
+            Self::SummaryLanguageHeader => {
+                ["| 语言 | 文件数 | 行数 |", "| Language | Files | Lines |"]
+            }
+            Self::NoLanguage => ["(无)", "(none)"],
+            Self::LargestFiles => ["最大的文件:", "Largest files:"],
+            Self::IgnoredMarker => ["[已忽略]", "[ignored]"],
+            Self::HiddenMarker => ["[已隐藏]", "[hidden]"],
+            Self::LinesCount => ["{0} 行", "{0} lines"],
+            Self::TokensCount => ["约 {0} tokens", "~{0} tokens"],
+            Self::OmittedMarker => ["[已省略: {0}]", "[omitted: {0}]"],
+            Self::OmissionBinary => ["二进制文件", "binary file"],
+            Self::OmissionTooLarge => ["文件过大", "too large"],
+            Self::OmissionUndecodable => ["解码失败", "undecodable"],
+            Self::OmissionExcluded => ["已排除", "excluded"],
+            Self::NoExtension => ["无扩展名", "no extension"],
+            Self::HiddenDirectories => ["{0} 个目录", "{0} more directories"],
+            Self::HiddenFiles => ["{0} 个文件", "{0} more files"],
+            Self::HiddenSummary => ["… 另有 {0}", "… and {0}"],
+            Self::ListSeparator => ["、", ", "],
+        }
+    }
+    pub(crate) const fn pattern(self, locale: Locale) -> &'static str {
+        let [zh_cn, en] = self.patterns();
+        match locale {
+            Locale::ZhCn => zh_cn,
+            Locale::En => en,
+        }
+    }
+}
+pub(crate) fn tr(message: Message, args: &[&dyn Display]) -> String {
+    tr_in(console_locale(), message, args)
+}
+pub(crate) fn tr_in(locale: Locale, message: Message, args: &[&dyn Display]) -> String {
+    let pattern = message.pattern(locale);
+    let mut output = String::with_capacity(pattern.len());
+    let mut rest = pattern;
+    while let Some(start) = rest.find('{') {
+        let (before, tail) = rest.split_at(start);
+        output.push_str(before);
+        let argument = tail
+            .get(1..)
+            .and_then(|after| after.split_once('}'))
+            .and_then(|(index, after)| Some((args.get(index.parse::<usize>().ok()?)?, after)));
+        if let Some((value, after)) = argument {
+            output.push_str(&value.to_string());
+            rest = after;
+        } else {
+            output.push('{');
+            rest = tail.get(1..).unwrap_or_default();
+        }
+    }
+    output.push_str(rest);
+    output
+}
+#[cfg(test)]
+mod tests;
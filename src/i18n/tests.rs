@@ -0,0 +1,51 @@
+use super::{Locale, Message, tr_in};
+#[test]
+fn locale_names_follow_posix_and_bcp47_forms() {
+    assert_eq!(Locale::parse("en_US.UTF-8"), Some(Locale::En));
+    assert_eq!(Locale::parse("EN"), Some(Locale::En));
+    assert_eq!(Locale::parse("zh_CN"), Some(Locale::ZhCn));
+    assert_eq!(Locale::parse("zh-Hans@latin"), Some(Locale::ZhCn));
+    assert_eq!(Locale::parse("C"), None);
+    assert_eq!(Locale::parse(""), None);
+}
+#[test]
+fn placeholders_are_filled_by_position() {
+    assert_eq!(
+        tr_in(
+            Locale::En,
+            Message::InvalidCount,
+            &[&"--tree-collapse", &"x", &"nope"]
+        ),
+        "--tree-collapse expects a count, got: x: nope"
+    );
+    assert_eq!(
+        tr_in(
+            Locale::ZhCn,
+            Message::ConfigLineError,
+            &[&3_usize, &"缺少 '='"]
+        ),
+        "配置文件第 3 行缺少 '='"
+    );
+}
+#[test]
+fn literal_braces_pass_through() {
+    assert_eq!(
+        tr_in(Locale::En, Message::TemplateUnknownTag, &[&"x"]),
+        "unknown template tag: {{x}}"
+    );
+    assert_eq!(
+        tr_in(Locale::ZhCn, Message::TemplateUnclosedEach, &[]),
+        "#each 缺少匹配的 {{/each}}"
+    );
+}
+#[test]
+fn document_headings_are_translated() {
+    assert_eq!(
+        tr_in(Locale::ZhCn, Message::TreeHeading, &[]),
+        "## 1. 目录结构"
+    );
+    assert_eq!(
+        tr_in(Locale::En, Message::ContentsHeading, &[]),
+        "## 2. File Contents"
+    );
+}
@@ -0,0 +1,132 @@
+use crate::i18n::{Message, tr};
+use ignore::{
+    Match,
+    gitignore::{Gitignore, GitignoreBuilder, Glob},
+};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+const IGNORE_FILENAME: &str = ".ignore";
+const GITIGNORE_FILENAME: &str = ".gitignore";
+pub(crate) const GIT_DIRECTORY: &str = ".git";
+const GIT_EXCLUDE_PATH: &str = ".git/info/exclude";
+struct IgnoreLayer {
+    directory: PathBuf,
+    matcher: Gitignore,
+}
+pub(crate) struct IgnoreRules {
+    layers: Vec<IgnoreLayer>,
+}
+impl IgnoreRules {
+    pub(crate) fn load(directory: &Path) -> io::Result<Self> {
+        let mut ignore_layers = Vec::new();
+        let mut git_layers = Vec::new();
+        let mut exclude_layers = Vec::new();
+        let mut saw_git = false;
+        for ancestor in directory.ancestors() {
+            push_layer(
+                &mut ignore_layers,
+                ancestor,
+                &ancestor.join(IGNORE_FILENAME),
+            )?;
+            if !saw_git {
+                push_layer(
+                    &mut git_layers,
+                    ancestor,
+                    &ancestor.join(GITIGNORE_FILENAME),
+                )?;
+                push_layer(
+                    &mut exclude_layers,
+                    ancestor,
+                    &ancestor.join(GIT_EXCLUDE_PATH),
+                )?;
+            }
+            saw_git = saw_git || ancestor.join(GIT_DIRECTORY).exists();
+        }
+        let mut layers = ignore_layers;
+        layers.append(&mut git_layers);
+        layers.append(&mut exclude_layers);
+        let (global, _global_error) = Gitignore::global();
+        if !global.is_empty() {
+            layers.push(IgnoreLayer {
+                directory: PathBuf::new(),
+                matcher: global,
+            });
+        }
+        Ok(Self { layers })
+    }
+    pub(crate) fn matched(&self, candidate: &Path, is_dir: bool) -> Match<&Glob> {
+        self.matching_layer(candidate, is_dir)
+            .map_or(Match::None, |(_, matched)| matched)
+    }
+    pub(crate) fn matched_line(&self, candidate: &Path, is_dir: bool) -> io::Result<Option<usize>> {
+        let Some((layer, matched)) = self.matching_layer(candidate, is_dir) else {
+            return Ok(None);
+        };
+        let Some(source) = matched.inner().and_then(|glob| glob.from()) else {
+            return Ok(None);
+        };
+        let text = fs::read_to_string(source).map_err(|err| {
+            io::Error::new(
+                err.kind(),
+                tr(Message::ReadFileFailed, &[&source.display()]),
+            )
+        })?;
+        for (index, line) in text
+            .lines()
+            .collect::<Vec<_>>()
+            .into_iter()
+            .enumerate()
+            .rev()
+        {
+            let mut builder = GitignoreBuilder::new(layer.matcher.path());
+            if builder.add_line(None, line).is_err() {
+                continue;
+            }
+            let single = builder.build().map_err(|err| {
+                io::Error::other(tr(Message::IgnoreFileFailed, &[&source.display(), &err]))
+            })?;
+            let found = single.matched(candidate, is_dir);
+            if found.is_ignore() == matched.is_ignore()
+                && found.is_whitelist() == matched.is_whitelist()
+            {
+                return Ok(Some(index.saturating_add(1)));
+            }
+        }
+        Ok(None)
+    }
+    fn matching_layer(
+        &self,
+        candidate: &Path,
+        is_dir: bool,
+    ) -> Option<(&IgnoreLayer, Match<&Glob>)> {
+        self.layers
+            .iter()
+            .filter(|layer| candidate.starts_with(&layer.directory) && candidate != layer.directory)
+            .map(|layer| (layer, layer.matcher.matched(candidate, is_dir)))
+            .find(|found| !found.1.is_none())
+    }
+}
+fn push_layer(layers: &mut Vec<IgnoreLayer>, directory: &Path, file: &Path) -> io::Result<()> {
+    if !file.is_file() {
+        return Ok(());
+    }
+    let mut builder = GitignoreBuilder::new(directory);
+    if let Some(err) = builder.add(file) {
+        return Err(io::Error::other(tr(
+            Message::IgnoreFileFailed,
+            &[&file.display(), &err],
+        )));
+    }
+    let matcher = builder
+        .build()
+        .map_err(|err| io::Error::other(tr(Message::IgnoreFileFailed, &[&file.display(), &err])))?;
+    layers.push(IgnoreLayer {
+        directory: directory.to_path_buf(),
+        matcher,
+    });
+    Ok(())
+}
+#[cfg(test)]
+mod tests;
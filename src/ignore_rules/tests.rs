@@ -0,0 +1,62 @@
+use super::IgnoreRules;
+use crate::test_support::{TestDir, must};
+use ignore::Match;
+use std::{fs, path::Path};
+fn is_ignored(rules: &IgnoreRules, candidate: &Path, is_dir: bool) -> Option<bool> {
+    match rules.matched(candidate, is_dir) {
+        Match::None => None,
+        Match::Ignore(_) => Some(true),
+        Match::Whitelist(_) => Some(false),
+    }
+}
+#[test]
+fn ignore_files_take_precedence_over_gitignore() {
+    let dir = must(TestDir::new("ignore-rules-precedence"), "创建测试目录失败");
+    must(
+        dir.write_str(".gitignore", "*.log\n"),
+        "写入 .gitignore 失败",
+    );
+    must(dir.write_str(".ignore", "!keep.log\n"), "写入 .ignore 失败");
+    must(
+        dir.write_str("src/.gitignore", "!debug.log\n"),
+        "写入 src/.gitignore 失败",
+    );
+    let root = must(fs::canonicalize(dir.path()), "解析测试目录失败");
+    let rules = must(IgnoreRules::load(&root.join("src")), "加载忽略规则失败");
+    assert_eq!(
+        is_ignored(&rules, &root.join("keep.log"), false),
+        Some(false)
+    );
+    assert_eq!(
+        is_ignored(&rules, &root.join("other.log"), false),
+        Some(true)
+    );
+    assert_eq!(
+        is_ignored(&rules, &root.join("src/debug.log"), false),
+        Some(false)
+    );
+    assert_eq!(is_ignored(&rules, &root.join("src/main.rs"), false), None);
+}
+#[test]
+fn gitignore_files_above_a_repository_do_not_apply() {
+    let dir = must(TestDir::new("ignore-rules-repository"), "创建测试目录失败");
+    must(
+        dir.write_str(".gitignore", "*.txt\n"),
+        "写入外层 .gitignore 失败",
+    );
+    must(dir.write_str(".ignore", "*.tmp\n"), "写入外层 .ignore 失败");
+    must(
+        fs::create_dir_all(dir.path().join("repo/.git")),
+        "创建 .git 目录失败",
+    );
+    let root = must(fs::canonicalize(dir.path()), "解析测试目录失败");
+    let rules = must(IgnoreRules::load(&root.join("repo")), "加载忽略规则失败");
+    assert_eq!(
+        is_ignored(&rules, &root.join("repo/notes.txt"), false),
+        None
+    );
+    assert_eq!(
+        is_ignored(&rules, &root.join("repo/cache.tmp"), false),
+        Some(true)
+    );
+}
@@ -1,104 +1,894 @@
 use crate::{
-    config::{EXTRA_EXCLUDED_FILES, OUTPUT_FILENAME},
-    errors::AppResult,
+    attributes::{Attributes, FileAttributes, GeneratedFiles, is_attributes_file},
+    changes::{ChangeScope, TrackedFiles, Tracking, changed_files, tracked_files},
+    config::{DEFAULT_EXCLUSIONS, OUTPUT_EXTENSIONS, SIGNATURE_SCAN_LIMIT},
+    errors::{AppResult, FailureKind},
+    file_list::read_file_list,
+    front_matter::has_generator_signature,
+    gitmodules::{Submodule, SubmoduleMode, load_submodules},
+    history::{HistoryOrder, HistoryWindow, file_activity, sort_by_history},
+    i18n::{Message, tr},
+    ignore_rules::{GIT_DIRECTORY, IgnoreRules},
+    languages::{LanguageRegistry, parse_shebang},
+    logging::{self, Decision, LogEvent, LogLevel, Phase, Rule},
+    modified::ModifiedWindow,
     paths,
+    revision::{read_object, revision_files},
+};
+use alloc::collections::{BTreeMap, BTreeSet};
+use core::iter;
+use ignore::{
+    Match, Walk, WalkBuilder,
+    gitignore::{Gitignore, GitignoreBuilder},
+    overrides::{Override, OverrideBuilder},
 };
-use ignore::{Walk, WalkBuilder};
 use std::{
-    io,
+    ffi::OsStr,
+    fs,
+    io::{self, Read as _},
     path::{Path, PathBuf},
+    time::{Instant, SystemTime},
 };
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub(crate) struct FileEntry {
-    pub(crate) absolute_path: PathBuf,
-    pub(crate) relative_path: String,
-    pub(crate) code_block_language: String,
+#[non_exhaustive]
+pub struct FileEntry {
+    pub absolute_path: PathBuf,
+    pub relative_path: String,
+    pub code_block_language: String,
+    pub size: u64,
+    pub omission: Option<Omission>,
+    pub git_object: Option<String>,
+    pub untracked: bool,
+    pub declared_encoding: Option<String>,
+    pub permalink: Option<String>,
 }
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub(crate) struct ProjectInventory {
+    pub(crate) root_path: PathBuf,
     pub(crate) root_name: String,
     pub(crate) tree_entries: Vec<TreeEntry>,
     pub(crate) content_files: Vec<FileEntry>,
+    pub(crate) deleted_files: Vec<String>,
 }
-#[derive(Clone, Debug, Eq, PartialEq)]
-pub(crate) struct TreeEntry {
-    pub(crate) depth: usize,
-    pub(crate) name: String,
-    pub(crate) kind: TreeEntryKind,
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct TreeEntry {
+    pub depth: usize,
+    pub name: String,
+    pub kind: TreeEntryKind,
+    pub relative_path: String,
+    pub is_last: bool,
+    pub bytes: u64,
+    pub lines: usize,
+    pub tokens: usize,
+    pub omission: Option<Omission>,
+    pub untracked: bool,
+    pub submodule: Option<Submodule>,
 }
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
-pub(crate) enum TreeEntryKind {
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum TreeEntryKind {
     Directory,
+    #[default]
     File,
+    Ignored,
+    Hidden,
+    Collapsed,
+}
+impl TreeEntryKind {
+    pub(crate) const fn name(self) -> &'static str {
+        match self {
+            Self::Directory => "directory",
+            Self::File => "file",
+            Self::Ignored => "ignored",
+            Self::Hidden => "hidden",
+            Self::Collapsed => "collapsed",
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum Omission {
+    Binary,
+    TooLarge,
+    Undecodable,
+    Excluded,
+}
+impl Omission {
+    pub(crate) const fn name(self) -> &'static str {
+        match self {
+            Self::Binary => "binary",
+            Self::TooLarge => "too_large",
+            Self::Undecodable => "undecodable",
+            Self::Excluded => "excluded",
+        }
+    }
+}
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub(crate) struct InventoryOptions {
+    pub(crate) languages: LanguageRegistry,
+    pub(crate) max_file_size: Option<u64>,
+    pub(crate) show_ignored_directories: bool,
+    pub(crate) content_selection: Vec<String>,
+    pub(crate) exclusions: Vec<String>,
+    pub(crate) output_path: Option<PathBuf>,
+    pub(crate) changes: Option<ChangeScope>,
+    pub(crate) revision: Option<String>,
+    pub(crate) tracking: Option<Tracking>,
+    pub(crate) generated_files: GeneratedFiles,
+    pub(crate) submodules: SubmoduleMode,
+    pub(crate) order: Option<HistoryOrder>,
+    pub(crate) touched: Option<HistoryWindow>,
+    pub(crate) modified: Option<ModifiedWindow>,
+    pub(crate) files_from: Option<PathBuf>,
+}
+#[derive(Clone, Copy)]
+struct ContentFilter<'filter> {
+    selection: Option<&'filter Override>,
+    changed: Option<&'filter BTreeSet<String>>,
+    tracked: Option<&'filter TrackedFiles>,
+    touched: Option<&'filter BTreeSet<String>>,
+    modified_after: Option<SystemTime>,
+}
+struct FileCandidate<'candidate> {
+    path: &'candidate Path,
+    relative_path: &'candidate Path,
+    size: u64,
+    modified: Option<SystemTime>,
+    git_object: Option<String>,
+    attributes: FileAttributes,
 }
-pub(crate) fn collect_project_inventory(root_path: &Path) -> AppResult<ProjectInventory> {
+pub(crate) fn collect_project_inventory(
+    root_path: &Path,
+    options: &InventoryOptions,
+) -> AppResult<ProjectInventory> {
+    let selection = build_selection(root_path, &options.content_selection)
+        .map_err(|err| FailureKind::Cli.wrap(err))?;
+    let exclusions = build_exclusions(root_path, &options.exclusions)
+        .map_err(|err| FailureKind::Cli.wrap(err))?;
+    let changes = options
+        .changes
+        .as_ref()
+        .map(|scope| changed_files(root_path, scope))
+        .transpose()
+        .map_err(|err| FailureKind::Git.wrap(err))?;
+    let tracked = options
+        .tracking
+        .map(|tracking| tracked_files(root_path, tracking))
+        .transpose()
+        .map_err(|err| FailureKind::Git.wrap(err))?;
+    let touched = options
+        .touched
+        .map(|window| file_activity(root_path, options.revision.as_deref(), Some(window)))
+        .transpose()
+        .map_err(|err| FailureKind::Git.wrap(err))?
+        .map(|activity| activity.into_keys().collect::<BTreeSet<_>>());
+    let listed = options
+        .files_from
+        .as_deref()
+        .map(|source| read_file_list(source, root_path))
+        .transpose()
+        .map_err(|err| FailureKind::Read.wrap(err))?;
+    let output_path = output_relative_path(root_path, options.output_path.as_deref());
+    let filter = ContentFilter {
+        selection: selection.as_ref(),
+        changed: changes.as_ref().map(|found| &found.changed),
+        tracked: tracked.as_ref(),
+        touched: touched.as_ref(),
+        modified_after: options
+            .modified
+            .map(|window| window.cutoff(SystemTime::now())),
+    };
+    let started = Instant::now();
+    let mut inventory = if let Some(reference) = options.revision.as_deref() {
+        revision_project(
+            root_path,
+            reference,
+            options,
+            filter,
+            &exclusions,
+            listed.as_ref(),
+        )
+        .map_err(|err| FailureKind::Git.wrap(err))?
+    } else if let Some(files) = listed.as_ref() {
+        listed_project(
+            root_path,
+            files,
+            options,
+            filter,
+            &exclusions,
+            output_path.as_deref(),
+        )
+        .map_err(|err| FailureKind::Walk.wrap(err))?
+    } else {
+        walk_project(
+            root_path,
+            options,
+            filter,
+            &exclusions,
+            output_path.as_deref(),
+        )
+        .map_err(|err| FailureKind::Walk.wrap(err))?
+    };
+    inventory.deleted_files = changes.map(|found| found.deleted).unwrap_or_default();
+    if let Some(order) = options.order {
+        sort_by_history(
+            root_path,
+            options.revision.as_deref(),
+            order,
+            &mut inventory.content_files,
+        )
+        .map_err(|err| FailureKind::Git.wrap(err))?;
+    }
+    logging::log(LogEvent::Phase {
+        phase: Phase::Walk,
+        elapsed: started.elapsed(),
+    });
+    Ok(inventory)
+}
+fn walk_project(
+    root_path: &Path,
+    options: &InventoryOptions,
+    filter: ContentFilter<'_>,
+    exclusions: &Gitignore,
+    output_path: Option<&Path>,
+) -> io::Result<ProjectInventory> {
     let mut tree_entries = Vec::new();
     let mut content_files = Vec::new();
-    for entry_result in build_walk(root_path) {
-        let entry = entry_result.map_err(|err| io::Error::other(format!("遍历目录失败: {err}")))?;
+    let mut attributes = Attributes::default();
+    let submodules = load_submodules(root_path)?;
+    for entry_result in build_walk(root_path, exclusions, &submodules, options.submodules) {
+        let entry =
+            entry_result.map_err(|err| io::Error::other(tr(Message::WalkFailed, &[&err])))?;
         let path = entry.path();
         let relative_path = path.strip_prefix(root_path).map_err(|err| {
-            io::Error::other(format!("无法计算相对路径: {}: {err}", path.display()))
+            io::Error::other(tr(Message::RelativePathFailed, &[&path.display(), &err]))
         })?;
         if relative_path.as_os_str().is_empty() {
+            attributes.load_directory(root_path, relative_path)?;
             continue;
         }
-        let file_type = entry
-            .file_type()
-            .ok_or_else(|| io::Error::other(format!("无法获取文件类型: {}", path.display())))?;
+        let file_type = entry.file_type().ok_or_else(|| {
+            io::Error::other(tr(Message::FileTypeUnavailable, &[&path.display()]))
+        })?;
+        let excluded = if exclusions
+            .matched(relative_path, file_type.is_dir())
+            .is_ignore()
+        {
+            Some(Rule::Builtin)
+        } else if !file_type.is_dir() && is_previous_output(path, relative_path, output_path)? {
+            Some(Rule::Generated)
+        } else {
+            None
+        };
         if file_type.is_dir() {
-            push_directory(path, relative_path, &mut tree_entries)?;
+            attributes.load_directory(root_path, relative_path)?;
+            push_directory(path, relative_path, excluded, &mut tree_entries)?;
+            if let (Some(submodule), Some(tree_entry)) =
+                (submodules.get(relative_path), tree_entries.last_mut())
+            {
+                tree_entry.submodule = Some(submodule.clone());
+            }
         } else {
-            push_file(path, relative_path, &mut tree_entries, &mut content_files)?;
+            let metadata = entry.metadata().map_err(|err| {
+                io::Error::other(tr(Message::MetadataFailed, &[&path.display(), &err]))
+            })?;
+            let candidate = FileCandidate {
+                path,
+                relative_path,
+                size: metadata.len(),
+                modified: metadata.modified().ok(),
+                git_object: None,
+                attributes: attributes.lookup(root_path, relative_path),
+            };
+            push_file(
+                candidate,
+                options,
+                filter,
+                excluded,
+                &mut tree_entries,
+                &mut content_files,
+            )?;
+        }
+    }
+    if logging::enabled(LogLevel::Verbose) {
+        log_skipped_paths(root_path, &tree_entries, &submodules)?;
+    }
+    if let Some(tracked) = filter.tracked {
+        tree_entries.retain(|entry| {
+            let keep = entry.kind != TreeEntryKind::Directory
+                || tracked.contains_under(&entry.relative_path);
+            if !keep {
+                log_decision(&entry.relative_path, Decision::Excluded(Rule::Untracked));
+            }
+            keep
+        });
+    }
+    if options.show_ignored_directories {
+        tree_entries = insert_ignored_directories(root_path, tree_entries)?;
+    }
+    mark_last_siblings(&mut tree_entries);
+    Ok(ProjectInventory {
+        root_path: root_path.to_path_buf(),
+        root_name: paths::root_name(root_path)?,
+        tree_entries,
+        content_files,
+        deleted_files: Vec::new(),
+    })
+}
+fn revision_project(
+    root_path: &Path,
+    reference: &str,
+    options: &InventoryOptions,
+    filter: ContentFilter<'_>,
+    exclusions: &Gitignore,
+    listed: Option<&BTreeSet<PathBuf>>,
+) -> io::Result<ProjectInventory> {
+    let mut tree_entries = Vec::new();
+    let mut content_files = Vec::new();
+    let mut directories = ParentDirectories::default();
+    let files = revision_files(root_path, reference)?;
+    let mut attributes = Attributes::default();
+    for file in files
+        .iter()
+        .filter(|file| is_attributes_file(&file.relative_path))
+    {
+        let text = read_object(root_path, &file.object)?;
+        attributes.add_source(
+            root_path,
+            file.relative_path.parent().unwrap_or_else(|| Path::new("")),
+            &String::from_utf8_lossy(&text),
+        )?;
+    }
+    for file in files {
+        let relative_path = file.relative_path.as_path();
+        if listed.is_some_and(|paths| !paths.contains(relative_path)) {
+            continue;
+        }
+        if let Some(rule) = file.ignored {
+            log_decision(
+                &paths::relative_path_text(relative_path)?,
+                Decision::Excluded(rule),
+            );
+            continue;
+        }
+        if directories.push_parents(root_path, relative_path, exclusions, &mut tree_entries)? {
+            continue;
+        }
+        let excluded = exclusions
+            .matched(relative_path, false)
+            .is_ignore()
+            .then_some(Rule::Builtin);
+        let path = root_path.join(relative_path);
+        let candidate = FileCandidate {
+            path: &path,
+            relative_path,
+            size: file.size,
+            modified: None,
+            git_object: Some(file.object),
+            attributes: attributes.lookup(root_path, relative_path),
+        };
+        push_file(
+            candidate,
+            options,
+            filter,
+            excluded,
+            &mut tree_entries,
+            &mut content_files,
+        )?;
+    }
+    mark_last_siblings(&mut tree_entries);
+    Ok(ProjectInventory {
+        root_path: root_path.to_path_buf(),
+        root_name: paths::root_name(root_path)?,
+        tree_entries,
+        content_files,
+        deleted_files: Vec::new(),
+    })
+}
+fn listed_project(
+    root_path: &Path,
+    files: &BTreeSet<PathBuf>,
+    options: &InventoryOptions,
+    filter: ContentFilter<'_>,
+    exclusions: &Gitignore,
+    output_path: Option<&Path>,
+) -> io::Result<ProjectInventory> {
+    let mut tree_entries = Vec::new();
+    let mut content_files = Vec::new();
+    let mut directories = ParentDirectories::default();
+    let mut attribute_directories = BTreeSet::new();
+    let mut attributes = Attributes::default();
+    let root = fs::canonicalize(root_path)?;
+    for relative_path in files {
+        let path = root_path.join(relative_path);
+        let metadata_failed = |err: io::Error| {
+            io::Error::new(
+                err.kind(),
+                tr(Message::MetadataFailed, &[&path.display(), &err]),
+            )
+        };
+        let resolved = match fs::canonicalize(&path) {
+            Ok(resolved) => resolved,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                log_decision(
+                    &paths::relative_path_text(relative_path)?,
+                    Decision::Excluded(Rule::Missing),
+                );
+                continue;
+            }
+            Err(err) => return Err(metadata_failed(err)),
+        };
+        if !resolved.starts_with(&root) {
+            log_decision(
+                &paths::relative_path_text(relative_path)?,
+                Decision::Excluded(Rule::Symlink),
+            );
+            continue;
+        }
+        if !fs::metadata(&path).map_err(metadata_failed)?.is_file() {
+            log_decision(
+                &paths::relative_path_text(relative_path)?,
+                Decision::Excluded(Rule::Irregular),
+            );
+            continue;
+        }
+        let metadata = fs::symlink_metadata(&path).map_err(metadata_failed)?;
+        let mut parents: Vec<&Path> = relative_path.ancestors().skip(1).collect();
+        parents.reverse();
+        for parent in parents {
+            if attribute_directories.insert(parent.to_path_buf()) {
+                attributes.load_directory(root_path, parent)?;
+            }
+        }
+        if directories.push_parents(root_path, relative_path, exclusions, &mut tree_entries)? {
+            continue;
         }
+        let excluded = if exclusions.matched(relative_path, false).is_ignore() {
+            Some(Rule::Builtin)
+        } else if is_previous_output(&path, relative_path, output_path)? {
+            Some(Rule::Generated)
+        } else {
+            None
+        };
+        let candidate = FileCandidate {
+            path: &path,
+            relative_path,
+            size: metadata.len(),
+            modified: metadata.modified().ok(),
+            git_object: None,
+            attributes: attributes.lookup(root_path, relative_path),
+        };
+        push_file(
+            candidate,
+            options,
+            filter,
+            excluded,
+            &mut tree_entries,
+            &mut content_files,
+        )?;
     }
+    mark_last_siblings(&mut tree_entries);
     Ok(ProjectInventory {
+        root_path: root_path.to_path_buf(),
         root_name: paths::root_name(root_path)?,
         tree_entries,
         content_files,
+        deleted_files: Vec::new(),
     })
 }
-fn build_walk(root_path: &Path) -> Walk {
+#[derive(Default)]
+struct ParentDirectories {
+    seen: BTreeSet<PathBuf>,
+    excluded: BTreeSet<PathBuf>,
+}
+impl ParentDirectories {
+    fn push_parents(
+        &mut self,
+        root_path: &Path,
+        relative_path: &Path,
+        exclusions: &Gitignore,
+        tree_entries: &mut Vec<TreeEntry>,
+    ) -> io::Result<bool> {
+        let mut parents: Vec<&Path> = relative_path
+            .ancestors()
+            .skip(1)
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .collect();
+        parents.reverse();
+        for parent in parents {
+            if self.seen.insert(parent.to_path_buf()) {
+                let excluded = exclusions.matched(parent, true).is_ignore();
+                if excluded {
+                    self.excluded.insert(parent.to_path_buf());
+                }
+                push_directory(
+                    &root_path.join(parent),
+                    parent,
+                    excluded.then_some(Rule::Builtin),
+                    tree_entries,
+                )?;
+            }
+            if self.excluded.contains(parent) {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+}
+fn build_walk(
+    root_path: &Path,
+    exclusions: &Gitignore,
+    submodules: &BTreeMap<PathBuf, Submodule>,
+    mode: SubmoduleMode,
+) -> Walk {
     let mut builder = WalkBuilder::new(root_path);
     builder.require_git(false);
+    let root = root_path.to_path_buf();
+    let matcher = exclusions.clone();
+    let submodule_paths: BTreeSet<PathBuf> = submodules.keys().cloned().collect();
+    builder.filter_entry(move |entry| {
+        let Ok(relative_path) = entry.path().strip_prefix(&root) else {
+            return true;
+        };
+        let blocked = match mode {
+            SubmoduleMode::Mark => relative_path.parent(),
+            SubmoduleMode::Skip => Some(relative_path),
+            SubmoduleMode::Recurse => None,
+        };
+        if blocked.is_some_and(|path| submodule_paths.contains(path)) {
+            return false;
+        }
+        relative_path.parent().is_none_or(|parent| {
+            parent.as_os_str().is_empty()
+                || !matcher
+                    .matched_path_or_any_parents(parent, true)
+                    .is_ignore()
+        })
+    });
     builder.build()
 }
 fn push_directory(
     path: &Path,
     relative_path: &Path,
+    excluded: Option<Rule>,
     tree_entries: &mut Vec<TreeEntry>,
-) -> AppResult<()> {
+) -> io::Result<()> {
+    let relative_path_text = paths::relative_path_text(relative_path)?;
+    if let Some(rule) = excluded {
+        log_decision(&relative_path_text, Decision::Excluded(rule));
+    }
     tree_entries.push(TreeEntry {
         depth: relative_path.components().count(),
-        name: paths::os_str_to_utf8(path.file_name(), path, "目录名")?.to_owned(),
+        name: paths::os_str_to_utf8(path.file_name(), path, Message::SubjectDirectoryName)?
+            .to_owned(),
         kind: TreeEntryKind::Directory,
+        relative_path: relative_path_text,
+        omission: excluded.map(|_| Omission::Excluded),
+        ..TreeEntry::default()
     });
     Ok(())
 }
 fn push_file(
-    path: &Path,
-    relative_path: &Path,
+    candidate: FileCandidate<'_>,
+    options: &InventoryOptions,
+    filter: ContentFilter<'_>,
+    excluded: Option<Rule>,
     tree_entries: &mut Vec<TreeEntry>,
     content_files: &mut Vec<FileEntry>,
-) -> AppResult<()> {
-    let file_name = paths::os_str_to_utf8(path.file_name(), path, "文件名")?;
-    if is_excluded_file(file_name) {
+) -> io::Result<()> {
+    let FileCandidate {
+        path,
+        relative_path,
+        size,
+        modified,
+        git_object,
+        attributes,
+    } = candidate;
+    let file_name = paths::os_str_to_utf8(path.file_name(), path, Message::SubjectFileName)?;
+    let relative_path_text = paths::relative_path_text(relative_path)?;
+    let untracked = match filter.tracked {
+        Some(tracked) if !tracked.tracked.contains(&relative_path_text) => {
+            if !tracked.untracked.contains(&relative_path_text) {
+                log_decision(&relative_path_text, Decision::Excluded(Rule::Untracked));
+                return Ok(());
+            }
+            true
+        }
+        Some(_) | None => false,
+    };
+    if let Some(rule) = excluded {
+        log_decision(&relative_path_text, Decision::Excluded(rule));
+        tree_entries.push(TreeEntry {
+            depth: relative_path.components().count(),
+            name: file_name.to_owned(),
+            kind: TreeEntryKind::File,
+            relative_path: relative_path_text,
+            omission: Some(Omission::Excluded),
+            untracked,
+            ..TreeEntry::default()
+        });
         return Ok(());
     }
+    let omission = options
+        .max_file_size
+        .is_some_and(|limit| size > limit)
+        .then_some(Omission::TooLarge);
     tree_entries.push(TreeEntry {
         depth: relative_path.components().count(),
         name: file_name.to_owned(),
         kind: TreeEntryKind::File,
+        relative_path: relative_path_text.clone(),
+        bytes: size,
+        omission,
+        untracked,
+        ..TreeEntry::default()
     });
+    if filter
+        .selection
+        .is_some_and(|matcher| !matcher.matched(relative_path, false).is_whitelist())
+    {
+        log_decision(&relative_path_text, Decision::TreeOnly(Rule::Select));
+        return Ok(());
+    }
+    if filter
+        .changed
+        .is_some_and(|changed| !changed.contains(&relative_path_text))
+    {
+        log_decision(&relative_path_text, Decision::TreeOnly(Rule::Unchanged));
+        return Ok(());
+    }
+    if filter
+        .touched
+        .is_some_and(|touched| !touched.contains(&relative_path_text))
+    {
+        log_decision(&relative_path_text, Decision::TreeOnly(Rule::Untouched));
+        return Ok(());
+    }
+    if filter
+        .modified_after
+        .zip(modified)
+        .is_some_and(|(cutoff, mtime)| mtime < cutoff)
+    {
+        log_decision(&relative_path_text, Decision::TreeOnly(Rule::Stale));
+        return Ok(());
+    }
+    if options.generated_files == GeneratedFiles::Omit && attributes.linguist_label().is_some() {
+        log_decision(&relative_path_text, Decision::TreeOnly(Rule::Linguist));
+        return Ok(());
+    }
+    let file_omission = omission.or_else(|| attributes.binary.then_some(Omission::Binary));
+    log_decision(
+        &relative_path_text,
+        match file_omission {
+            Some(Omission::Binary) => Decision::TreeOnly(Rule::Binary),
+            Some(Omission::TooLarge | Omission::Undecodable | Omission::Excluded) => {
+                Decision::TreeOnly(Rule::Size)
+            }
+            None => Decision::Included,
+        },
+    );
+    let code_block_language = match git_object.as_deref() {
+        Some(object) => options.languages.detect_with(path, |blob_path| {
+            read_object(blob_path, object).map(|bytes| parse_shebang(&bytes))
+        })?,
+        None => options.languages.detect(path)?,
+    };
     content_files.push(FileEntry {
         absolute_path: path.to_path_buf(),
-        relative_path: paths::relative_path_text(relative_path)?,
-        code_block_language: paths::code_block_language(path)?,
+        relative_path: relative_path_text,
+        code_block_language,
+        size,
+        omission: file_omission,
+        git_object,
+        untracked,
+        declared_encoding: attributes.encoding,
+        permalink: None,
     });
     Ok(())
 }
-fn is_excluded_file(file_name: &str) -> bool {
-    file_name == OUTPUT_FILENAME || EXTRA_EXCLUDED_FILES.contains(&file_name)
+fn log_decision(path: &str, decision: Decision) {
+    logging::log(LogEvent::Path { path, decision });
+}
+fn log_skipped_paths(
+    root_path: &Path,
+    tree_entries: &[TreeEntry],
+    submodules: &BTreeMap<PathBuf, Submodule>,
+) -> io::Result<()> {
+    let walked: BTreeSet<&str> = tree_entries
+        .iter()
+        .map(|entry| entry.relative_path.as_str())
+        .collect();
+    let directories = iter::once("").chain(
+        tree_entries
+            .iter()
+            .filter(|entry| {
+                entry.kind == TreeEntryKind::Directory
+                    && entry.omission.is_none()
+                    && entry.submodule.is_none()
+            })
+            .map(|entry| entry.relative_path.as_str()),
+    );
+    let root = fs::canonicalize(root_path)?;
+    for directory in directories {
+        let rules = IgnoreRules::load(&root.join(directory))?;
+        for child_result in fs::read_dir(root.join(directory))? {
+            let child = child_result?;
+            let name = child.file_name().to_string_lossy().into_owned();
+            let child_path = Path::new(directory).join(&name);
+            let relative_path = paths::relative_path_text(&child_path)?;
+            if walked.contains(relative_path.as_str()) {
+                continue;
+            }
+            let is_dir = child.file_type()?.is_dir();
+            let rule = if submodules.contains_key(&child_path) {
+                Rule::Submodule
+            } else {
+                match rules.matched(&root.join(&child_path), is_dir) {
+                    Match::Ignore(_) => Rule::Gitignore,
+                    Match::None if name.starts_with('.') => Rule::Hidden,
+                    Match::None | Match::Whitelist(_) => continue,
+                }
+            };
+            log_decision(&relative_path, Decision::Excluded(rule));
+        }
+    }
+    Ok(())
+}
+pub(crate) fn build_selection(
+    root_path: &Path,
+    patterns: &[String],
+) -> io::Result<Option<Override>> {
+    if patterns.is_empty() {
+        return Ok(None);
+    }
+    let mut builder = OverrideBuilder::new(root_path);
+    for pattern in patterns {
+        builder
+            .add(pattern)
+            .map_err(|err| io::Error::other(tr(Message::InvalidSelectPattern, &[pattern, &err])))?;
+    }
+    let matcher = builder
+        .build()
+        .map_err(|err| io::Error::other(tr(Message::SelectionBuildFailed, &[&err])))?;
+    Ok(Some(matcher))
+}
+pub(crate) fn build_exclusions(root_path: &Path, patterns: &[String]) -> io::Result<Gitignore> {
+    let mut builder = GitignoreBuilder::new(root_path);
+    for pattern in DEFAULT_EXCLUSIONS
+        .into_iter()
+        .chain(patterns.iter().map(String::as_str))
+    {
+        builder.add_line(None, pattern).map_err(|err| {
+            io::Error::other(tr(Message::InvalidExcludePattern, &[&pattern, &err]))
+        })?;
+    }
+    builder
+        .build()
+        .map_err(|err| io::Error::other(tr(Message::ExclusionBuildFailed, &[&err])))
+}
+fn insert_ignored_directories(
+    root_path: &Path,
+    tree_entries: Vec<TreeEntry>,
+) -> io::Result<Vec<TreeEntry>> {
+    let visited: BTreeSet<String> = tree_entries
+        .iter()
+        .filter(|entry| entry.kind == TreeEntryKind::Directory)
+        .map(|entry| entry.relative_path.clone())
+        .collect();
+    let root = fs::canonicalize(root_path)?;
+    let mut entries = Vec::with_capacity(tree_entries.len());
+    let mut open_directories = vec![(0, String::new())];
+    for entry in tree_entries {
+        while open_directories
+            .last()
+            .is_some_and(|&(depth, _)| depth >= entry.depth)
+        {
+            if let Some((depth, relative_path)) = open_directories.pop() {
+                push_ignored_children(&root, depth, &relative_path, &visited, &mut entries)?;
+            }
+        }
+        if entry.kind == TreeEntryKind::Directory {
+            open_directories.push((entry.depth, entry.relative_path.clone()));
+        }
+        entries.push(entry);
+    }
+    while let Some((depth, relative_path)) = open_directories.pop() {
+        push_ignored_children(&root, depth, &relative_path, &visited, &mut entries)?;
+    }
+    Ok(entries)
+}
+fn push_ignored_children(
+    root: &Path,
+    depth: usize,
+    relative_path: &str,
+    visited: &BTreeSet<String>,
+    entries: &mut Vec<TreeEntry>,
+) -> io::Result<()> {
+    let directory = root.join(relative_path);
+    let rules = IgnoreRules::load(&directory)?;
+    let read_dir = fs::read_dir(&directory).map_err(|err| {
+        io::Error::new(
+            err.kind(),
+            tr(Message::ReadDirFailed, &[&directory.display()]),
+        )
+    })?;
+    let mut ignored = Vec::new();
+    for child_result in read_dir {
+        let child = child_result?;
+        let child_path = child.path();
+        let name = paths::os_str_to_utf8(
+            child_path.file_name(),
+            &child_path,
+            Message::SubjectDirectoryName,
+        )?;
+        if name == GIT_DIRECTORY || !child.file_type()?.is_dir() {
+            continue;
+        }
+        let child_relative_path = paths::relative_path_text(&Path::new(relative_path).join(name))?;
+        if !visited.contains(&child_relative_path) {
+            let kind = if name.starts_with('.') && !rules.matched(&child_path, true).is_ignore() {
+                TreeEntryKind::Hidden
+            } else {
+                TreeEntryKind::Ignored
+            };
+            ignored.push(TreeEntry {
+                depth: depth.saturating_add(1),
+                name: name.to_owned(),
+                kind,
+                relative_path: child_relative_path,
+                ..TreeEntry::default()
+            });
+        }
+    }
+    ignored.sort_by(|left, right| left.name.cmp(&right.name));
+    entries.append(&mut ignored);
+    Ok(())
+}
+pub(crate) fn mark_last_siblings(entries: &mut [TreeEntry]) {
+    let mut sibling_follows: Vec<bool> = Vec::new();
+    for entry in entries.iter_mut().rev() {
+        sibling_follows.resize(entry.depth, false);
+        if let Some(follows) = sibling_follows.last_mut() {
+            entry.is_last = !*follows;
+            *follows = true;
+        }
+    }
+}
+pub(crate) fn output_relative_path(
+    root_path: &Path,
+    output_path: Option<&Path>,
+) -> Option<PathBuf> {
+    let root = fs::canonicalize(root_path).ok()?;
+    let output = fs::canonicalize(output_path?).ok()?;
+    output.strip_prefix(root).ok().map(Path::to_path_buf)
+}
+pub(crate) fn is_previous_output(
+    path: &Path,
+    relative_path: &Path,
+    output_path: Option<&Path>,
+) -> io::Result<bool> {
+    if output_path == Some(relative_path) {
+        return Ok(true);
+    }
+    let known_output = path
+        .extension()
+        .and_then(OsStr::to_str)
+        .is_some_and(|extension| {
+            OUTPUT_EXTENSIONS
+                .iter()
+                .any(|known| extension.eq_ignore_ascii_case(known))
+        });
+    if !known_output {
+        return Ok(false);
+    }
+    let mut head = Vec::new();
+    fs::File::open(path)
+        .and_then(|file| file.take(SIGNATURE_SCAN_LIMIT).read_to_end(&mut head))
+        .map_err(|err| {
+            io::Error::new(err.kind(), tr(Message::ReadFileFailed, &[&path.display()]))
+        })?;
+    Ok(has_generator_signature(&String::from_utf8_lossy(&head)))
 }
 #[cfg(test)]
 mod tests;
@@ -1,13 +1,12 @@
-use super::{TreeEntryKind, collect_project_inventory, is_excluded_file};
-use crate::test_support::{TestDir, must};
-use std::path::PathBuf;
-#[test]
-fn generated_and_repository_metadata_files_are_excluded() {
-    assert!(is_excluded_file("project.md"));
-    assert!(is_excluded_file("README.md"));
-    assert!(is_excluded_file("LICENSE"));
-    assert!(!is_excluded_file("Cargo.toml"));
-}
+use super::{InventoryOptions, Omission, TreeEntryKind, collect_project_inventory};
+use crate::{
+    attributes::GeneratedFiles,
+    changes::Tracking,
+    modified::ModifiedWindow,
+    test_support::{TestDir, must},
+};
+use core::time::Duration;
+use std::{fs::File, path::PathBuf, time::SystemTime};
 #[test]
 fn visible_files_are_collected_with_tree_metadata() {
     let dir = must(TestDir::new("inventory-visible"), "创建测试目录失败");
@@ -20,7 +19,10 @@ fn visible_files_are_collected_with_tree_metadata() {
         "写入 Cargo.toml 失败",
     );
     must(dir.create_dir("empty"), "创建空目录失败");
-    let inventory = must(collect_project_inventory(dir.path()), "收集项目清单失败");
+    let inventory = must(
+        collect_project_inventory(dir.path(), &InventoryOptions::default()),
+        "收集项目清单失败",
+    );
     let main_path = relative_path(["src", "main.rs"]);
     assert!(
         inventory
@@ -46,20 +48,141 @@ fn visible_files_are_collected_with_tree_metadata() {
         inventory
             .content_files
             .iter()
-            .any(|file| { file.relative_path == main_path && file.code_block_language == "rs" })
+            .any(|file| { file.relative_path == main_path && file.code_block_language == "rust" })
     );
 }
 #[test]
-fn excluded_files_are_not_listed_or_collected() {
+fn excluded_files_are_marked_in_tree_but_not_collected() {
     let dir = must(TestDir::new("inventory-excluded"), "创建测试目录失败");
     must(dir.write_str("README.md", "readme"), "写入 README 失败");
     must(dir.write_str("LICENSE", "license"), "写入 LICENSE 失败");
-    must(dir.write_str("project.md", "old"), "写入旧输出文件失败");
-    let inventory = must(collect_project_inventory(dir.path()), "收集项目清单失败");
-    assert!(inventory.tree_entries.is_empty());
+    must(
+        dir.write_str("old.md", "---\ngenerator: proj2md\n---\n"),
+        "写入旧输出文件失败",
+    );
+    let inventory = must(
+        collect_project_inventory(dir.path(), &InventoryOptions::default()),
+        "收集项目清单失败",
+    );
+    let mut excluded: Vec<_> = inventory
+        .tree_entries
+        .iter()
+        .filter(|entry| entry.omission == Some(Omission::Excluded))
+        .map(|entry| entry.name.as_str())
+        .collect();
+    excluded.sort_unstable();
+    assert_eq!(excluded, ["LICENSE", "README.md", "old.md"]);
+    assert_eq!(inventory.tree_entries.len(), 3);
     assert!(inventory.content_files.is_empty());
 }
 #[test]
+fn only_generated_outputs_are_excluded_by_name_or_marker() {
+    let dir = must(TestDir::new("inventory-outputs"), "创建测试目录失败");
+    must(
+        dir.write_str("docs/project.md", "# Project\n"),
+        "写入项目文档失败",
+    );
+    let output = must(dir.write_str("out/bundle.md", "plain"), "写入输出文件失败");
+    must(
+        dir.write_str(
+            "out/bundle.xml",
+            "<project root=\"x\">\n  <metadata generator=\"proj2md\">",
+        ),
+        "写入 XML 输出失败",
+    );
+    let options = InventoryOptions {
+        output_path: Some(output),
+        ..InventoryOptions::default()
+    };
+    let inventory = must(
+        collect_project_inventory(dir.path(), &options),
+        "收集项目清单失败",
+    );
+    let collected: Vec<_> = inventory
+        .content_files
+        .iter()
+        .map(|file| file.relative_path.clone())
+        .collect();
+    assert_eq!(collected, [relative_path(["docs", "project.md"])]);
+}
+#[test]
+fn signed_outputs_are_recognised_only_by_output_extension() {
+    let dir = must(TestDir::new("inventory-signed"), "创建测试目录失败");
+    must(
+        dir.write_str(
+            "copy/project.md",
+            "<!-- generator: proj2md -->\n\n## 1. 目录结构\n",
+        ),
+        "写入旧输出文件失败",
+    );
+    must(
+        dir.write_str(
+            "notes.txt",
+            "<!-- generator: proj2md -->\n\n## 1. 目录结构\n",
+        ),
+        "写入普通文件失败",
+    );
+    let inventory = must(
+        collect_project_inventory(dir.path(), &InventoryOptions::default()),
+        "收集项目清单失败",
+    );
+    let collected: Vec<_> = inventory
+        .content_files
+        .iter()
+        .map(|file| file.relative_path.as_str())
+        .collect();
+    assert_eq!(collected, ["notes.txt"]);
+}
+#[test]
+fn default_exclusions_apply_only_at_the_root() {
+    let dir = must(TestDir::new("inventory-nested-readme"), "创建测试目录失败");
+    must(dir.write_str("README.md", "readme"), "写入 README 失败");
+    must(
+        dir.write_str("crates/core/README.md", "core"),
+        "写入子 crate README 失败",
+    );
+    let inventory = must(
+        collect_project_inventory(dir.path(), &InventoryOptions::default()),
+        "收集项目清单失败",
+    );
+    let collected: Vec<_> = inventory
+        .content_files
+        .iter()
+        .map(|file| file.relative_path.clone())
+        .collect();
+    assert_eq!(collected, [relative_path(["crates", "core", "README.md"])]);
+}
+#[test]
+fn exclusions_can_be_extended_and_negated() {
+    let dir = must(TestDir::new("inventory-exclude"), "创建测试目录失败");
+    must(dir.write_str("README.md", "readme"), "写入 README 失败");
+    must(dir.write_str("vendor/lib.rs", "x"), "写入 vendor 文件失败");
+    let options = InventoryOptions {
+        exclusions: vec!["!/README.md".to_owned(), "vendor/".to_owned()],
+        ..InventoryOptions::default()
+    };
+    let inventory = must(
+        collect_project_inventory(dir.path(), &options),
+        "收集项目清单失败",
+    );
+    let collected: Vec<_> = inventory
+        .content_files
+        .iter()
+        .map(|file| file.relative_path.as_str())
+        .collect();
+    assert_eq!(collected, ["README.md"]);
+    assert!(inventory.tree_entries.iter().any(|entry| {
+        entry.kind == TreeEntryKind::Directory
+            && entry.name == "vendor"
+            && entry.omission == Some(Omission::Excluded)
+    }));
+    assert!(!has_tree_entry(
+        &inventory.tree_entries,
+        TreeEntryKind::File,
+        "lib.rs"
+    ));
+}
+#[test]
 fn gitignore_rules_are_honored() {
     let dir = must(TestDir::new("inventory-ignore"), "创建测试目录失败");
     must(
@@ -72,7 +195,10 @@ fn gitignore_rules_are_honored() {
         "写入忽略目录文件失败",
     );
     must(dir.write_str("visible.txt", "visible"), "写入可见文件失败");
-    let inventory = must(collect_project_inventory(dir.path()), "收集项目清单失败");
+    let inventory = must(
+        collect_project_inventory(dir.path(), &InventoryOptions::default()),
+        "收集项目清单失败",
+    );
     assert!(
         inventory
             .content_files
@@ -91,6 +217,321 @@ fn gitignore_rules_are_honored() {
         "ignored_dir"
     ));
 }
+#[test]
+fn ignored_directories_can_be_listed_as_single_entries() {
+    let dir = must(TestDir::new("inventory-show-ignored"), "创建测试目录失败");
+    must(
+        dir.write_str(".gitignore", "target/\n.venv/\n"),
+        "写入 gitignore 失败",
+    );
+    must(
+        dir.write_str("target/debug/app", "binary"),
+        "写入构建产物失败",
+    );
+    must(
+        dir.write_str("src/main.rs", "fn main() {}\n"),
+        "写入 main.rs 失败",
+    );
+    must(dir.create_dir(".cache"), "创建隐藏目录失败");
+    must(dir.create_dir(".venv"), "创建被忽略的隐藏目录失败");
+    let options = InventoryOptions {
+        show_ignored_directories: true,
+        ..InventoryOptions::default()
+    };
+    let inventory = must(
+        collect_project_inventory(dir.path(), &options),
+        "收集项目清单失败",
+    );
+    let ignored: Vec<_> = inventory
+        .tree_entries
+        .iter()
+        .filter(|entry| entry.kind == TreeEntryKind::Ignored)
+        .map(|entry| (entry.depth, entry.name.as_str()))
+        .collect();
+    assert_eq!(ignored, [(1, ".venv"), (1, "target")]);
+    let hidden: Vec<_> = inventory
+        .tree_entries
+        .iter()
+        .filter(|entry| entry.kind == TreeEntryKind::Hidden)
+        .map(|entry| entry.name.as_str())
+        .collect();
+    assert_eq!(hidden, [".cache"]);
+    assert!(
+        !inventory
+            .content_files
+            .iter()
+            .any(|file| file.relative_path.contains("debug"))
+    );
+}
+#[test]
+fn tracked_only_drops_untracked_files_unless_marked() {
+    let dir = must(TestDir::new("inventory-tracked"), "创建测试目录失败");
+    must(
+        dir.write_str("src/main.rs", "fn main() {}\n"),
+        "写入 main.rs 失败",
+    );
+    must(
+        dir.write_str(".gitignore", "*.tmp\n"),
+        "写入 .gitignore 失败",
+    );
+    must(dir.git(&["init", "-q"]), "初始化仓库失败");
+    must(dir.git(&["add", "-A"]), "暂存文件失败");
+    must(dir.git(&["commit", "-q", "-m", "init"]), "提交失败");
+    must(dir.write_str("notes.txt", "todo\n"), "写入 notes.txt 失败");
+    must(dir.write_str("scratch/a.txt", "a\n"), "写入 scratch 失败");
+    must(dir.write_str("cache.tmp", "x\n"), "写入 cache.tmp 失败");
+    let tracked_only = InventoryOptions {
+        tracking: Some(Tracking::TrackedOnly),
+        ..InventoryOptions::default()
+    };
+    let inventory = must(
+        collect_project_inventory(dir.path(), &tracked_only),
+        "收集已跟踪文件失败",
+    );
+    let mut paths: Vec<_> = inventory
+        .tree_entries
+        .iter()
+        .map(|entry| entry.relative_path.as_str())
+        .collect();
+    paths.sort_unstable();
+    assert_eq!(paths, ["src", "src/main.rs"]);
+    let with_untracked = InventoryOptions {
+        tracking: Some(Tracking::IncludeUntracked),
+        ..InventoryOptions::default()
+    };
+    let marked = must(
+        collect_project_inventory(dir.path(), &with_untracked),
+        "收集未跟踪文件失败",
+    );
+    let mut files: Vec<_> = marked
+        .content_files
+        .iter()
+        .map(|file| (file.relative_path.as_str(), file.untracked))
+        .collect();
+    files.sort_unstable();
+    assert_eq!(
+        files,
+        [
+            ("notes.txt", true),
+            ("scratch/a.txt", true),
+            ("src/main.rs", false)
+        ]
+    );
+}
+#[test]
+fn gitattributes_omit_generated_files_and_mark_binary_ones() {
+    let dir = must(TestDir::new("inventory-attributes"), "创建测试目录失败");
+    must(
+        dir.write_str(
+            ".gitattributes",
+            "*.min.js linguist-generated\nassets/*.txt binary\n*.csv working-tree-encoding=Shift_JIS\n",
+        ),
+        "写入 .gitattributes 失败",
+    );
+    must(dir.write_str("app.min.js", "a()\n"), "写入 app.min.js 失败");
+    must(
+        dir.write_str("assets/logo.txt", "logo\n"),
+        "写入 logo.txt 失败",
+    );
+    must(dir.write_str("data.csv", "a,b\n"), "写入 data.csv 失败");
+    let inventory = must(
+        collect_project_inventory(dir.path(), &InventoryOptions::default()),
+        "收集项目清单失败",
+    );
+    let mut files: Vec<_> = inventory
+        .content_files
+        .iter()
+        .map(|file| {
+            (
+                file.relative_path.as_str(),
+                file.omission,
+                file.declared_encoding.as_deref(),
+            )
+        })
+        .collect();
+    files.sort_unstable_by_key(|&(path, _, _)| path);
+    assert_eq!(
+        files,
+        [
+            ("assets/logo.txt", Some(Omission::Binary), None),
+            ("data.csv", None, Some("Shift_JIS")),
+        ]
+    );
+    assert!(has_tree_entry(
+        &inventory.tree_entries,
+        TreeEntryKind::File,
+        "app.min.js"
+    ));
+    let included = must(
+        collect_project_inventory(
+            dir.path(),
+            &InventoryOptions {
+                generated_files: GeneratedFiles::Include,
+                ..InventoryOptions::default()
+            },
+        ),
+        "收集生成文件失败",
+    );
+    assert_eq!(included.content_files.len(), 3);
+}
+#[test]
+fn modified_window_keeps_stale_files_in_the_tree_only() {
+    let dir = must(TestDir::new("inventory-modified"), "创建测试目录失败");
+    must(
+        dir.write_str("fresh.rs", "fn fresh() {}\n"),
+        "写入 fresh.rs 失败",
+    );
+    let stale = must(
+        dir.write_str("stale.rs", "fn stale() {}\n"),
+        "写入 stale.rs 失败",
+    );
+    let old = SystemTime::now() - Duration::from_hours(240);
+    must(
+        File::options()
+            .write(true)
+            .open(&stale)
+            .and_then(|file| file.set_modified(old)),
+        "修改 stale.rs 时间失败",
+    );
+    let inventory = must(
+        collect_project_inventory(
+            dir.path(),
+            &InventoryOptions {
+                modified: ModifiedWindow::parse_within("3d"),
+                ..InventoryOptions::default()
+            },
+        ),
+        "按修改时间收集文件失败",
+    );
+    assert!(has_tree_entry(
+        &inventory.tree_entries,
+        TreeEntryKind::File,
+        "stale.rs"
+    ));
+    assert_eq!(
+        inventory
+            .content_files
+            .iter()
+            .map(|file| file.relative_path.as_str())
+            .collect::<Vec<_>>(),
+        ["fresh.rs"]
+    );
+}
+#[test]
+fn files_from_builds_the_inventory_from_the_listed_files() {
+    let dir = must(TestDir::new("inventory-files-from"), "创建测试目录失败");
+    must(
+        dir.write_str("src/main.rs", "fn main() {}\n"),
+        "写入 main.rs 失败",
+    );
+    must(
+        dir.write_str("src/skip.rs", "fn skip() {}\n"),
+        "写入 skip.rs 失败",
+    );
+    must(dir.write_str(".env", "TOKEN=1\n"), "写入 .env 失败");
+    must(
+        dir.write_bytes("assets/logo.png", &[0x89, b'P', b'N', b'G', 0, 0]),
+        "写入 logo.png 失败",
+    );
+    let list = must(
+        dir.write_str("list.txt", "src/main.rs\n.env\nassets/logo.png\n"),
+        "写入文件列表失败",
+    );
+    let inventory = must(
+        collect_project_inventory(
+            dir.path(),
+            &InventoryOptions {
+                files_from: Some(list),
+                ..InventoryOptions::default()
+            },
+        ),
+        "按文件列表收集失败",
+    );
+    assert_eq!(
+        inventory
+            .tree_entries
+            .iter()
+            .map(|entry| entry.relative_path.clone())
+            .collect::<Vec<_>>(),
+        [
+            ".env".to_owned(),
+            "assets".to_owned(),
+            relative_path(["assets", "logo.png"]),
+            "src".to_owned(),
+            relative_path(["src", "main.rs"]),
+        ]
+    );
+    assert_eq!(inventory.content_files.len(), 3);
+    let stale = must(
+        dir.write_str("stale.txt", "src/gone.rs\nsrc\nsrc/main.rs\n"),
+        "写入文件列表失败",
+    );
+    let skipped = must(
+        collect_project_inventory(
+            dir.path(),
+            &InventoryOptions {
+                files_from: Some(stale),
+                ..InventoryOptions::default()
+            },
+        ),
+        "列表中缺失的文件和目录应当被跳过",
+    );
+    assert_eq!(
+        skipped
+            .content_files
+            .iter()
+            .map(|file| file.relative_path.clone())
+            .collect::<Vec<_>>(),
+        [relative_path(["src", "main.rs"])]
+    );
+}
+#[cfg(unix)]
+#[test]
+fn files_from_skips_symlinks_that_leave_the_root() {
+    let dir = must(
+        TestDir::new("inventory-files-from-link"),
+        "创建测试目录失败",
+    );
+    let outside = must(
+        TestDir::new("inventory-files-from-secret"),
+        "创建测试目录失败",
+    );
+    let secret = must(
+        outside.write_str("secret.txt", "secret\n"),
+        "写入 secret.txt 失败",
+    );
+    let inside = must(dir.write_str("a.txt", "a\n"), "写入 a.txt 失败");
+    must(
+        std::os::unix::fs::symlink(&secret, dir.path().join("leak.txt")),
+        "创建外部链接失败",
+    );
+    must(
+        std::os::unix::fs::symlink(&inside, dir.path().join("alias.txt")),
+        "创建内部链接失败",
+    );
+    let list = must(
+        outside.write_str("list.txt", "leak.txt\nalias.txt\na.txt\n"),
+        "写入文件列表失败",
+    );
+    let inventory = must(
+        collect_project_inventory(
+            dir.path(),
+            &InventoryOptions {
+                files_from: Some(list),
+                ..InventoryOptions::default()
+            },
+        ),
+        "按文件列表收集失败",
+    );
+    assert_eq!(
+        inventory
+            .content_files
+            .iter()
+            .map(|file| file.relative_path.as_str())
+            .collect::<Vec<_>>(),
+        ["a.txt", "alias.txt"]
+    );
+}
 fn has_tree_entry(entries: &[super::TreeEntry], kind: TreeEntryKind, name: &str) -> bool {
     entries
         .iter()
@@ -0,0 +1,285 @@
+use crate::{
+    front_matter::{BundleMetadata, GENERATOR},
+    inventory::{FileEntry, TreeEntry},
+    patch::Commit,
+    render::Renderer,
+    stats::ProjectSummary,
+    tree::TreeOptions,
+};
+use std::io;
+pub(crate) struct JsonRenderer<'writer, W> {
+    writer: &'writer mut W,
+    tree: TreeOptions,
+    first_item: bool,
+}
+impl<'writer, W> JsonRenderer<'writer, W>
+where
+    W: io::Write,
+{
+    pub(crate) const fn new(writer: &'writer mut W, tree: TreeOptions) -> Self {
+        Self {
+            writer,
+            tree,
+            first_item: true,
+        }
+    }
+    fn item_separator(&mut self) -> io::Result<()> {
+        if self.first_item {
+            self.first_item = false;
+            self.writer.write_all(b"\n")
+        } else {
+            self.writer.write_all(b",\n")
+        }
+    }
+}
+impl<W> Renderer for JsonRenderer<'_, W>
+where
+    W: io::Write,
+{
+    fn begin_document(&mut self, root_name: &str) -> io::Result<()> {
+        write!(
+            self.writer,
+            "{{\n  \"generator\": \"{GENERATOR}\",\n  \"root\": \"{}\"",
+            escape_json(root_name)
+        )
+    }
+    fn front_matter(&mut self, metadata: &BundleMetadata) -> io::Result<()> {
+        let options: Vec<String> = metadata
+            .options
+            .iter()
+            .map(|option| {
+                let values: Vec<String> = option
+                    .values
+                    .iter()
+                    .map(|value| format!("\"{}\"", escape_json(value)))
+                    .collect();
+                let value = if option.repeated {
+                    format!("[{}]", values.join(", "))
+                } else {
+                    values.join(", ")
+                };
+                format!("\"{}\": {value}", escape_json(&option.name))
+            })
+            .collect();
+        let git = metadata.git.as_ref().map_or_else(
+            || "null".to_owned(),
+            |git| {
+                format!(
+                    "{{\"branch\": \"{}\", \"commit\": \"{}\", \"dirty\": {}}}",
+                    escape_json(&git.branch),
+                    escape_json(&git.commit),
+                    git.dirty
+                )
+            },
+        );
+        write!(
+            self.writer,
+            ",\n  \"metadata\": {{\"generator\": \"{GENERATOR}\", \"version\": \"{}\", \"generated_at\": \"{}\", \"options\": {{{}}}, \"files\": {}, \"total_bytes\": {}, \"content_hash\": \"{}\", \"git\": {git}}}",
+            escape_json(&metadata.version),
+            metadata.generated_at,
+            options.join(", "),
+            metadata.file_count,
+            metadata.total_bytes,
+            metadata.content_hash
+        )
+    }
+    fn project_summary(&mut self, summary: &ProjectSummary) -> io::Result<()> {
+        let languages: Vec<String> = summary
+            .languages
+            .iter()
+            .map(|language| {
+                format!(
+                    "{{\"language\": \"{}\", \"files\": {}, \"lines\": {}}}",
+                    escape_json(&language.language),
+                    language.files,
+                    language.lines
+                )
+            })
+            .collect();
+        let largest: Vec<String> = summary
+            .largest_files
+            .iter()
+            .map(|file| {
+                format!(
+                    "{{\"path\": \"{}\", \"bytes\": {}}}",
+                    escape_json(&file.path),
+                    file.bytes
+                )
+            })
+            .collect();
+        let encodings: Vec<String> = summary
+            .encodings
+            .iter()
+            .map(|encoding| {
+                format!(
+                    "{{\"encoding\": \"{}\", \"files\": {}}}",
+                    escape_json(&encoding.encoding),
+                    encoding.files
+                )
+            })
+            .collect();
+        write!(
+            self.writer,
+            ",\n  \"summary\": {{\"files\": {}, \"bytes\": {}, \"lines\": {}, \"tokens\": {}, \"binary\": {}, \"too_large\": {}, \"undecodable\": {}, \"languages\": [{}], \"largest\": [{}], \"encodings\": [{}]}}",
+            summary.file_count,
+            summary.total_bytes,
+            summary.total_lines,
+            summary.total_tokens,
+            summary.binary_files,
+            summary.too_large_files,
+            summary.undecodable_files,
+            languages.join(", "),
+            largest.join(", "),
+            encodings.join(", ")
+        )
+    }
+    fn commit_log(&mut self, reference: &str, commits: &[Commit]) -> io::Result<()> {
+        let items: Vec<String> = commits
+            .iter()
+            .map(|commit| {
+                format!(
+                    "{{\"hash\": \"{}\", \"author\": \"{}\", \"date\": \"{}\", \"subject\": \"{}\"}}",
+                    escape_json(&commit.hash),
+                    escape_json(&commit.author),
+                    escape_json(&commit.date),
+                    escape_json(&commit.subject)
+                )
+            })
+            .collect();
+        write!(
+            self.writer,
+            ",\n  \"diff_base\": \"{}\",\n  \"commits\": [{}]",
+            escape_json(reference),
+            items.join(", ")
+        )
+    }
+    fn begin_tree(&mut self) -> io::Result<()> {
+        self.first_item = true;
+        self.writer.write_all(b",\n  \"tree\": [")
+    }
+    fn tree_entry(&mut self, entry: &TreeEntry) -> io::Result<()> {
+        self.item_separator()?;
+        let kind = entry.kind.name();
+        let untracked = if entry.untracked {
+            ", \"untracked\": true"
+        } else {
+            ""
+        };
+        let submodule = entry
+            .submodule
+            .as_ref()
+            .map_or_else(String::new, |submodule| {
+                format!(
+                    ", \"submodule\": {{\"url\": \"{}\", \"commit\": {}}}",
+                    escape_json(&submodule.url),
+                    submodule
+                        .commit
+                        .as_ref()
+                        .map_or_else(|| "null".to_owned(), |commit| format!("\"{commit}\""))
+                )
+            });
+        let annotations = self.tree.entry_annotations(entry);
+        let stats: String = [
+            annotations
+                .bytes
+                .map(|bytes| format!(", \"bytes\": {bytes}")),
+            annotations
+                .lines
+                .map(|lines| format!(", \"lines\": {lines}")),
+            annotations
+                .tokens
+                .map(|tokens| format!(", \"tokens\": {tokens}")),
+            annotations
+                .omission
+                .map(|omission| format!(", \"omission\": \"{}\"", omission.name())),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        write!(
+            self.writer,
+            "    {{\"depth\": {}, \"name\": \"{}\", \"kind\": \"{kind}\"{untracked}{stats}{submodule}}}",
+            entry.depth,
+            escape_json(&entry.name)
+        )
+    }
+    fn end_tree(&mut self) -> io::Result<()> {
+        close_array(self.writer, self.first_item)
+    }
+    fn begin_contents(&mut self) -> io::Result<()> {
+        self.first_item = true;
+        self.writer.write_all(b",\n  \"files\": [")
+    }
+    fn begin_file(&mut self, file: &FileEntry) -> io::Result<()> {
+        self.item_separator()?;
+        let untracked = if file.untracked {
+            "\"untracked\": true, "
+        } else {
+            ""
+        };
+        let permalink = file.permalink.as_deref().map_or_else(String::new, |url| {
+            format!("\"permalink\": \"{}\", ", escape_json(url))
+        });
+        write!(
+            self.writer,
+            "    {{\"path\": \"{}\", \"language\": \"{}\", {untracked}{permalink}\"content\": \"",
+            escape_json(&file.relative_path),
+            escape_json(&file.code_block_language)
+        )
+    }
+    fn content_chunk(&mut self, chunk: &str) -> io::Result<()> {
+        self.writer.write_all(escape_json(chunk).as_bytes())
+    }
+    fn file_diff(&mut self, diff: &str) -> io::Result<()> {
+        write!(self.writer, "\", \"diff\": \"{}", escape_json(diff))
+    }
+    fn end_file(&mut self) -> io::Result<()> {
+        self.writer.write_all(b"\"}")
+    }
+    fn end_contents(&mut self) -> io::Result<()> {
+        close_array(self.writer, self.first_item)
+    }
+    fn deleted_files(&mut self, paths: &[String]) -> io::Result<()> {
+        let items: Vec<String> = paths
+            .iter()
+            .map(|path| format!("\"{}\"", escape_json(path)))
+            .collect();
+        write!(self.writer, ",\n  \"deleted\": [{}]", items.join(", "))
+    }
+    fn end_document(&mut self) -> io::Result<()> {
+        self.writer.write_all(b"\n}\n")
+    }
+}
+fn close_array<W>(writer: &mut W, empty: bool) -> io::Result<()>
+where
+    W: io::Write,
+{
+    if empty {
+        writer.write_all(b"]")
+    } else {
+        writer.write_all(b"\n  ]")
+    }
+}
+pub(crate) fn escape_json(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            control if control < ' ' => {
+                let code = u32::from(control);
+                escaped.push_str("\\u00");
+                for digit in [code >> 4_u32, code & 0xF] {
+                    escaped.push(char::from_digit(digit, 16).unwrap_or('0'));
+                }
+            }
+            other => escaped.push(other),
+        }
+    }
+    escaped
+}
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,77 @@
+use super::{JsonRenderer, escape_json};
+use crate::{
+    inventory::{InventoryOptions, collect_project_inventory},
+    render::{RenderOptions, render_inventory, render_project},
+    test_support::{TestDir, must},
+    tree::{TreeAnnotations, TreeOptions},
+};
+#[test]
+fn escape_json_escapes_quotes_and_control_characters() {
+    assert_eq!(escape_json("a\"b\\c\nd\u{1}"), "a\\\"b\\\\c\\nd\\u0001");
+}
+#[test]
+fn project_json_lists_tree_and_files() {
+    let dir = must(TestDir::new("json-project"), "创建测试目录失败");
+    must(
+        dir.write_str("src/main.rs", "fn main() {}\n"),
+        "写入 main.rs 失败",
+    );
+    let mut output = Vec::new();
+    must(
+        render_project(
+            dir.path(),
+            &mut JsonRenderer::new(&mut output, TreeOptions::default()),
+        ),
+        "写入 JSON 文档失败",
+    );
+    let document = must(String::from_utf8(output), "JSON 文档必须是 UTF-8");
+    assert!(
+        document.starts_with(
+            "{\n  \"generator\": \"proj2md\",\n  \"root\": \"proj2md-test-json-project-"
+        )
+    );
+    assert!(document.contains("{\"depth\": 1, \"name\": \"src\", \"kind\": \"directory\"}"));
+    assert!(document.contains("\"language\": \"rust\", \"content\": \"fn main() {}\\n\"}"));
+    assert!(document.ends_with("\n  ]\n}\n"));
+}
+#[test]
+fn tree_entries_carry_annotations_and_exclusions() {
+    let dir = must(TestDir::new("json-tree-annotations"), "创建测试目录失败");
+    must(dir.write_str("a.md", "one\ntwo"), "写入 a.md 失败");
+    must(dir.write_str("b.md", "skipped"), "写入 b.md 失败");
+    let inventory_options = InventoryOptions {
+        exclusions: vec!["b.md".to_owned()],
+        ..InventoryOptions::default()
+    };
+    let inventory = must(
+        collect_project_inventory(dir.path(), &inventory_options),
+        "收集项目清单失败",
+    );
+    let tree = TreeOptions {
+        annotations: must(
+            TreeAnnotations::parse("size,lines").ok_or("注解列表无效"),
+            "解析注解失败",
+        ),
+        ..TreeOptions::default()
+    };
+    let options = RenderOptions {
+        tree,
+        ..RenderOptions::default()
+    };
+    let mut output = Vec::new();
+    must(
+        render_inventory(
+            &inventory,
+            &options,
+            &mut JsonRenderer::new(&mut output, tree),
+        ),
+        "写入文档失败",
+    );
+    let document = must(String::from_utf8(output), "文档必须是 UTF-8");
+    assert!(document.contains(
+        "{\"depth\": 1, \"name\": \"a.md\", \"kind\": \"file\", \"bytes\": 7, \"lines\": 2}"
+    ));
+    assert!(document.contains(
+        "{\"depth\": 1, \"name\": \"b.md\", \"kind\": \"file\", \"omission\": \"excluded\"}"
+    ));
+}
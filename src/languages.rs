@@ -0,0 +1,238 @@
+use crate::{
+    i18n::{Message, tr},
+    paths,
+};
+use alloc::collections::BTreeMap;
+use std::{
+    fs,
+    io::{self, Read as _},
+    path::Path,
+};
+const SHEBANG_SCAN_LIMIT: u64 = 256;
+const EXTENSION_LANGUAGES: [(&str, &str); 85] = [
+    ("bash", "bash"),
+    ("bat", "batch"),
+    ("c", "c"),
+    ("cc", "cpp"),
+    ("cfg", "ini"),
+    ("cjs", "javascript"),
+    ("clj", "clojure"),
+    ("cmake", "cmake"),
+    ("cmd", "batch"),
+    ("cpp", "cpp"),
+    ("cs", "csharp"),
+    ("css", "css"),
+    ("csv", "csv"),
+    ("cts", "typescript"),
+    ("cxx", "cpp"),
+    ("dart", "dart"),
+    ("diff", "diff"),
+    ("dockerfile", "dockerfile"),
+    ("erl", "erlang"),
+    ("ex", "elixir"),
+    ("exs", "elixir"),
+    ("fish", "fish"),
+    ("fs", "fsharp"),
+    ("go", "go"),
+    ("gql", "graphql"),
+    ("gradle", "groovy"),
+    ("graphql", "graphql"),
+    ("groovy", "groovy"),
+    ("h", "c"),
+    ("hh", "cpp"),
+    ("hpp", "cpp"),
+    ("hs", "haskell"),
+    ("htm", "html"),
+    ("html", "html"),
+    ("hxx", "cpp"),
+    ("ini", "ini"),
+    ("java", "java"),
+    ("js", "javascript"),
+    ("json", "json"),
+    ("jsonc", "jsonc"),
+    ("jsx", "jsx"),
+    ("kt", "kotlin"),
+    ("kts", "kotlin"),
+    ("less", "less"),
+    ("lua", "lua"),
+    ("m", "objectivec"),
+    ("markdown", "markdown"),
+    ("md", "markdown"),
+    ("mjs", "javascript"),
+    ("mk", "makefile"),
+    ("ml", "ocaml"),
+    ("mm", "objectivec"),
+    ("mts", "typescript"),
+    ("nim", "nim"),
+    ("patch", "diff"),
+    ("php", "php"),
+    ("pl", "perl"),
+    ("pm", "perl"),
+    ("proto", "protobuf"),
+    ("ps1", "powershell"),
+    ("psm1", "powershell"),
+    ("py", "python"),
+    ("pyi", "python"),
+    ("r", "r"),
+    ("rb", "ruby"),
+    ("rs", "rust"),
+    ("sass", "sass"),
+    ("scala", "scala"),
+    ("scss", "scss"),
+    ("sh", "sh"),
+    ("sql", "sql"),
+    ("svelte", "svelte"),
+    ("svg", "xml"),
+    ("swift", "swift"),
+    ("tf", "hcl"),
+    ("toml", "toml"),
+    ("ts", "typescript"),
+    ("tsx", "tsx"),
+    ("txt", "text"),
+    ("vue", "vue"),
+    ("xml", "xml"),
+    ("yaml", "yaml"),
+    ("yml", "yaml"),
+    ("zig", "zig"),
+    ("zsh", "zsh"),
+];
+const FILENAME_LANGUAGES: [(&str, &str); 21] = [
+    (".bash_aliases", "bash"),
+    (".bash_profile", "bash"),
+    (".bashrc", "bash"),
+    (".editorconfig", "ini"),
+    (".gitconfig", "ini"),
+    (".profile", "bash"),
+    (".zprofile", "zsh"),
+    (".zshrc", "zsh"),
+    ("CMakeLists.txt", "cmake"),
+    ("Cargo.lock", "toml"),
+    ("Containerfile", "dockerfile"),
+    ("Dockerfile", "dockerfile"),
+    ("GNUmakefile", "makefile"),
+    ("Gemfile", "ruby"),
+    ("Jenkinsfile", "groovy"),
+    ("Makefile", "makefile"),
+    ("Rakefile", "ruby"),
+    ("Vagrantfile", "ruby"),
+    ("justfile", "just"),
+    ("makefile", "makefile"),
+    ("meson.build", "meson"),
+];
+const INTERPRETER_LANGUAGES: [(&str, &str); 14] = [
+    ("bash", "bash"),
+    ("bun", "javascript"),
+    ("deno", "typescript"),
+    ("fish", "fish"),
+    ("lua", "lua"),
+    ("node", "javascript"),
+    ("perl", "perl"),
+    ("php", "php"),
+    ("pwsh", "powershell"),
+    ("python", "python"),
+    ("Rscript", "r"),
+    ("ruby", "ruby"),
+    ("sh", "sh"),
+    ("zsh", "zsh"),
+];
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub(crate) struct LanguageRegistry {
+    extensions: BTreeMap<String, String>,
+    filenames: BTreeMap<String, String>,
+    interpreters: BTreeMap<String, String>,
+}
+impl LanguageRegistry {
+    pub(crate) fn add_override(&mut self, spec: &str) -> io::Result<()> {
+        let (key, language) = spec
+            .split_once('=')
+            .map(|(key, language)| (key.trim(), language.trim()))
+            .filter(|&(key, _)| !key.is_empty())
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    tr(Message::InvalidLanguageMapping, &[&spec]),
+                )
+            })?;
+        if let Some(extension) = key.strip_prefix("*.") {
+            self.extensions
+                .insert(extension.to_lowercase(), language.to_owned());
+        } else if let Some(interpreter) = key.strip_prefix("#!") {
+            self.interpreters
+                .insert(interpreter.to_owned(), language.to_owned());
+        } else {
+            self.filenames.insert(key.to_owned(), language.to_owned());
+        }
+        Ok(())
+    }
+    pub(crate) fn detect(&self, path: &Path) -> io::Result<String> {
+        self.detect_with(path, read_shebang_interpreter)
+    }
+    pub(crate) fn detect_with<F>(&self, path: &Path, shebang: F) -> io::Result<String>
+    where
+        F: FnOnce(&Path) -> io::Result<Option<String>>,
+    {
+        let file_name = paths::os_str_to_utf8(path.file_name(), path, Message::SubjectFileName)?;
+        if let Some(language) = lookup(&self.filenames, &FILENAME_LANGUAGES, file_name) {
+            return Ok(language);
+        }
+        let extension = paths::extension_text(path)?;
+        let lowercase_extension = extension.to_lowercase();
+        if let Some(language) = lookup(&self.extensions, &EXTENSION_LANGUAGES, &lowercase_extension)
+        {
+            return Ok(language);
+        }
+        if extension.is_empty()
+            && let Some(interpreter) = shebang(path)?
+            && let Some(language) = self.interpreter_language(&interpreter)
+        {
+            return Ok(language);
+        }
+        Ok(extension)
+    }
+    fn interpreter_language(&self, interpreter: &str) -> Option<String> {
+        lookup(&self.interpreters, &INTERPRETER_LANGUAGES, interpreter).or_else(|| {
+            let base = interpreter
+                .trim_end_matches(|character: char| character.is_ascii_digit() || character == '.');
+            lookup(&self.interpreters, &INTERPRETER_LANGUAGES, base)
+        })
+    }
+}
+fn lookup(
+    overrides: &BTreeMap<String, String>,
+    builtin: &[(&str, &str)],
+    key: &str,
+) -> Option<String> {
+    overrides.get(key).cloned().or_else(|| {
+        builtin
+            .iter()
+            .find(|&&(name, _)| name == key)
+            .map(|&(_, language)| language.to_owned())
+    })
+}
+fn read_shebang_interpreter(path: &Path) -> io::Result<Option<String>> {
+    let file = fs::File::open(path).map_err(|err| {
+        io::Error::new(err.kind(), tr(Message::ReadFileFailed, &[&path.display()]))
+    })?;
+    let mut head = Vec::new();
+    file.take(SHEBANG_SCAN_LIMIT).read_to_end(&mut head)?;
+    Ok(parse_shebang(&head))
+}
+pub(crate) fn parse_shebang(head: &[u8]) -> Option<String> {
+    let rest = head.strip_prefix(b"#!")?;
+    let line_end = rest
+        .iter()
+        .position(|&byte| byte == b'\n')
+        .unwrap_or(rest.len());
+    let line = core::str::from_utf8(rest.get(..line_end)?).ok()?;
+    let mut words = line.split_whitespace();
+    let program = words.next()?;
+    let program_name = program.rsplit('/').next()?;
+    if program_name != "env" {
+        return Some(program_name.to_owned());
+    }
+    words
+        .find(|word| !word.starts_with('-') && !word.contains('='))
+        .map(ToOwned::to_owned)
+}
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,71 @@
+use super::{LanguageRegistry, parse_shebang};
+use crate::test_support::{TestDir, must, must_err};
+use std::{io, path::Path};
+fn detect(registry: &LanguageRegistry, path: &Path) -> String {
+    must(registry.detect(path), "识别语言失败")
+}
+#[test]
+fn extensions_map_to_canonical_names() {
+    let registry = LanguageRegistry::default();
+    assert_eq!(detect(&registry, Path::new("src/main.rs")), "rust");
+    assert_eq!(detect(&registry, Path::new("include/api.H")), "c");
+    assert_eq!(detect(&registry, Path::new("config.yml")), "yaml");
+    assert_eq!(
+        detect(&registry, Path::new("data.unknownext")),
+        "unknownext"
+    );
+}
+#[test]
+fn exact_filenames_take_precedence_over_extensions() {
+    let registry = LanguageRegistry::default();
+    assert_eq!(detect(&registry, Path::new("Dockerfile")), "dockerfile");
+    assert_eq!(detect(&registry, Path::new("Makefile")), "makefile");
+    assert_eq!(detect(&registry, Path::new("CMakeLists.txt")), "cmake");
+    assert_eq!(detect(&registry, Path::new(".bashrc")), "bash");
+}
+#[test]
+fn shebang_lines_name_the_interpreter() {
+    assert_eq!(
+        parse_shebang(b"#!/usr/bin/env python3\nprint()"),
+        Some("python3".to_owned())
+    );
+    assert_eq!(parse_shebang(b"#!/bin/bash -e\n"), Some("bash".to_owned()));
+    assert_eq!(
+        parse_shebang(b"#!/usr/bin/env -S deno run\n"),
+        Some("deno".to_owned())
+    );
+    assert_eq!(parse_shebang(b"echo hi\n"), None);
+}
+#[test]
+fn extensionless_scripts_are_tagged_from_shebang() {
+    let dir = must(TestDir::new("languages-shebang"), "创建测试目录失败");
+    let script = must(
+        dir.write_str("bin/deploy", "#!/usr/bin/env python3.12\nprint('hi')\n"),
+        "写入脚本失败",
+    );
+    let plain = must(dir.write_str("NOTES", "just text\n"), "写入文本失败");
+    let registry = LanguageRegistry::default();
+    assert_eq!(detect(&registry, &script), "python");
+    assert_eq!(detect(&registry, &plain), "");
+}
+#[test]
+fn overrides_replace_builtin_mappings() {
+    let mut registry = LanguageRegistry::default();
+    must(registry.add_override("*.h=cpp"), "添加扩展名映射失败");
+    must(
+        registry.add_override("Jenkinsfile = groovy-dsl"),
+        "添加文件名映射失败",
+    );
+    must(registry.add_override("#!python3=py3"), "添加解释器映射失败");
+    assert_eq!(detect(&registry, Path::new("api.h")), "cpp");
+    assert_eq!(detect(&registry, Path::new("Jenkinsfile")), "groovy-dsl");
+    let dir = must(TestDir::new("languages-override"), "创建测试目录失败");
+    let script = must(dir.write_str("run", "#!/usr/bin/python3\n"), "写入脚本失败");
+    assert_eq!(detect(&registry, &script), "py3");
+}
+#[test]
+fn malformed_overrides_are_rejected() {
+    let mut registry = LanguageRegistry::default();
+    let err = must_err(registry.add_override("rust"), "缺少等号必须报错");
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+}
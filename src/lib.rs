@@ -1,16 +1,41 @@
+extern crate alloc;
 mod app;
+mod attributes;
+mod changes;
 mod cli;
 mod clipboard;
 mod config;
 mod content;
-mod errors;
-mod inventory;
+mod dry_run;
+pub mod errors;
+mod explain;
+mod file_list;
+pub mod front_matter;
+mod git;
+pub mod gitmodules;
+mod history;
+mod i18n;
+mod ignore_rules;
+pub mod inventory;
+mod json;
+mod languages;
+mod logging;
 mod markdown;
+mod modified;
 mod output;
+pub mod patch;
 mod paths;
+mod permalink;
+pub mod render;
+mod revision;
+pub mod stats;
+mod template;
 #[cfg(test)]
 mod test_support;
-use std::ffi::OsString;
+mod tokens;
+mod tree;
+mod xml;
+use std::{ffi::OsString, path::Path};
 #[inline]
 pub fn run<I>(args: I) -> errors::AppResult<()>
 where
@@ -18,3 +43,11 @@ where
 {
     app::run(args)
 }
+#[inline]
+pub fn render_project(
+    root_path: &Path,
+    renderer: &mut dyn render::Renderer,
+) -> errors::AppResult<()> {
+    paths::validate_root_path(root_path).map_err(|err| errors::FailureKind::Path.wrap(err))?;
+    render::render_project(root_path, renderer)
+}
@@ -0,0 +1,241 @@
+use crate::{
+    i18n::{Locale, Message, console_locale, tr_in},
+    inventory::Omission,
+    json::escape_json,
+};
+use core::{cell::Cell, time::Duration};
+thread_local! {
+    static LOG_LEVEL: Cell<u8> = const { Cell::new(LogLevel::Normal.code()) };
+    static LOG_FORMAT: Cell<u8> = const { Cell::new(LogFormat::Text.code()) };
+}
+#[derive(Clone, Copy, Debug, Default, Eq, Ord, PartialEq, PartialOrd)]
+pub(crate) enum LogLevel {
+    Quiet,
+    #[default]
+    Normal,
+    Verbose,
+    Trace,
+}
+impl LogLevel {
+    pub(crate) const fn louder(self) -> Self {
+        match self {
+            Self::Quiet | Self::Normal => Self::Verbose,
+            Self::Verbose | Self::Trace => Self::Trace,
+        }
+    }
+    const fn code(self) -> u8 {
+        match self {
+            Self::Quiet => 0,
+            Self::Normal => 1,
+            Self::Verbose => 2,
+            Self::Trace => 3,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub(crate) enum LogFormat {
+    #[default]
+    Text,
+    Jsonl,
+}
+impl LogFormat {
+    pub(crate) fn parse(name: &str) -> Option<Self> {
+        match name {
+            "text" => Some(Self::Text),
+            "jsonl" => Some(Self::Jsonl),
+            _ => None,
+        }
+    }
+    const fn code(self) -> u8 {
+        match self {
+            Self::Text => 0,
+            Self::Jsonl => 1,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum Rule {
+    Gitignore,
+    Hidden,
+    Builtin,
+    Size,
+    Select,
+    Binary,
+    Undecodable,
+    Generated,
+    Unchanged,
+    Untracked,
+    Linguist,
+    Submodule,
+    Untouched,
+    Stale,
+    Unlisted,
+    Symlink,
+    Missing,
+    Irregular,
+}
+impl Rule {
+    pub(crate) const fn name(self) -> &'static str {
+        match self {
+            Self::Gitignore => "gitignore",
+            Self::Hidden => "hidden",
+            Self::Builtin => "builtin",
+            Self::Size => "size",
+            Self::Select => "select",
+            Self::Binary => "binary",
+            Self::Undecodable => "undecodable",
+            Self::Generated => "generated",
+            Self::Unchanged => "unchanged",
+            Self::Untracked => "untracked",
+            Self::Linguist => "linguist",
+            Self::Submodule => "submodule",
+            Self::Untouched => "untouched",
+            Self::Stale => "stale",
+            Self::Unlisted => "unlisted",
+            Self::Symlink => "symlink",
+            Self::Missing => "missing",
+            Self::Irregular => "irregular",
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum Decision {
+    Included,
+    Excluded(Rule),
+    TreeOnly(Rule),
+}
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum Phase {
+    Walk,
+    Read,
+    Write,
+}
+impl Phase {
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Walk => "walk",
+            Self::Read => "read",
+            Self::Write => "write",
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum LogEvent<'event> {
+    Path {
+        path: &'event str,
+        decision: Decision,
+    },
+    Content {
+        path: &'event str,
+        encoding: Option<&'static str>,
+        omission: Option<Omission>,
+    },
+    Phase {
+        phase: Phase,
+        elapsed: Duration,
+    },
+}
+impl LogEvent<'_> {
+    const fn level(self) -> LogLevel {
+        match self {
+            Self::Path { .. } | Self::Phase { .. } => LogLevel::Verbose,
+            Self::Content { .. } => LogLevel::Trace,
+        }
+    }
+}
+pub(crate) fn configure(level: LogLevel, format: LogFormat) {
+    LOG_LEVEL.set(level.code());
+    LOG_FORMAT.set(format.code());
+}
+pub(crate) fn enabled(level: LogLevel) -> bool {
+    LOG_LEVEL.get() >= level.code()
+}
+pub(crate) fn log(event: LogEvent<'_>) {
+    if !enabled(event.level()) {
+        return;
+    }
+    let format = if LOG_FORMAT.get() == LogFormat::Jsonl.code() {
+        LogFormat::Jsonl
+    } else {
+        LogFormat::Text
+    };
+    eprintln!("{}", format_event(event, format, console_locale()));
+}
+pub(crate) fn format_event(event: LogEvent<'_>, format: LogFormat, locale: Locale) -> String {
+    match format {
+        LogFormat::Text => text_event(event, locale),
+        LogFormat::Jsonl => json_event(event),
+    }
+}
+fn text_event(event: LogEvent<'_>, locale: Locale) -> String {
+    match event {
+        LogEvent::Path { path, decision } => match decision {
+            Decision::Included => tr_in(locale, Message::LogIncluded, &[&path]),
+            Decision::Excluded(rule) => tr_in(locale, Message::LogExcluded, &[&path, &rule.name()]),
+            Decision::TreeOnly(rule) => tr_in(locale, Message::LogTreeOnly, &[&path, &rule.name()]),
+        },
+        LogEvent::Content {
+            path,
+            encoding,
+            omission,
+        } => match (omission, encoding) {
+            (Some(Omission::Binary), _) => tr_in(locale, Message::LogBinary, &[&path]),
+            (Some(Omission::TooLarge), _) => {
+                tr_in(locale, Message::LogTreeOnly, &[&path, &Rule::Size.name()])
+            }
+            (Some(Omission::Excluded), _) => tr_in(
+                locale,
+                Message::LogExcluded,
+                &[&path, &Rule::Builtin.name()],
+            ),
+            (Some(Omission::Undecodable), _) | (None, None) => {
+                tr_in(locale, Message::LogUndecodable, &[&path])
+            }
+            (None, Some(name)) => tr_in(locale, Message::LogDecoded, &[&path, &name]),
+        },
+        LogEvent::Phase { phase, elapsed } => tr_in(
+            locale,
+            Message::LogPhase,
+            &[&phase.name(), &elapsed.as_millis()],
+        ),
+    }
+}
+fn json_event(event: LogEvent<'_>) -> String {
+    match event {
+        LogEvent::Path { path, decision } => {
+            let (name, rule) = match decision {
+                Decision::Included => ("included", None),
+                Decision::Excluded(rule) => ("excluded", Some(rule)),
+                Decision::TreeOnly(rule) => ("tree_only", Some(rule)),
+            };
+            let rule_field = rule
+                .map(|matched| format!(", \"rule\": \"{}\"", matched.name()))
+                .unwrap_or_default();
+            format!(
+                "{{\"event\": \"path\", \"path\": \"{}\", \"decision\": \"{name}\"{rule_field}}}",
+                escape_json(path)
+            )
+        }
+        LogEvent::Content {
+            path,
+            encoding,
+            omission,
+        } => {
+            let decision = omission.map_or("decoded", Omission::name);
+            let encoding_field = encoding
+                .map(|name| format!(", \"encoding\": \"{name}\""))
+                .unwrap_or_default();
+            format!(
+                "{{\"event\": \"content\", \"path\": \"{}\", \"decision\": \"{decision}\"{encoding_field}}}",
+                escape_json(path)
+            )
+        }
+        LogEvent::Phase { phase, elapsed } => format!(
+            "{{\"event\": \"phase\", \"phase\": \"{}\", \"elapsed_ms\": {}}}",
+            phase.name(),
+            elapsed.as_millis()
+        ),
+    }
+}
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,62 @@
+use super::{Decision, LogEvent, LogFormat, LogLevel, Phase, Rule, format_event};
+use crate::{i18n::Locale, inventory::Omission};
+use core::time::Duration;
+#[test]
+fn verbosity_flags_raise_the_level_step_by_step() {
+    assert_eq!(LogLevel::Normal.louder(), LogLevel::Verbose);
+    assert_eq!(LogLevel::Verbose.louder(), LogLevel::Trace);
+    assert_eq!(LogLevel::Trace.louder(), LogLevel::Trace);
+    assert!(LogLevel::Quiet < LogLevel::Normal);
+}
+#[test]
+fn path_decisions_name_the_rule() {
+    let excluded = LogEvent::Path {
+        path: "target/debug",
+        decision: Decision::Excluded(Rule::Gitignore),
+    };
+    assert_eq!(
+        format_event(excluded, LogFormat::Jsonl, Locale::ZhCn),
+        "{\"event\": \"path\", \"path\": \"target/debug\", \"decision\": \"excluded\", \"rule\": \"gitignore\"}"
+    );
+    assert_eq!(
+        format_event(excluded, LogFormat::Text, Locale::En),
+        "excluded: target/debug (rule: gitignore)"
+    );
+    let included = LogEvent::Path {
+        path: "src/main.rs",
+        decision: Decision::Included,
+    };
+    assert_eq!(
+        format_event(included, LogFormat::Text, Locale::ZhCn),
+        "包含: src/main.rs"
+    );
+}
+#[test]
+fn content_and_phase_events_report_details() {
+    let decoded = LogEvent::Content {
+        path: "notes.txt",
+        encoding: Some("GBK"),
+        omission: None,
+    };
+    assert_eq!(
+        format_event(decoded, LogFormat::Jsonl, Locale::En),
+        "{\"event\": \"content\", \"path\": \"notes.txt\", \"decision\": \"decoded\", \"encoding\": \"GBK\"}"
+    );
+    let binary = LogEvent::Content {
+        path: "logo.png",
+        encoding: None,
+        omission: Some(Omission::Binary),
+    };
+    assert_eq!(
+        format_event(binary, LogFormat::Text, Locale::En),
+        "binary: logo.png"
+    );
+    let phase = LogEvent::Phase {
+        phase: Phase::Walk,
+        elapsed: Duration::from_millis(42),
+    };
+    assert_eq!(
+        format_event(phase, LogFormat::Jsonl, Locale::En),
+        "{\"event\": \"phase\", \"phase\": \"walk\", \"elapsed_ms\": 42}"
+    );
+}
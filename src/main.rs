@@ -3,7 +3,7 @@ use mimalloc::MiMalloc;
 static GLOBAL: MiMalloc = MiMalloc;
 fn main() {
     if let Err(err) = proj2md::run(std::env::args_os()) {
-        eprintln!("错误: {err}");
-        std::process::exit(1);
+        eprintln!("{}", proj2md::errors::error_report(&err));
+        std::process::exit(i32::from(err.exit_code()));
     }
 }
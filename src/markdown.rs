@@ -1,48 +1,331 @@
 use crate::{
-    content::read_file_content,
-    errors::AppResult,
-    inventory::{FileEntry, ProjectInventory, TreeEntryKind, collect_project_inventory},
+    front_matter::{BundleMetadata, GENERATOR, front_matter_yaml},
+    i18n::{Locale, Message, tr_in},
+    inventory::{FileEntry, Omission, TreeEntry},
+    patch::{Commit, HEAD},
+    render::Renderer,
+    stats::ProjectSummary,
+    tree::{TreeFormatter, TreeOptions, format_size, group_digits, omission_label},
 };
-use std::{io, path::Path};
-pub(crate) fn write_project_markdown<W>(root_path: &Path, writer: &mut W) -> AppResult<()>
+use std::io;
+const MIN_FENCE_LEN: usize = 3;
+const HEADING_SPECIAL_CHARS: [char; 12] =
+    ['\\', '`', '*', '_', '[', ']', '<', '>', '#', '~', '|', '&'];
+pub(crate) struct MarkdownRenderer<'writer, W> {
+    writer: &'writer mut W,
+    root_name: String,
+    tree: TreeFormatter,
+    revision: Option<String>,
+    locale: Locale,
+    signed: bool,
+    pending_separator: bool,
+    language: String,
+    content: String,
+    diff: String,
+}
+impl<'writer, W> MarkdownRenderer<'writer, W>
 where
     W: io::Write,
 {
-    let inventory = collect_project_inventory(root_path)?;
-    write_directory_tree(&inventory, writer)?;
-    write_file_contents(&inventory.content_files, writer)
+    pub(crate) const fn new(
+        writer: &'writer mut W,
+        tree_options: TreeOptions,
+        revision: Option<String>,
+        locale: Locale,
+    ) -> Self {
+        Self {
+            writer,
+            root_name: String::new(),
+            tree: TreeFormatter::new(tree_options, locale),
+            revision,
+            locale,
+            signed: false,
+            pending_separator: false,
+            language: String::new(),
+            content: String::new(),
+            diff: String::new(),
+        }
+    }
+    fn write_signature(&mut self) -> io::Result<()> {
+        if self.signed {
+            return Ok(());
+        }
+        self.signed = true;
+        writeln!(self.writer, "<!-- generator: {GENERATOR} -->\n")
+    }
 }
-fn write_directory_tree<W>(inventory: &ProjectInventory, writer: &mut W) -> AppResult<()>
+impl<W> Renderer for MarkdownRenderer<'_, W>
 where
     W: io::Write,
 {
-    writer.write_all("## 1. 目录结构\n\n".as_bytes())?;
-    writeln!(writer, "{}/", inventory.root_name)?;
-    for entry in &inventory.tree_entries {
-        let indent = "    ".repeat(entry.depth);
-        match entry.kind {
-            TreeEntryKind::Directory => writeln!(writer, "{indent}{}/", entry.name)?,
-            TreeEntryKind::File => writeln!(writer, "{indent}{}", entry.name)?,
+    fn begin_document(&mut self, root_name: &str) -> io::Result<()> {
+        root_name.clone_into(&mut self.root_name);
+        Ok(())
+    }
+    fn front_matter(&mut self, metadata: &BundleMetadata) -> io::Result<()> {
+        self.signed = true;
+        self.writer
+            .write_all(front_matter_yaml(metadata).as_bytes())
+    }
+    fn project_summary(&mut self, summary: &ProjectSummary) -> io::Result<()> {
+        self.write_signature()?;
+        self.writer
+            .write_all(summary_markdown(summary, self.locale).as_bytes())
+    }
+    fn commit_log(&mut self, reference: &str, commits: &[Commit]) -> io::Result<()> {
+        self.write_signature()?;
+        let target = self.revision.as_deref().unwrap_or(HEAD);
+        self.writer
+            .write_all(commit_log_markdown(reference, target, commits, self.locale).as_bytes())
+    }
+    fn begin_tree(&mut self) -> io::Result<()> {
+        self.write_signature()?;
+        self.pending_separator = true;
+        writeln!(
+            self.writer,
+            "{}\n",
+            tr_in(self.locale, Message::TreeHeading, &[])
+        )?;
+        writeln!(self.writer, "{}/", self.root_name)
+    }
+    fn tree_entry(&mut self, entry: &TreeEntry) -> io::Result<()> {
+        writeln!(self.writer, "{}", self.tree.line(entry))
+    }
+    fn end_tree(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+    fn begin_contents(&mut self) -> io::Result<()> {
+        self.write_signature()?;
+        if self.pending_separator {
+            self.pending_separator = false;
+            self.writer.write_all(b"\n")?;
         }
+        writeln!(
+            self.writer,
+            "{}\n",
+            tr_in(self.locale, Message::ContentsHeading, &[])
+        )
+    }
+    fn begin_file(&mut self, file: &FileEntry) -> io::Result<()> {
+        let marker = if file.untracked {
+            format!(" {}", tr_in(self.locale, Message::UntrackedMarker, &[]))
+        } else {
+            String::new()
+        };
+        let heading = escape_heading(&file.relative_path);
+        if let Some(url) = file.permalink.as_deref() {
+            writeln!(self.writer, "### [{heading}]({url}){marker}")?;
+        } else {
+            writeln!(self.writer, "### {heading}{marker}")?;
+        }
+        file.code_block_language.clone_into(&mut self.language);
+        self.content.clear();
+        self.diff.clear();
+        Ok(())
+    }
+    fn content_chunk(&mut self, chunk: &str) -> io::Result<()> {
+        self.content.push_str(chunk);
+        Ok(())
+    }
+    fn file_diff(&mut self, diff: &str) -> io::Result<()> {
+        self.diff.push_str(diff);
+        Ok(())
+    }
+    fn end_file(&mut self) -> io::Result<()> {
+        write_code_block(self.writer, &self.content, &self.language)?;
+        if !self.diff.is_empty() {
+            write_code_block(self.writer, &self.diff, "diff")?;
+        }
+        Ok(())
+    }
+    fn end_contents(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+    fn deleted_files(&mut self, paths: &[String]) -> io::Result<()> {
+        self.write_signature()?;
+        if self.pending_separator {
+            self.pending_separator = false;
+            self.writer.write_all(b"\n")?;
+        }
+        writeln!(
+            self.writer,
+            "{}\n",
+            tr_in(self.locale, Message::DeletedHeading, &[])
+        )?;
+        for path in paths {
+            writeln!(self.writer, "- {}", escape_heading(path))?;
+        }
+        Ok(())
+    }
+    fn end_document(&mut self) -> io::Result<()> {
+        self.write_signature()
     }
-    Ok(())
 }
-fn write_file_contents<W>(files: &[FileEntry], writer: &mut W) -> AppResult<()>
+fn write_code_block<W>(writer: &mut W, content: &str, language: &str) -> io::Result<()>
 where
     W: io::Write,
 {
-    writer.write_all("\n## 2. 文件内容\n\n".as_bytes())?;
-    for file in files {
-        writeln!(writer, "### {}", file.relative_path)?;
-        writeln!(writer, "```{}", file.code_block_language)?;
-        let file_content = read_file_content(&file.absolute_path)?;
-        writer.write_all(file_content.as_bytes())?;
-        if !file_content.ends_with('\n') {
-            writer.write_all(b"\n")?;
-        }
-        writer.write_all(b"```\n\n")?;
-    }
-    Ok(())
+    let fence = code_fence(content, language);
+    writeln!(writer, "{fence}{language}")?;
+    writer.write_all(content.as_bytes())?;
+    if !content.ends_with('\n') {
+        writer.write_all(b"\n")?;
+    }
+    writeln!(writer, "{fence}\n")
+}
+pub(crate) fn commit_log_markdown(
+    reference: &str,
+    target: &str,
+    commits: &[Commit],
+    locale: Locale,
+) -> String {
+    let mut lines = vec![
+        tr_in(
+            locale,
+            Message::CommitLogHeading,
+            &[&escape_heading(reference), &escape_heading(target)],
+        ),
+        String::new(),
+    ];
+    if commits.is_empty() {
+        lines.push(tr_in(locale, Message::CommitLogEmpty, &[]));
+    }
+    lines.extend(commits.iter().map(|commit| {
+        format!(
+            "- `{}` {} {}: {}",
+            commit.hash,
+            commit.date,
+            escape_heading(&commit.author),
+            escape_heading(&commit.subject)
+        )
+    }));
+    lines.push(String::new());
+    lines.push(String::new());
+    lines.join("\n")
+}
+pub(crate) fn summary_markdown(summary: &ProjectSummary, locale: Locale) -> String {
+    let omitted = [
+        (Omission::Binary, summary.binary_files),
+        (Omission::TooLarge, summary.too_large_files),
+        (Omission::Undecodable, summary.undecodable_files),
+    ]
+    .map(|(omission, count)| {
+        format!(
+            "{} {}",
+            omission_label(omission, locale),
+            group_digits(count)
+        )
+    });
+    let encodings: Vec<String> = summary
+        .encodings
+        .iter()
+        .map(|encoding| format!("{} ({})", encoding.encoding, group_digits(encoding.files)))
+        .collect();
+    let mut lines = vec![
+        tr_in(locale, Message::SummaryHeading, &[]),
+        String::new(),
+        tr_in(
+            locale,
+            Message::SummaryFiles,
+            &[&group_digits(summary.file_count)],
+        ),
+        tr_in(
+            locale,
+            Message::SummaryBytes,
+            &[&format_size(summary.total_bytes)],
+        ),
+        tr_in(
+            locale,
+            Message::SummaryLines,
+            &[&group_digits(summary.total_lines)],
+        ),
+        tr_in(
+            locale,
+            Message::SummaryTokens,
+            &[&group_digits(summary.total_tokens)],
+        ),
+        tr_in(locale, Message::SummaryOmitted, &[&omitted.join(", ")]),
+    ];
+    if !encodings.is_empty() {
+        lines.push(tr_in(
+            locale,
+            Message::SummaryEncodings,
+            &[&encodings.join(", ")],
+        ));
+    }
+    if !summary.languages.is_empty() {
+        lines.push(String::new());
+        lines.push(tr_in(locale, Message::SummaryLanguageHeader, &[]));
+        lines.push("| --- | ---: | ---: |".to_owned());
+        lines.extend(summary.languages.iter().map(|language| {
+            let name = if language.language.is_empty() {
+                tr_in(locale, Message::NoLanguage, &[])
+            } else {
+                escape_heading(&language.language)
+            };
+            format!(
+                "| {name} | {} | {} |",
+                group_digits(language.files),
+                group_digits(language.lines)
+            )
+        }));
+    }
+    if !summary.largest_files.is_empty() {
+        lines.push(String::new());
+        lines.push(tr_in(locale, Message::LargestFiles, &[]));
+        lines.push(String::new());
+        lines.extend(
+            summary
+                .largest_files
+                .iter()
+                .enumerate()
+                .map(|(index, file)| {
+                    format!(
+                        "{}. {} ({})",
+                        index.saturating_add(1),
+                        escape_heading(&file.path),
+                        format_size(file.bytes)
+                    )
+                }),
+        );
+    }
+    lines.push(String::new());
+    lines.push(String::new());
+    lines.join("\n")
+}
+pub(crate) fn code_fence(content: &str, info: &str) -> String {
+    let marker = if info.contains('`') { '~' } else { '`' };
+    let length = longest_run(content, marker)
+        .saturating_add(1)
+        .max(MIN_FENCE_LEN);
+    core::iter::repeat_n(marker, length).collect()
+}
+fn longest_run(text: &str, marker: char) -> usize {
+    let mut longest = 0_usize;
+    let mut current = 0_usize;
+    for character in text.chars() {
+        if character == marker {
+            current = current.saturating_add(1);
+            longest = longest.max(current);
+        } else {
+            current = 0;
+        }
+    }
+    longest
+}
+pub(crate) fn escape_heading(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        if HEADING_SPECIAL_CHARS.contains(&character) {
+            escaped.push('\\');
+            escaped.push(character);
+        } else if character.is_control() {
+            escaped.push(' ');
+        } else {
+            escaped.push(character);
+        }
+    }
+    escaped
 }
 #[cfg(test)]
 mod tests;
@@ -1,59 +1,78 @@
-use super::{write_directory_tree, write_file_contents, write_project_markdown};
+use super::{MarkdownRenderer, code_fence, commit_log_markdown, escape_heading, summary_markdown};
 use crate::{
-    inventory::{FileEntry, ProjectInventory, TreeEntry, TreeEntryKind},
+    i18n::Locale,
+    inventory::{FileEntry, TreeEntry, TreeEntryKind},
+    patch::Commit,
+    render::{Renderer as _, render_file_contents, render_project},
+    stats::{EncodingCount, LanguageCount, LargestFile, ProjectSummary},
     test_support::{TestDir, must},
+    tree::TreeOptions,
 };
-#[test]
-fn write_directory_tree_renders_depth_and_kind() {
-    let inventory = ProjectInventory {
-        root_name: "root".to_owned(),
-        tree_entries: vec![
-            TreeEntry {
-                depth: 1,
-                name: "src".to_owned(),
-                kind: TreeEntryKind::Directory,
-            },
-            TreeEntry {
-                depth: 2,
-                name: "main.rs".to_owned(),
-                kind: TreeEntryKind::File,
-            },
-        ],
-        content_files: Vec::new(),
-    };
+use std::path::PathBuf;
+fn render_contents(entries: &[FileEntry]) -> String {
     let mut output = Vec::new();
+    let mut renderer =
+        MarkdownRenderer::new(&mut output, TreeOptions::default(), None, Locale::ZhCn);
     must(
-        write_directory_tree(&inventory, &mut output),
-        "写入目录树失败",
+        render_file_contents(entries, Locale::ZhCn, None, None, &mut renderer),
+        "写入文件内容失败",
     );
+    must(String::from_utf8(output), "文件内容输出必须是 UTF-8")
+}
+#[test]
+fn tree_section_renders_depth_and_kind() {
+    let entries = [
+        TreeEntry {
+            depth: 1,
+            name: "src".to_owned(),
+            kind: TreeEntryKind::Directory,
+            ..TreeEntry::default()
+        },
+        TreeEntry {
+            depth: 2,
+            name: "main.rs".to_owned(),
+            kind: TreeEntryKind::File,
+            ..TreeEntry::default()
+        },
+    ];
+    let mut output = Vec::new();
+    let mut renderer =
+        MarkdownRenderer::new(&mut output, TreeOptions::default(), None, Locale::ZhCn);
+    must(renderer.begin_document("root"), "写入文档开头失败");
+    must(renderer.begin_tree(), "写入目录树标题失败");
+    for entry in &entries {
+        must(renderer.tree_entry(entry), "写入目录树失败");
+    }
+    must(renderer.end_tree(), "结束目录树失败");
     let document = must(String::from_utf8(output), "目录树输出必须是 UTF-8");
     assert_eq!(
         document,
-        "## 1. 目录结构\n\nroot/\n    src/\n        main.rs\n"
+        "<!-- generator: proj2md -->\n\n## 1. 目录结构\n\nroot/\n    src/\n        main.rs\n"
     );
 }
 #[test]
-fn write_file_contents_adds_trailing_newline_before_fence() {
+fn file_contents_add_trailing_newline_before_fence() {
     let dir = must(TestDir::new("markdown-content"), "创建测试目录失败");
     let file = must(dir.write_str("note.txt", "hello"), "写入内容文件失败");
     let entries = [FileEntry {
         absolute_path: file,
         relative_path: "note.txt".to_owned(),
         code_block_language: "txt".to_owned(),
+        size: 0,
+        omission: None,
+        git_object: None,
+        untracked: false,
+        declared_encoding: None,
+        permalink: None,
     }];
-    let mut output = Vec::new();
-    must(
-        write_file_contents(&entries, &mut output),
-        "写入文件内容失败",
-    );
-    let document = must(String::from_utf8(output), "文件内容输出必须是 UTF-8");
+    let document = render_contents(&entries);
     assert_eq!(
         document,
-        "\n## 2. 文件内容\n\n### note.txt\n```txt\nhello\n```\n\n"
+        "<!-- generator: proj2md -->\n\n## 2. 文件内容\n\n### note.txt\n```txt\nhello\n```\n\n"
     );
 }
 #[test]
-fn write_project_markdown_combines_tree_and_content() {
+fn project_markdown_combines_tree_and_content() {
     let dir = must(TestDir::new("markdown-project"), "创建测试目录失败");
     must(
         dir.write_str("src/main.rs", "fn main() {}\n"),
@@ -61,7 +80,10 @@ fn write_project_markdown_combines_tree_and_content() {
     );
     let mut output = Vec::new();
     must(
-        write_project_markdown(dir.path(), &mut output),
+        render_project(
+            dir.path(),
+            &mut MarkdownRenderer::new(&mut output, TreeOptions::default(), None, Locale::ZhCn),
+        ),
         "写入项目文档失败",
     );
     let document = must(String::from_utf8(output), "项目文档必须是 UTF-8");
@@ -70,3 +92,157 @@ fn write_project_markdown_combines_tree_and_content() {
     assert!(document.contains("main.rs"));
     assert!(document.contains("fn main() {}"));
 }
+#[test]
+fn code_fence_outgrows_embedded_backtick_runs() {
+    assert_eq!(code_fence("plain", "rs"), "```");
+    assert_eq!(code_fence("```rust\nfn a() {}\n```\n", "md"), "````");
+    assert_eq!(code_fence("`````", "md"), "``````");
+}
+#[test]
+fn code_fence_falls_back_to_tildes_for_backtick_info_strings() {
+    assert_eq!(code_fence("~~~~\n```\n", "a`b"), "~~~~~");
+    assert_eq!(code_fence("", "a`b"), "~~~");
+}
+#[test]
+fn escape_heading_neutralizes_markdown_syntax() {
+    assert_eq!(
+        escape_heading("docs/[draft]_*notes*#1.md"),
+        "docs/\\[draft\\]\\_\\*notes\\*\\#1.md"
+    );
+    assert_eq!(escape_heading("a\nb`c"), "a b\\`c");
+}
+#[test]
+fn adversarial_content_cannot_close_the_fence_early() {
+    let dir = must(TestDir::new("markdown-adversarial"), "创建测试目录失败");
+    let readme = "# Title\n\n```sh\ncargo build\n```\n\n````\nnested\n````\n";
+    let file = must(dir.write_str("guide.md", readme), "写入 guide.md 失败");
+    let entries = [FileEntry {
+        absolute_path: file,
+        relative_path: "docs/<guide>_v2.md".to_owned(),
+        code_block_language: "md".to_owned(),
+        size: 0,
+        omission: None,
+        git_object: None,
+        untracked: false,
+        declared_encoding: None,
+        permalink: None,
+    }];
+    let document = render_contents(&entries);
+    assert_eq!(
+        document,
+        format!(
+            "<!-- generator: proj2md -->\n\n## 2. 文件内容\n\n### docs/\\<guide\\>\\_v2.md\n`````md\n{readme}`````\n\n"
+        )
+    );
+}
+#[test]
+fn summary_section_lists_totals_languages_and_largest_files() {
+    let summary = ProjectSummary {
+        file_count: 1200,
+        total_bytes: 2048,
+        total_lines: 35,
+        total_tokens: 240,
+        languages: vec![LanguageCount {
+            language: "rust".to_owned(),
+            files: 1200,
+            lines: 35,
+        }],
+        largest_files: vec![LargestFile {
+            path: "src/main.rs".to_owned(),
+            bytes: 2048,
+        }],
+        binary_files: 2,
+        encodings: vec![EncodingCount {
+            encoding: "UTF-8".to_owned(),
+            files: 1198,
+        }],
+        ..ProjectSummary::default()
+    };
+    let section = summary_markdown(&summary, Locale::ZhCn);
+    assert!(section.starts_with("## 项目概览\n\n- 文件数: 1,200\n- 总大小: 2.0 KiB\n"));
+    assert!(section.contains("- 已省略: 二进制文件 2, 文件过大 0, 解码失败 0\n"));
+    assert!(section.contains("- 检测到的编码: UTF-8 (1,198)\n"));
+    assert!(section.contains("| rust | 1,200 | 35 |\n"));
+    assert!(section.ends_with("1. src/main.rs (2.0 KiB)\n\n"));
+}
+#[test]
+fn english_documents_translate_headings_and_summary() {
+    let mut output = Vec::new();
+    let mut renderer = MarkdownRenderer::new(&mut output, TreeOptions::default(), None, Locale::En);
+    must(renderer.begin_document("root"), "写入文档开头失败");
+    must(renderer.begin_tree(), "写入目录树标题失败");
+    must(renderer.end_tree(), "结束目录树失败");
+    must(renderer.begin_contents(), "写入文件内容标题失败");
+    let document = must(String::from_utf8(output), "输出必须是 UTF-8");
+    assert_eq!(
+        document,
+        "<!-- generator: proj2md -->\n\n## 1. Directory Structure\n\nroot/\n\n## 2. File Contents\n\n"
+    );
+    let section = summary_markdown(&ProjectSummary::default(), Locale::En);
+    assert!(section.starts_with("## Project Summary\n\n- Files: 0\n"));
+    assert!(section.contains("- Omitted: binary file 0, too large 0, undecodable 0"));
+}
+#[test]
+fn diff_follows_file_contents_in_its_own_block() {
+    let mut output = Vec::new();
+    let mut renderer =
+        MarkdownRenderer::new(&mut output, TreeOptions::default(), None, Locale::ZhCn);
+    let file = FileEntry {
+        relative_path: "a.rs".to_owned(),
+        code_block_language: "rust".to_owned(),
+        absolute_path: PathBuf::from("a.rs"),
+        size: 0,
+        omission: None,
+        git_object: None,
+        untracked: false,
+        declared_encoding: None,
+        permalink: None,
+    };
+    must(renderer.begin_file(&file), "写入文件标题失败");
+    must(renderer.content_chunk("fn a() {}\n"), "写入文件内容失败");
+    must(renderer.file_diff("-fn a();\n+fn a() {}\n"), "写入补丁失败");
+    must(renderer.end_file(), "结束文件失败");
+    assert_eq!(
+        must(String::from_utf8(output), "输出必须是 UTF-8"),
+        "### a.rs\n```rust\nfn a() {}\n```\n\n```diff\n-fn a();\n+fn a() {}\n```\n\n"
+    );
+}
+#[test]
+fn commit_log_lists_commits_since_the_reference() {
+    let commits = [Commit {
+        hash: "abc1234".to_owned(),
+        author: "Lin".to_owned(),
+        date: "2026-10-01".to_owned(),
+        subject: "Fix *parser*".to_owned(),
+    }];
+    assert_eq!(
+        commit_log_markdown("main", "HEAD", &commits, Locale::ZhCn),
+        "## 提交记录 (main..HEAD)\n\n- `abc1234` 2026-10-01 Lin: Fix \\*parser\\*\n\n"
+    );
+    assert_eq!(
+        commit_log_markdown("v1", "v2", &[], Locale::En),
+        "## Commit Log (v1..v2)\n\n(no new commits)\n\n"
+    );
+}
+#[test]
+fn permalinks_turn_file_headings_into_links() {
+    let mut output = Vec::new();
+    let mut renderer =
+        MarkdownRenderer::new(&mut output, TreeOptions::default(), None, Locale::ZhCn);
+    let file = FileEntry {
+        relative_path: "src/a_b.rs".to_owned(),
+        code_block_language: "rust".to_owned(),
+        absolute_path: PathBuf::from("a_b.rs"),
+        size: 0,
+        omission: None,
+        git_object: None,
+        untracked: true,
+        declared_encoding: None,
+        permalink: Some("https://example.com/blob/abc/src/a_b.rs".to_owned()),
+    };
+    must(renderer.begin_file(&file), "写入文件标题失败");
+    assert_eq!(
+        must(String::from_utf8(output), "输出必须是 UTF-8"),
+        "### [src/a\\_b.rs](https://example.com/blob/abc/src/a_b.rs) [未跟踪]\n"
+    );
+}
@@ -0,0 +1,139 @@
+use crate::front_matter::{SECONDS_PER_DAY, civil_from_days, format_timestamp};
+use core::time::Duration;
+use std::time::SystemTime;
+const DURATION_UNITS: [(char, u64); 5] = [
+    ('w', 604_800),
+    ('d', 86_400),
+    ('h', 3_600),
+    ('m', 60),
+    ('s', 1),
+];
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum ModifiedWindow {
+    Within(u64),
+    Since(u64),
+}
+impl ModifiedWindow {
+    pub(crate) fn parse_within(value: &str) -> Option<Self> {
+        let unit = value.chars().last()?;
+        let (_, scale) = DURATION_UNITS
+            .iter()
+            .copied()
+            .find(|&(name, _)| name == unit)?;
+        let amount = value.strip_suffix(unit)?;
+        if amount.is_empty() || !amount.bytes().all(|byte| byte.is_ascii_digit()) {
+            return None;
+        }
+        amount
+            .parse::<u64>()
+            .ok()?
+            .checked_mul(scale)
+            .filter(|&seconds| seconds > 0)
+            .map(Self::Within)
+    }
+    pub(crate) fn parse_since(value: &str) -> Option<Self> {
+        parse_timestamp(value).map(Self::Since)
+    }
+    pub(crate) const fn option_name(self) -> &'static str {
+        match self {
+            Self::Within(_) => "modified-within",
+            Self::Since(_) => "modified-since",
+        }
+    }
+    pub(crate) fn describe(self) -> String {
+        match self {
+            Self::Within(seconds) => DURATION_UNITS
+                .iter()
+                .copied()
+                .find(|&(_, scale)| seconds.checked_rem(scale) == Some(0))
+                .map_or_else(
+                    || format!("{seconds}s"),
+                    |(name, scale)| format!("{}{name}", seconds.checked_div(scale).unwrap_or(0)),
+                ),
+            Self::Since(epoch) => format_timestamp(epoch),
+        }
+    }
+    pub(crate) fn cutoff(self, now: SystemTime) -> SystemTime {
+        match self {
+            Self::Within(seconds) => now
+                .checked_sub(Duration::from_secs(seconds))
+                .unwrap_or(SystemTime::UNIX_EPOCH),
+            Self::Since(epoch) => SystemTime::UNIX_EPOCH
+                .checked_add(Duration::from_secs(epoch))
+                .unwrap_or(now),
+        }
+    }
+}
+pub(crate) fn describe_time(time: SystemTime) -> String {
+    format_timestamp(
+        time.duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs()),
+    )
+}
+fn parse_timestamp(text: &str) -> Option<u64> {
+    if let Some(seconds) = timestamp_field(text) {
+        return Some(seconds);
+    }
+    let without_zone = text.strip_suffix('Z').unwrap_or(text);
+    let (date, time) = without_zone
+        .split_once(['T', ' '])
+        .unwrap_or((without_zone, "00:00"));
+    let date_fields = date
+        .split('-')
+        .map(timestamp_field)
+        .collect::<Option<Vec<_>>>()?;
+    let time_fields = time
+        .split(':')
+        .map(timestamp_field)
+        .collect::<Option<Vec<_>>>()?;
+    let &[year, month, day] = date_fields.as_slice() else {
+        return None;
+    };
+    let (hour, minute, second) = match *time_fields.as_slice() {
+        [hour, minute] => (hour, minute, 0),
+        [hour, minute, second] => (hour, minute, second),
+        _ => return None,
+    };
+    if hour >= 24 || minute >= 60 || second >= 60 {
+        return None;
+    }
+    let days = days_from_civil(year, month, day)?;
+    days.checked_mul(SECONDS_PER_DAY)?
+        .checked_add(hour.checked_mul(3600)?)?
+        .checked_add(minute.checked_mul(60)?)?
+        .checked_add(second)
+}
+fn timestamp_field(text: &str) -> Option<u64> {
+    if text.is_empty() || !text.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    text.parse().ok()
+}
+fn days_from_civil(year: u64, month: u64, day: u64) -> Option<u64> {
+    let shifted_year = year.checked_sub(u64::from(month <= 2))?;
+    let era = shifted_year.checked_div(400)?;
+    let year_of_era = shifted_year.checked_sub(era.checked_mul(400)?)?;
+    let month_index = if month > 2 {
+        month.checked_sub(3)?
+    } else {
+        month.checked_add(9)?
+    };
+    let day_of_year = month_index
+        .checked_mul(153)?
+        .checked_add(2)?
+        .checked_div(5)?
+        .checked_add(day)?
+        .checked_sub(1)?;
+    let day_of_era = year_of_era
+        .checked_mul(365)?
+        .checked_add(year_of_era.checked_div(4)?)?
+        .checked_sub(year_of_era.checked_div(100)?)?
+        .checked_add(day_of_year)?;
+    let days = era
+        .checked_mul(146_097)?
+        .checked_add(day_of_era)?
+        .checked_sub(719_468)?;
+    (civil_from_days(days) == (year, month, day)).then_some(days)
+}
+#[cfg(test)]
+mod tests;
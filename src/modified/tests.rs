@@ -0,0 +1,52 @@
+use super::{ModifiedWindow, describe_time, parse_timestamp};
+use core::time::Duration;
+use std::time::SystemTime;
+#[test]
+fn durations_accept_common_units() {
+    assert_eq!(
+        ModifiedWindow::parse_within("3d"),
+        Some(ModifiedWindow::Within(259_200))
+    );
+    assert_eq!(
+        ModifiedWindow::parse_within("90m"),
+        Some(ModifiedWindow::Within(5_400))
+    );
+    assert_eq!(
+        ModifiedWindow::parse_within("2w").map(ModifiedWindow::describe),
+        Some("2w".to_owned())
+    );
+    assert_eq!(
+        ModifiedWindow::parse_within("36h").map(ModifiedWindow::describe),
+        Some("36h".to_owned())
+    );
+    for invalid in ["", "d", "0d", "3", "3y", "-3d", "+3d"] {
+        assert_eq!(ModifiedWindow::parse_within(invalid), None, "{invalid}");
+    }
+}
+#[test]
+fn cutoffs_are_relative_to_now_or_absolute() {
+    let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+    assert_eq!(
+        ModifiedWindow::Within(86_400).cutoff(now),
+        SystemTime::UNIX_EPOCH + Duration::from_secs(913_600)
+    );
+    let since = ModifiedWindow::parse_since("2026-10-18");
+    assert_eq!(
+        since.map(ModifiedWindow::describe),
+        Some("2026-10-18T00:00:00Z".to_owned())
+    );
+    assert_eq!(
+        since.map(|window| describe_time(window.cutoff(now))),
+        Some("2026-10-18T00:00:00Z".to_owned())
+    );
+}
+#[test]
+fn timestamps_are_parsed_as_utc() {
+    assert_eq!(parse_timestamp("1792329296"), Some(1_792_329_296));
+    assert_eq!(parse_timestamp("2000-02-29"), Some(951_782_400));
+    assert_eq!(parse_timestamp("2026-10-18T13:14:56Z"), Some(1_792_329_296));
+    assert_eq!(parse_timestamp("2026-10-18 13:14"), Some(1_792_329_240));
+    assert_eq!(parse_timestamp("2026-02-30"), None);
+    assert_eq!(parse_timestamp("2026-10-18T24:00"), None);
+    assert_eq!(parse_timestamp("yesterday"), None);
+}
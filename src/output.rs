@@ -1,31 +1,65 @@
-use crate::{config::OUTPUT_FILENAME, errors::AppResult, markdown::write_project_markdown};
+use crate::{
+    errors::{AppResult, FailureKind},
+    i18n::{Message, tr},
+    inventory::ProjectInventory,
+    render::{OutputFormat, RenderOptions, create_renderer, render_inventory},
+};
 use std::{
     env, fs,
     io::{self, BufWriter, Write as _},
     path::{Path, PathBuf},
 };
-pub(crate) fn write_output_file(root_path: &Path) -> AppResult<PathBuf> {
-    let output_dir = env::temp_dir().join("proj2md");
-    write_output_file_in(root_path, &output_dir)
+pub(crate) fn write_output_file(
+    inventory: &ProjectInventory,
+    options: &RenderOptions,
+) -> AppResult<PathBuf> {
+    write_output_file_in(inventory, options, &output_dir())
+}
+pub(crate) fn output_path(format: OutputFormat) -> PathBuf {
+    output_dir().join(format.output_filename())
+}
+fn output_dir() -> PathBuf {
+    env::temp_dir().join("proj2md")
 }
-fn write_output_file_in(root_path: &Path, output_dir: &Path) -> AppResult<PathBuf> {
-    let (output_path, mut writer) = create_output_writer(output_dir)?;
-    write_project_markdown(root_path, &mut writer)?;
-    writer.flush()?;
+fn write_output_file_in(
+    inventory: &ProjectInventory,
+    options: &RenderOptions,
+    output_dir: &Path,
+) -> AppResult<PathBuf> {
+    let (output_path, mut writer) =
+        create_output_writer(output_dir, options.format.output_filename())
+            .map_err(|err| FailureKind::Output.wrap(err))?;
+    render_inventory(
+        inventory,
+        options,
+        create_renderer(options, &mut writer).as_mut(),
+    )?;
+    writer
+        .flush()
+        .map_err(|err| FailureKind::Output.wrap(err))?;
     Ok(output_path)
 }
-fn create_output_writer(output_dir: &Path) -> AppResult<(PathBuf, BufWriter<fs::File>)> {
+fn create_output_writer(
+    output_dir: &Path,
+    output_filename: &str,
+) -> io::Result<(PathBuf, BufWriter<fs::File>)> {
     fs::create_dir_all(output_dir).map_err(|err| {
         io::Error::new(
             err.kind(),
-            format!("创建临时目录失败: {}: {err}", output_dir.display()),
+            tr(
+                Message::CreateOutputDirFailed,
+                &[&output_dir.display(), &err],
+            ),
         )
     })?;
-    let output_path = output_dir.join(OUTPUT_FILENAME);
+    let output_path = output_dir.join(output_filename);
     let file = fs::File::create(&output_path).map_err(|err| {
         io::Error::new(
             err.kind(),
-            format!("创建输出文件失败: {}: {err}", output_path.display()),
+            tr(
+                Message::CreateOutputFileFailed,
+                &[&output_path.display(), &err],
+            ),
         )
     })?;
     Ok((output_path, BufWriter::new(file)))
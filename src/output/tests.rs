@@ -1,6 +1,8 @@
 use super::write_output_file_in;
 use crate::{
     config::OUTPUT_FILENAME,
+    inventory::{InventoryOptions, collect_project_inventory},
+    render::{OutputFormat, RenderOptions},
     test_support::{TestDir, must},
 };
 use std::fs;
@@ -12,8 +14,12 @@ fn write_output_file_in_creates_markdown_in_requested_directory() {
         project.write_str("src/main.rs", "fn main() {}\n"),
         "写入 main.rs 失败",
     );
+    let inventory = must(
+        collect_project_inventory(project.path(), &InventoryOptions::default()),
+        "收集项目清单失败",
+    );
     let output_path = must(
-        write_output_file_in(project.path(), output_dir.path()),
+        write_output_file_in(&inventory, &RenderOptions::default(), output_dir.path()),
         "写入输出文件失败",
     );
     let document = must(fs::read_to_string(&output_path), "读取输出文件失败");
@@ -22,3 +28,30 @@ fn write_output_file_in_creates_markdown_in_requested_directory() {
     assert!(document.contains("## 2. 文件内容"));
     assert!(document.contains("fn main() {}"));
 }
+#[test]
+fn write_output_file_in_uses_format_specific_filename() {
+    let project = must(TestDir::new("output-json-project"), "创建项目测试目录失败");
+    let output_dir = must(TestDir::new("output-json-target"), "创建输出测试目录失败");
+    must(
+        project.write_str("main.rs", "fn main() {}\n"),
+        "写入 main.rs 失败",
+    );
+    let inventory = must(
+        collect_project_inventory(project.path(), &InventoryOptions::default()),
+        "收集项目清单失败",
+    );
+    let output_path = must(
+        write_output_file_in(
+            &inventory,
+            &RenderOptions {
+                format: OutputFormat::Json,
+                ..RenderOptions::default()
+            },
+            output_dir.path(),
+        ),
+        "写入 JSON 输出文件失败",
+    );
+    let document = must(fs::read_to_string(&output_path), "读取输出文件失败");
+    assert_eq!(output_path, output_dir.path().join("project.json"));
+    assert!(document.contains("\"path\": \"main.rs\""));
+}
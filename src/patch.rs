@@ -0,0 +1,106 @@
+use crate::{
+    git::{git_stdout, resolve_commit},
+    i18n::{Message, tr},
+    inventory::FileEntry,
+    paths,
+};
+use alloc::collections::{BTreeMap, BTreeSet};
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+const FIELD_SEPARATOR: char = '\u{1f}';
+pub(crate) const HEAD: &str = "HEAD";
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Commit {
+    pub hash: String,
+    pub author: String,
+    pub date: String,
+    pub subject: String,
+}
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub(crate) struct Patches {
+    pub(crate) reference: String,
+    pub(crate) commits: Vec<Commit>,
+    pub(crate) diffs: BTreeMap<String, String>,
+}
+pub(crate) fn collect_patches(
+    root_path: &Path,
+    reference: &str,
+    revision: Option<&str>,
+    files: &[FileEntry],
+) -> io::Result<Patches> {
+    let base = resolve_commit(root_path, reference)?;
+    let target = revision
+        .map(|found| resolve_commit(root_path, found))
+        .transpose()?;
+    let mut endpoints = vec![base.as_str()];
+    endpoints.extend(target.as_deref());
+    let mut names_args = vec!["diff", "--name-only", "--relative", "-z"];
+    names_args.extend(&endpoints);
+    names_args.push("--");
+    let names = git_stdout(root_path, &names_args)?;
+    let mut changed = BTreeSet::new();
+    for name in names.split('\0').filter(|name| !name.is_empty()) {
+        let native: PathBuf = name.split('/').collect();
+        changed.insert(paths::relative_path_text(&native)?);
+    }
+    let mut diffs = BTreeMap::new();
+    for file in files
+        .iter()
+        .filter(|file| changed.contains(&file.relative_path))
+    {
+        let mut diff_args = vec![
+            "--literal-pathspecs",
+            "diff",
+            "--no-color",
+            "--no-ext-diff",
+            "--no-textconv",
+            "--relative",
+        ];
+        diff_args.extend(&endpoints);
+        diff_args.extend(["--", file.relative_path.as_str()]);
+        let diff = git_stdout(root_path, &diff_args)?;
+        if !diff.is_empty() {
+            diffs.insert(file.relative_path.clone(), diff);
+        }
+    }
+    Ok(Patches {
+        reference: reference.to_owned(),
+        commits: commit_log(root_path, &base, target.as_deref().unwrap_or(HEAD))?,
+        diffs,
+    })
+}
+fn commit_log(root_path: &Path, base: &str, target: &str) -> io::Result<Vec<Commit>> {
+    let range = format!("{base}..{target}");
+    let log = git_stdout(
+        root_path,
+        &[
+            "log",
+            "--no-color",
+            "--date=short",
+            "--format=%h%x1f%an%x1f%ad%x1f%s",
+            &range,
+            "--",
+        ],
+    )?;
+    log.lines()
+        .map(|line| {
+            let mut fields = line.split(FIELD_SEPARATOR);
+            match (fields.next(), fields.next(), fields.next(), fields.next()) {
+                (Some(hash), Some(author), Some(date), Some(subject)) => Ok(Commit {
+                    hash: hash.to_owned(),
+                    author: author.to_owned(),
+                    date: date.to_owned(),
+                    subject: subject.to_owned(),
+                }),
+                _ => Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    tr(Message::GitLogLineInvalid, &[&line]),
+                )),
+            }
+        })
+        .collect()
+}
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,100 @@
+use super::collect_patches;
+use crate::{
+    inventory::{InventoryOptions, collect_project_inventory},
+    test_support::{TestDir, must, must_err},
+};
+#[test]
+fn diffs_and_commit_log_are_collected_against_a_reference() {
+    let dir = must(TestDir::new("patch-collect"), "创建测试目录失败");
+    must(dir.write_str("a.rs", "fn a() {}\n"), "写入 a.rs 失败");
+    must(dir.write_str("b.rs", "fn b() {}\n"), "写入 b.rs 失败");
+    must(dir.git(&["init", "-q"]), "初始化仓库失败");
+    must(dir.git(&["add", "-A"]), "暂存文件失败");
+    must(dir.git(&["commit", "-q", "-m", "init"]), "提交失败");
+    must(dir.git(&["tag", "base"]), "创建标签失败");
+    must(dir.write_str("a.rs", "fn a() { 1; }\n"), "修改 a.rs 失败");
+    must(dir.git(&["commit", "-q", "-am", "edit a"]), "提交失败");
+    let inventory = must(
+        collect_project_inventory(dir.path(), &InventoryOptions::default()),
+        "收集项目清单失败",
+    );
+    let patches = must(
+        collect_patches(dir.path(), "base", None, &inventory.content_files),
+        "收集补丁失败",
+    );
+    assert_eq!(patches.reference, "base");
+    assert_eq!(
+        patches.diffs.keys().map(String::as_str).collect::<Vec<_>>(),
+        ["a.rs"]
+    );
+    assert!(
+        patches
+            .diffs
+            .get("a.rs")
+            .is_some_and(|diff| diff.contains("+fn a() { 1; }"))
+    );
+    let subjects: Vec<_> = patches
+        .commits
+        .iter()
+        .map(|commit| (commit.author.as_str(), commit.subject.as_str()))
+        .collect();
+    assert_eq!(subjects, [("proj2md", "edit a")]);
+}
+#[test]
+fn unknown_reference_is_reported() {
+    let dir = must(TestDir::new("patch-bad-ref"), "创建测试目录失败");
+    must(dir.git(&["init", "-q"]), "初始化仓库失败");
+    must_err(
+        collect_patches(dir.path(), "nope", None, &[]),
+        "未知的引用应当报错",
+    );
+    must_err(
+        collect_patches(dir.path(), "--output=injected.txt", None, &[]),
+        "以 - 开头的引用应当报错",
+    );
+    assert!(!dir.path().join("injected.txt").exists());
+}
+#[test]
+fn revision_diffs_ignore_external_drivers_and_pathspec_magic() {
+    let dir = must(TestDir::new("patch-revision"), "创建测试目录失败");
+    must(dir.write_str("a.rs", "fn a() {}\n"), "写入 a.rs 失败");
+    must(
+        dir.write_str(":odd*.rs", "fn odd() {}\n"),
+        "写入 :odd*.rs 失败",
+    );
+    must(dir.git(&["init", "-q"]), "初始化仓库失败");
+    must(
+        dir.git(&["config", "diff.external", "false"]),
+        "配置外部 diff 失败",
+    );
+    must(dir.git(&["add", "-A"]), "暂存文件失败");
+    must(dir.git(&["commit", "-q", "-m", "init"]), "提交失败");
+    must(dir.git(&["tag", "base"]), "创建标签失败");
+    must(dir.write_str("a.rs", "fn a() { 1; }\n"), "修改 a.rs 失败");
+    must(
+        dir.write_str(":odd*.rs", "fn odd() { 1; }\n"),
+        "修改 :odd*.rs 失败",
+    );
+    must(dir.git(&["commit", "-q", "-am", "edit"]), "提交失败");
+    must(dir.write_str("a.rs", "fn a() { 2; }\n"), "修改 a.rs 失败");
+    let inventory = must(
+        collect_project_inventory(dir.path(), &InventoryOptions::default()),
+        "收集项目清单失败",
+    );
+    let patches = must(
+        collect_patches(dir.path(), "base", Some("HEAD"), &inventory.content_files),
+        "收集补丁失败",
+    );
+    let a_diff = must(
+        patches.diffs.get("a.rs").ok_or("缺少 a.rs 补丁"),
+        "读取补丁失败",
+    );
+    assert!(a_diff.contains("+fn a() { 1; }"));
+    assert!(!a_diff.contains("2;"));
+    let odd_diff = must(
+        patches.diffs.get(":odd*.rs").ok_or("缺少 :odd*.rs 补丁"),
+        "读取补丁失败",
+    );
+    assert!(odd_diff.contains("+fn odd() { 1; }"));
+    assert!(!odd_diff.contains("a.rs"));
+}
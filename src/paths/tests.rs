@@ -1,4 +1,4 @@
-use super::{code_block_language, root_name, validate_root_path};
+use super::{extension_text, root_name, validate_root_path};
 use crate::test_support::{TestDir, must, must_err};
 use std::{io, path::Path};
 #[test]
@@ -29,18 +29,15 @@ fn root_name_uses_last_path_component() {
     assert!(name.starts_with("proj2md-test-root-name-"));
 }
 #[test]
-fn code_block_language_uses_file_extension() {
+fn extension_text_uses_file_extension() {
     let language = must(
-        code_block_language(Path::new("src/main.rs")),
+        extension_text(Path::new("src/main.rs")),
         "读取文件扩展名失败",
     );
     assert_eq!(language, "rs");
 }
 #[test]
-fn code_block_language_is_empty_without_extension() {
-    let language = must(
-        code_block_language(Path::new("LICENSE")),
-        "读取无扩展名文件失败",
-    );
+fn extension_text_is_empty_without_extension() {
+    let language = must(extension_text(Path::new("LICENSE")), "读取无扩展名文件失败");
     assert_eq!(language, "");
 }
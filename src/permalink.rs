@@ -0,0 +1,177 @@
+use crate::{
+    git::{git_stdout, resolve_commit},
+    i18n::{Message, tr},
+};
+use std::{io, path::Path};
+const GITHUB_TEMPLATE: &str = "{base}/blob/{commit}/{path}";
+const GITLAB_TEMPLATE: &str = "{base}/-/blob/{commit}/{path}";
+const GITEA_TEMPLATE: &str = "{base}/src/commit/{commit}/{path}";
+const LINES_PLACEHOLDER: &str = "{lines}";
+const DEFAULT_ANCHOR: &str = "L1-L{lines}";
+const GITLAB_ANCHOR: &str = "L1-{lines}";
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) enum PermalinkStyle {
+    GitHub,
+    GitLab,
+    Gitea,
+    Custom(String),
+}
+impl PermalinkStyle {
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        match value {
+            "github" => Some(Self::GitHub),
+            "gitlab" => Some(Self::GitLab),
+            "gitea" => Some(Self::Gitea),
+            _ if value.contains("{path}") => Some(Self::Custom(value.to_owned())),
+            _ => None,
+        }
+    }
+    pub(crate) fn describe(&self) -> String {
+        match self {
+            Self::GitHub => "github".to_owned(),
+            Self::GitLab => "gitlab".to_owned(),
+            Self::Gitea => "gitea".to_owned(),
+            Self::Custom(template) => template.clone(),
+        }
+    }
+    fn template(&self) -> &str {
+        match self {
+            Self::GitHub => GITHUB_TEMPLATE,
+            Self::GitLab => GITLAB_TEMPLATE,
+            Self::Gitea => GITEA_TEMPLATE,
+            Self::Custom(template) => custom_parts(template).0,
+        }
+    }
+    fn line_anchor(&self) -> &str {
+        match self {
+            Self::GitLab => GITLAB_ANCHOR,
+            Self::GitHub | Self::Gitea => DEFAULT_ANCHOR,
+            Self::Custom(template) => custom_parts(template).1.unwrap_or(DEFAULT_ANCHOR),
+        }
+    }
+}
+fn custom_parts(template: &str) -> (&str, Option<&str>) {
+    template
+        .split_once('#')
+        .filter(|&(_, fragment)| fragment.contains(LINES_PLACEHOLDER))
+        .map_or((template, None), |(url, fragment)| (url, Some(fragment)))
+}
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub(crate) enum LineAnchors {
+    #[default]
+    Off,
+    Range,
+}
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct Permalinks {
+    style: PermalinkStyle,
+    base: String,
+    commit: String,
+    prefix: String,
+    line_anchors: LineAnchors,
+}
+impl Permalinks {
+    pub(crate) fn url(&self, relative_path: &str, lines: Option<usize>) -> String {
+        let encoded_path = encode_path(&format!("{}{relative_path}", self.prefix));
+        let mut url = self
+            .style
+            .template()
+            .replace("{base}", &self.base)
+            .replace("{commit}", &self.commit)
+            .replace("{path}", &encoded_path);
+        if self.line_anchors == LineAnchors::Range
+            && let Some(count) = lines.filter(|&count| count > 0)
+        {
+            url.push('#');
+            url.push_str(
+                &self
+                    .style
+                    .line_anchor()
+                    .replace(LINES_PLACEHOLDER, &count.to_string()),
+            );
+        }
+        url
+    }
+}
+pub(crate) fn resolve_permalinks(
+    root_path: &Path,
+    style: &PermalinkStyle,
+    revision: Option<&str>,
+    line_anchors: LineAnchors,
+) -> io::Result<Permalinks> {
+    let remotes = git_stdout(root_path, &["remote"])?;
+    let remote = remotes
+        .lines()
+        .find(|name| *name == "origin")
+        .or_else(|| remotes.lines().next())
+        .ok_or_else(|| io::Error::other(tr(Message::GitRemoteMissing, &[])))?;
+    let remote_url = git_stdout(root_path, &["remote", "get-url", remote])?;
+    let base = web_base(remote_url.trim()).ok_or_else(|| {
+        io::Error::other(tr(Message::GitRemoteUnsupported, &[&remote_url.trim()]))
+    })?;
+    let commit = resolve_commit(root_path, revision.unwrap_or("HEAD"))?;
+    let prefix = git_stdout(root_path, &["rev-parse", "--show-prefix"])?
+        .trim()
+        .to_owned();
+    Ok(Permalinks {
+        style: style.clone(),
+        base,
+        commit,
+        prefix,
+        line_anchors,
+    })
+}
+pub(crate) fn web_base(remote_url: &str) -> Option<String> {
+    let without_slash = remote_url.trim_end_matches('/');
+    let trimmed = without_slash.strip_suffix(".git").unwrap_or(without_slash);
+    let (scheme, rest, keep_port) = if let Some(rest) = trimmed.strip_prefix("https://") {
+        ("https", rest, true)
+    } else if let Some(rest) = trimmed.strip_prefix("http://") {
+        ("http", rest, true)
+    } else if let Some(rest) = trimmed
+        .strip_prefix("ssh://")
+        .or_else(|| trimmed.strip_prefix("git+ssh://"))
+        .or_else(|| trimmed.strip_prefix("git://"))
+    {
+        ("https", rest, false)
+    } else if !trimmed.contains("://") && !trimmed.starts_with('/') {
+        let (authority, path) = trimmed.split_once(':')?;
+        return Some(format!(
+            "https://{}/{}",
+            strip_user(authority),
+            path.trim_start_matches('/')
+        ));
+    } else {
+        return None;
+    };
+    let (authority, path) = rest.split_once('/')?;
+    let host_port = strip_user(authority);
+    let host = if keep_port {
+        host_port
+    } else {
+        host_port.split(':').next().unwrap_or(host_port)
+    };
+    (!host.is_empty() && !path.is_empty()).then(|| format!("{scheme}://{host}/{path}"))
+}
+fn strip_user(authority: &str) -> &str {
+    authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host)
+}
+fn encode_path(path: &str) -> String {
+    let mut encoded = String::with_capacity(path.len());
+    for byte in path.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~' | b'/') {
+            encoded.push(char::from(byte));
+        } else {
+            encoded.push('%');
+            for nibble in [byte.wrapping_shr(4), byte & 0xf] {
+                let digit = char::from_digit(u32::from(nibble), 16).unwrap_or('0');
+                encoded.push(digit.to_ascii_uppercase());
+            }
+        }
+    }
+    encoded
+}
+#[cfg(test)]
+mod tests;
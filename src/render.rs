@@ -0,0 +1,385 @@
+use crate::{
+    content::{FileContent, count_lines, load_file_content},
+    errors::{AppResult, FailureKind},
+    front_matter::{BundleMetadata, ContentDigest, ResolvedOption, bundle_metadata},
+    i18n::Locale,
+    inventory::{
+        FileEntry, InventoryOptions, ProjectInventory, TreeEntry, collect_project_inventory,
+    },
+    json::JsonRenderer,
+    logging::{self, LogEvent, Phase},
+    markdown::MarkdownRenderer,
+    patch::{Commit, Patches, collect_patches},
+    permalink::{LineAnchors, PermalinkStyle, Permalinks, resolve_permalinks},
+    stats::{ContentStats, ProjectSummary, summarize},
+    template::{Template, TemplateRenderer},
+    tree::{TreeOptions, annotate_tree, collapse_tree},
+    xml::XmlRenderer,
+};
+use core::time::Duration;
+use std::{io, path::Path, time::Instant};
+pub trait Renderer {
+    fn begin_document(&mut self, root_name: &str) -> io::Result<()>;
+    #[inline]
+    fn front_matter(&mut self, _metadata: &BundleMetadata) -> io::Result<()> {
+        Ok(())
+    }
+    #[inline]
+    fn project_summary(&mut self, _summary: &ProjectSummary) -> io::Result<()> {
+        Ok(())
+    }
+    #[inline]
+    fn commit_log(&mut self, _reference: &str, _commits: &[Commit]) -> io::Result<()> {
+        Ok(())
+    }
+    fn begin_tree(&mut self) -> io::Result<()>;
+    fn tree_entry(&mut self, entry: &TreeEntry) -> io::Result<()>;
+    fn end_tree(&mut self) -> io::Result<()>;
+    fn begin_contents(&mut self) -> io::Result<()>;
+    fn begin_file(&mut self, file: &FileEntry) -> io::Result<()>;
+    fn content_chunk(&mut self, chunk: &str) -> io::Result<()>;
+    #[inline]
+    fn file_diff(&mut self, _diff: &str) -> io::Result<()> {
+        Ok(())
+    }
+    fn end_file(&mut self) -> io::Result<()>;
+    fn end_contents(&mut self) -> io::Result<()>;
+    #[inline]
+    fn deleted_files(&mut self, _paths: &[String]) -> io::Result<()> {
+        Ok(())
+    }
+    fn end_document(&mut self) -> io::Result<()>;
+}
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub(crate) enum OutputFormat {
+    #[default]
+    Markdown,
+    Json,
+    Xml,
+}
+impl OutputFormat {
+    pub(crate) fn parse(name: &str) -> Option<Self> {
+        match name {
+            "markdown" | "md" => Some(Self::Markdown),
+            "json" => Some(Self::Json),
+            "xml" => Some(Self::Xml),
+            _ => None,
+        }
+    }
+    pub(crate) const fn name(self) -> &'static str {
+        match self {
+            Self::Markdown => "markdown",
+            Self::Json => "json",
+            Self::Xml => "xml",
+        }
+    }
+    pub(crate) const fn output_filename(self) -> &'static str {
+        match self {
+            Self::Markdown => crate::config::OUTPUT_FILENAME,
+            Self::Json => "project.json",
+            Self::Xml => "project.xml",
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub(crate) enum DocumentMode {
+    #[default]
+    Full,
+    TreeOnly,
+    ContentOnly,
+}
+impl DocumentMode {
+    pub(crate) fn parse(name: &str) -> Option<Self> {
+        match name {
+            "full" => Some(Self::Full),
+            "tree" => Some(Self::TreeOnly),
+            "content" => Some(Self::ContentOnly),
+            _ => None,
+        }
+    }
+    pub(crate) const fn name(self) -> &'static str {
+        match self {
+            Self::Full => "full",
+            Self::TreeOnly => "tree",
+            Self::ContentOnly => "content",
+        }
+    }
+    pub(crate) const fn includes_tree(self) -> bool {
+        matches!(self, Self::Full | Self::TreeOnly)
+    }
+    pub(crate) const fn includes_contents(self) -> bool {
+        matches!(self, Self::Full | Self::ContentOnly)
+    }
+}
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub(crate) struct RenderOptions {
+    pub(crate) format: OutputFormat,
+    pub(crate) mode: DocumentMode,
+    pub(crate) summary: bool,
+    pub(crate) front_matter: bool,
+    pub(crate) resolved_options: Vec<ResolvedOption>,
+    pub(crate) template: Option<Template>,
+    pub(crate) diff_reference: Option<String>,
+    pub(crate) revision: Option<String>,
+    pub(crate) permalink: Option<PermalinkStyle>,
+    pub(crate) line_anchors: LineAnchors,
+    pub(crate) tree: TreeOptions,
+    pub(crate) locale: Locale,
+}
+pub(crate) fn create_renderer<'writer, W>(
+    options: &RenderOptions,
+    writer: &'writer mut W,
+) -> Box<dyn Renderer + 'writer>
+where
+    W: io::Write,
+{
+    if let Some(template) = options.template.as_ref() {
+        return Box::new(TemplateRenderer::new(
+            writer,
+            template.clone(),
+            options.revision.clone(),
+            options.locale,
+        ));
+    }
+    match options.format {
+        OutputFormat::Markdown => Box::new(MarkdownRenderer::new(
+            writer,
+            options.tree,
+            options.revision.clone(),
+            options.locale,
+        )),
+        OutputFormat::Json => Box::new(JsonRenderer::new(writer, options.tree)),
+        OutputFormat::Xml => Box::new(XmlRenderer::new(writer, options.tree)),
+    }
+}
+pub(crate) fn render_project(root_path: &Path, renderer: &mut dyn Renderer) -> AppResult<()> {
+    let inventory = collect_project_inventory(root_path, &InventoryOptions::default())?;
+    render_inventory(&inventory, &RenderOptions::default(), renderer)
+}
+pub(crate) fn render_inventory(
+    inventory: &ProjectInventory,
+    options: &RenderOptions,
+    renderer: &mut dyn Renderer,
+) -> AppResult<()> {
+    let started = Instant::now();
+    let measured = measure_contents(inventory, options)?;
+    let metadata = measured
+        .content_hash
+        .map(|content_hash| bundle_metadata(inventory, content_hash, &options.resolved_options))
+        .transpose()?;
+    let patches = options
+        .diff_reference
+        .as_deref()
+        .map(|reference| {
+            collect_patches(
+                &inventory.root_path,
+                reference,
+                options.revision.as_deref(),
+                &inventory.content_files,
+            )
+        })
+        .transpose()
+        .map_err(|err| FailureKind::Git.wrap(err))?;
+    let permalinks = options
+        .permalink
+        .as_ref()
+        .map(|style| {
+            resolve_permalinks(
+                &inventory.root_path,
+                style,
+                options.revision.as_deref(),
+                options.line_anchors,
+            )
+        })
+        .transpose()
+        .map_err(|err| FailureKind::Git.wrap(err))?;
+    let mut read_elapsed = started.elapsed();
+    let write_started = Instant::now();
+    write_preamble(
+        inventory,
+        options,
+        &measured.stats,
+        metadata.as_ref(),
+        patches.as_ref(),
+        renderer,
+    )
+    .map_err(|err| FailureKind::Output.wrap(err))?;
+    if options.mode.includes_contents() {
+        if let Some(contents) = measured.contents {
+            write_contents(
+                &inventory.content_files,
+                contents.into_iter().map(Ok),
+                patches.as_ref(),
+                permalinks.as_ref(),
+                renderer,
+            )?;
+        } else {
+            let streamed = render_file_contents(
+                &inventory.content_files,
+                options.locale,
+                patches.as_ref(),
+                permalinks.as_ref(),
+                renderer,
+            )?;
+            read_elapsed = read_elapsed.saturating_add(streamed);
+        }
+    }
+    write_closing(inventory, renderer).map_err(|err| FailureKind::Output.wrap(err))?;
+    let write_elapsed = write_started.elapsed().saturating_sub(read_elapsed);
+    logging::log(LogEvent::Phase {
+        phase: Phase::Read,
+        elapsed: read_elapsed,
+    });
+    logging::log(LogEvent::Phase {
+        phase: Phase::Write,
+        elapsed: write_elapsed,
+    });
+    Ok(())
+}
+#[derive(Debug, Default)]
+struct MeasuredContents {
+    contents: Option<Vec<FileContent>>,
+    stats: Vec<ContentStats>,
+    content_hash: Option<String>,
+}
+fn measure_contents(
+    inventory: &ProjectInventory,
+    options: &RenderOptions,
+) -> AppResult<MeasuredContents> {
+    let wants_stats = options.summary || !options.tree.annotations.is_empty();
+    if !wants_stats && !options.front_matter {
+        return Ok(MeasuredContents::default());
+    }
+    let mut digest = options.front_matter.then(ContentDigest::new);
+    let mut contents = options.mode.includes_contents().then(Vec::new);
+    let mut stats = Vec::new();
+    for file in &inventory.content_files {
+        let content = load_logged(file, options.locale)?;
+        if let Some(hasher) = digest.as_mut() {
+            hasher.add(&file.relative_path, &content.text);
+        }
+        if wants_stats {
+            stats.push(ContentStats::measure(&content));
+        }
+        if let Some(loaded) = contents.as_mut() {
+            loaded.push(content);
+        }
+    }
+    Ok(MeasuredContents {
+        contents,
+        stats,
+        content_hash: digest.map(ContentDigest::finish),
+    })
+}
+fn load_logged(file: &FileEntry, locale: Locale) -> AppResult<FileContent> {
+    let content = load_file_content(file, locale)?;
+    logging::log(LogEvent::Content {
+        path: &file.relative_path,
+        encoding: content.encoding,
+        omission: content.omission,
+    });
+    Ok(content)
+}
+fn write_preamble(
+    inventory: &ProjectInventory,
+    options: &RenderOptions,
+    stats: &[ContentStats],
+    metadata: Option<&BundleMetadata>,
+    patches: Option<&Patches>,
+    renderer: &mut dyn Renderer,
+) -> io::Result<()> {
+    renderer.begin_document(&inventory.root_name)?;
+    if let Some(found) = metadata {
+        renderer.front_matter(found)?;
+    }
+    if options.summary {
+        renderer.project_summary(&summarize(&inventory.content_files, stats))?;
+    }
+    if let Some(found) = patches {
+        renderer.commit_log(&found.reference, &found.commits)?;
+    }
+    if options.mode.includes_tree() {
+        let mut tree_entries =
+            annotate_tree(&inventory.tree_entries, &inventory.content_files, stats)?;
+        if let Some(threshold) = options.tree.collapse_threshold {
+            tree_entries = collapse_tree(&tree_entries, threshold, options.locale);
+        }
+        renderer.begin_tree()?;
+        for entry in &tree_entries {
+            renderer.tree_entry(entry)?;
+        }
+        renderer.end_tree()?;
+    }
+    Ok(())
+}
+fn write_closing(inventory: &ProjectInventory, renderer: &mut dyn Renderer) -> io::Result<()> {
+    if !inventory.deleted_files.is_empty() {
+        renderer.deleted_files(&inventory.deleted_files)?;
+    }
+    renderer.end_document()
+}
+pub(crate) fn render_file_contents(
+    files: &[FileEntry],
+    locale: Locale,
+    patches: Option<&Patches>,
+    permalinks: Option<&Permalinks>,
+    renderer: &mut dyn Renderer,
+) -> AppResult<Duration> {
+    let mut read_elapsed = Duration::ZERO;
+    let contents = files.iter().map(|file| {
+        let started = Instant::now();
+        let content = load_logged(file, locale);
+        read_elapsed = read_elapsed.saturating_add(started.elapsed());
+        content
+    });
+    write_contents(files, contents, patches, permalinks, renderer)?;
+    Ok(read_elapsed)
+}
+fn write_contents<I>(
+    files: &[FileEntry],
+    contents: I,
+    patches: Option<&Patches>,
+    permalinks: Option<&Permalinks>,
+    renderer: &mut dyn Renderer,
+) -> AppResult<()>
+where
+    I: IntoIterator<Item = AppResult<FileContent>>,
+{
+    renderer
+        .begin_contents()
+        .map_err(|err| FailureKind::Output.wrap(err))?;
+    for (file, content) in files.iter().zip(contents) {
+        write_file(file, &content?, patches, permalinks, renderer)
+            .map_err(|err| FailureKind::Output.wrap(err))?;
+    }
+    renderer
+        .end_contents()
+        .map_err(|err| FailureKind::Output.wrap(err))
+}
+fn write_file(
+    file: &FileEntry,
+    content: &FileContent,
+    patches: Option<&Patches>,
+    permalinks: Option<&Permalinks>,
+    renderer: &mut dyn Renderer,
+) -> io::Result<()> {
+    let permalink = permalinks.map(|links| {
+        let lines = content
+            .omission
+            .is_none()
+            .then(|| count_lines(&content.text));
+        links.url(&file.relative_path, lines)
+    });
+    renderer.begin_file(&FileEntry {
+        omission: content.omission,
+        permalink: permalink.or_else(|| file.permalink.clone()),
+        ..file.clone()
+    })?;
+    renderer.content_chunk(&content.text)?;
+    if let Some(diff) = patches.and_then(|found| found.diffs.get(&file.relative_path)) {
+        renderer.file_diff(diff)?;
+    }
+    renderer.end_file()
+}
+#[cfg(test)]
+mod tests;
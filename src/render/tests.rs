@@ -0,0 +1,240 @@
+use super::{
+    DocumentMode, OutputFormat, RenderOptions, Renderer, render_inventory, render_project,
+};
+use crate::{
+    changes::ChangeScope,
+    errors::FailureKind,
+    front_matter::BundleMetadata,
+    inventory::{FileEntry, InventoryOptions, TreeEntry, collect_project_inventory},
+    patch::Commit,
+    stats::ProjectSummary,
+    test_support::{TestDir, must, must_err},
+};
+use std::{fs, io};
+#[derive(Default)]
+struct RecordingRenderer {
+    events: Vec<String>,
+}
+impl Renderer for RecordingRenderer {
+    fn begin_document(&mut self, _root_name: &str) -> io::Result<()> {
+        self.events.push("begin_document".to_owned());
+        Ok(())
+    }
+    fn front_matter(&mut self, _metadata: &BundleMetadata) -> io::Result<()> {
+        self.events.push("front_matter".to_owned());
+        Ok(())
+    }
+    fn project_summary(&mut self, summary: &ProjectSummary) -> io::Result<()> {
+        self.events
+            .push(format!("project_summary:{}", summary.file_count));
+        Ok(())
+    }
+    fn commit_log(&mut self, reference: &str, commits: &[Commit]) -> io::Result<()> {
+        self.events
+            .push(format!("commit_log:{reference}:{}", commits.len()));
+        Ok(())
+    }
+    fn begin_tree(&mut self) -> io::Result<()> {
+        self.events.push("begin_tree".to_owned());
+        Ok(())
+    }
+    fn tree_entry(&mut self, entry: &TreeEntry) -> io::Result<()> {
+        self.events.push(format!("tree_entry:{}", entry.name));
+        Ok(())
+    }
+    fn end_tree(&mut self) -> io::Result<()> {
+        self.events.push("end_tree".to_owned());
+        Ok(())
+    }
+    fn begin_contents(&mut self) -> io::Result<()> {
+        self.events.push("begin_contents".to_owned());
+        Ok(())
+    }
+    fn begin_file(&mut self, file: &FileEntry) -> io::Result<()> {
+        self.events
+            .push(format!("begin_file:{}", file.relative_path));
+        Ok(())
+    }
+    fn content_chunk(&mut self, chunk: &str) -> io::Result<()> {
+        self.events.push(format!("content_chunk:{chunk}"));
+        Ok(())
+    }
+    fn file_diff(&mut self, diff: &str) -> io::Result<()> {
+        self.events
+            .push(format!("file_diff:{}", diff.lines().count()));
+        Ok(())
+    }
+    fn end_file(&mut self) -> io::Result<()> {
+        self.events.push("end_file".to_owned());
+        Ok(())
+    }
+    fn end_contents(&mut self) -> io::Result<()> {
+        self.events.push("end_contents".to_owned());
+        Ok(())
+    }
+    fn deleted_files(&mut self, paths: &[String]) -> io::Result<()> {
+        self.events
+            .push(format!("deleted_files:{}", paths.join(",")));
+        Ok(())
+    }
+    fn end_document(&mut self) -> io::Result<()> {
+        self.events.push("end_document".to_owned());
+        Ok(())
+    }
+}
+#[test]
+fn render_project_drives_renderer_in_document_order() {
+    let dir = must(TestDir::new("render-order"), "创建测试目录失败");
+    must(dir.write_str("a.txt", "alpha"), "写入 a.txt 失败");
+    let mut renderer = RecordingRenderer::default();
+    must(render_project(dir.path(), &mut renderer), "渲染项目失败");
+    assert_eq!(
+        renderer.events,
+        [
+            "begin_document",
+            "begin_tree",
+            "tree_entry:a.txt",
+            "end_tree",
+            "begin_contents",
+            "begin_file:a.txt",
+            "content_chunk:alpha",
+            "end_file",
+            "end_contents",
+            "end_document",
+        ]
+    );
+}
+#[test]
+fn file_contents_are_read_as_each_file_is_rendered() {
+    let dir = must(TestDir::new("render-lazy"), "创建测试目录失败");
+    must(dir.write_str("a.txt", "alpha"), "写入 a.txt 失败");
+    must(dir.write_str("b.txt", "beta"), "写入 b.txt 失败");
+    let inventory = must(
+        collect_project_inventory(dir.path(), &InventoryOptions::default()),
+        "收集项目清单失败",
+    );
+    let first = must(
+        inventory.content_files.first().ok_or("缺少内容文件"),
+        "读取第一个文件失败",
+    );
+    let last = must(
+        inventory.content_files.last().ok_or("缺少内容文件"),
+        "读取最后一个文件失败",
+    );
+    must(fs::remove_file(&last.absolute_path), "删除文件失败");
+    let mut renderer = RecordingRenderer::default();
+    let err = must_err(
+        render_inventory(&inventory, &RenderOptions::default(), &mut renderer),
+        "缺失的文件应当导致读取失败",
+    );
+    assert_eq!(err.kind(), FailureKind::Read);
+    assert!(
+        renderer
+            .events
+            .contains(&format!("begin_file:{}", first.relative_path))
+    );
+    assert!(!renderer.events.contains(&"end_contents".to_owned()));
+}
+#[test]
+fn summary_reads_every_file_once_before_writing() {
+    let dir = must(TestDir::new("render-preload"), "创建测试目录失败");
+    must(dir.write_str("a.txt", "alpha"), "写入 a.txt 失败");
+    must(dir.write_str("b.txt", "beta"), "写入 b.txt 失败");
+    let inventory = must(
+        collect_project_inventory(dir.path(), &InventoryOptions::default()),
+        "收集项目清单失败",
+    );
+    let last = must(
+        inventory.content_files.last().ok_or("缺少内容文件"),
+        "读取最后一个文件失败",
+    );
+    must(fs::remove_file(&last.absolute_path), "删除文件失败");
+    let options = RenderOptions {
+        summary: true,
+        ..RenderOptions::default()
+    };
+    let mut renderer = RecordingRenderer::default();
+    let err = must_err(
+        render_inventory(&inventory, &options, &mut renderer),
+        "缺失的文件应当导致读取失败",
+    );
+    assert_eq!(err.kind(), FailureKind::Read);
+    assert!(renderer.events.is_empty());
+}
+#[test]
+fn output_format_parses_known_names() {
+    assert_eq!(OutputFormat::parse("md"), Some(OutputFormat::Markdown));
+    assert_eq!(OutputFormat::parse("xml"), Some(OutputFormat::Xml));
+    assert_eq!(OutputFormat::parse("yaml"), None);
+    assert_eq!(OutputFormat::Json.output_filename(), "project.json");
+}
+#[test]
+fn document_modes_skip_sections_and_selection_limits_contents() {
+    let dir = must(TestDir::new("render-modes"), "创建测试目录失败");
+    must(dir.write_str("a.txt", "alpha"), "写入 a.txt 失败");
+    must(dir.write_str("b.rs", "beta"), "写入 b.rs 失败");
+    let inventory_options = InventoryOptions {
+        content_selection: vec!["*.rs".to_owned()],
+        ..InventoryOptions::default()
+    };
+    let inventory = must(
+        collect_project_inventory(dir.path(), &inventory_options),
+        "收集项目清单失败",
+    );
+    assert_eq!(inventory.tree_entries.len(), 2);
+    let render = |mode| {
+        let mut renderer = RecordingRenderer::default();
+        let options = RenderOptions {
+            mode,
+            ..RenderOptions::default()
+        };
+        must(
+            render_inventory(&inventory, &options, &mut renderer),
+            "渲染项目失败",
+        );
+        renderer.events
+    };
+    let tree_only = render(DocumentMode::TreeOnly);
+    assert!(tree_only.contains(&"begin_tree".to_owned()));
+    assert!(!tree_only.contains(&"begin_contents".to_owned()));
+    let content_only = render(DocumentMode::ContentOnly);
+    assert!(!content_only.contains(&"begin_tree".to_owned()));
+    assert!(content_only.contains(&"begin_file:b.rs".to_owned()));
+    assert!(!content_only.contains(&"begin_file:a.txt".to_owned()));
+    assert_eq!(DocumentMode::parse("tree"), Some(DocumentMode::TreeOnly));
+}
+#[test]
+fn change_scope_limits_contents_and_lists_deleted_files() {
+    let dir = must(TestDir::new("render-changes"), "创建测试目录失败");
+    must(dir.write_str("a.rs", "alpha"), "写入 a.rs 失败");
+    must(dir.write_str("b.rs", "beta"), "写入 b.rs 失败");
+    must(dir.write_str("gone.rs", "gone"), "写入 gone.rs 失败");
+    must(dir.git(&["init", "-q"]), "初始化仓库失败");
+    must(dir.git(&["add", "-A"]), "暂存文件失败");
+    must(dir.git(&["commit", "-q", "-m", "init"]), "提交失败");
+    must(dir.write_str("b.rs", "beta 2"), "修改 b.rs 失败");
+    must(
+        fs::remove_file(dir.path().join("gone.rs")),
+        "删除 gone.rs 失败",
+    );
+    let inventory_options = InventoryOptions {
+        changes: Some(ChangeScope::Uncommitted),
+        ..InventoryOptions::default()
+    };
+    let inventory = must(
+        collect_project_inventory(dir.path(), &inventory_options),
+        "收集项目清单失败",
+    );
+    assert_eq!(inventory.tree_entries.len(), 2);
+    let mut renderer = RecordingRenderer::default();
+    must(
+        render_inventory(&inventory, &RenderOptions::default(), &mut renderer),
+        "渲染项目失败",
+    );
+    assert!(renderer.events.contains(&"begin_file:b.rs".to_owned()));
+    assert!(!renderer.events.contains(&"begin_file:a.rs".to_owned()));
+    assert_eq!(
+        renderer.events.get(renderer.events.len().saturating_sub(2)),
+        Some(&"deleted_files:gone.rs".to_owned())
+    );
+}
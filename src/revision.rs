@@ -0,0 +1,111 @@
+use crate::{
+    git::{git_bytes, git_stdout, resolve_commit},
+    i18n::{Message, tr},
+    logging::Rule,
+};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+const GITIGNORE_FILENAME: &str = ".gitignore";
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct RevisionFile {
+    pub(crate) relative_path: PathBuf,
+    pub(crate) object: String,
+    pub(crate) size: u64,
+    pub(crate) ignored: Option<Rule>,
+}
+struct IgnoreLayer {
+    directory: PathBuf,
+    matcher: Gitignore,
+}
+pub(crate) fn revision_files(root_path: &Path, reference: &str) -> io::Result<Vec<RevisionFile>> {
+    let commit = resolve_commit(root_path, reference)?;
+    let listing = git_stdout(root_path, &["ls-tree", "-r", "-z", "--long", &commit])?;
+    let mut files = Vec::new();
+    for record in listing.split('\0').filter(|record| !record.is_empty()) {
+        let (header, path) = record
+            .split_once('\t')
+            .ok_or_else(|| listing_error(record))?;
+        let mut fields = header.split_whitespace();
+        let (Some(_mode), Some(kind), Some(object), Some(size)) =
+            (fields.next(), fields.next(), fields.next(), fields.next())
+        else {
+            return Err(listing_error(record));
+        };
+        if kind != "blob" {
+            continue;
+        }
+        files.push(RevisionFile {
+            relative_path: path.split('/').collect(),
+            object: object.to_owned(),
+            size: size.parse().map_err(|_err| listing_error(record))?,
+            ignored: None,
+        });
+    }
+    let layers = ignore_layers(root_path, &files)?;
+    for file in &mut files {
+        file.ignored = ignored_by(root_path, &layers, &file.relative_path);
+    }
+    Ok(files)
+}
+pub(crate) fn read_object(path: &Path, object: &str) -> io::Result<Vec<u8>> {
+    let directory = path
+        .ancestors()
+        .find(|ancestor| ancestor.is_dir())
+        .unwrap_or_else(|| Path::new("."));
+    git_bytes(directory, &["cat-file", "blob", object])
+}
+fn ignore_layers(root_path: &Path, files: &[RevisionFile]) -> io::Result<Vec<IgnoreLayer>> {
+    let mut layers = Vec::new();
+    for file in files
+        .iter()
+        .filter(|file| file.relative_path.file_name() == Some(GITIGNORE_FILENAME.as_ref()))
+    {
+        let directory =
+            root_path.join(file.relative_path.parent().unwrap_or_else(|| Path::new("")));
+        let bytes = git_bytes(root_path, &["cat-file", "blob", &file.object])?;
+        let mut builder = GitignoreBuilder::new(&directory);
+        for line in String::from_utf8_lossy(&bytes).lines() {
+            builder.add_line(None, line).map_err(|err| {
+                io::Error::other(tr(
+                    Message::IgnoreFileFailed,
+                    &[&file.relative_path.display(), &err],
+                ))
+            })?;
+        }
+        let matcher = builder.build().map_err(|err| {
+            io::Error::other(tr(
+                Message::IgnoreFileFailed,
+                &[&file.relative_path.display(), &err],
+            ))
+        })?;
+        layers.push(IgnoreLayer { directory, matcher });
+    }
+    layers.sort_by_key(|layer| core::cmp::Reverse(layer.directory.components().count()));
+    Ok(layers)
+}
+fn ignored_by(root_path: &Path, layers: &[IgnoreLayer], relative_path: &Path) -> Option<Rule> {
+    if relative_path
+        .components()
+        .any(|component| component.as_os_str().to_string_lossy().starts_with('.'))
+    {
+        return Some(Rule::Hidden);
+    }
+    let path = root_path.join(relative_path);
+    let matched = layers
+        .iter()
+        .filter(|layer| path.starts_with(&layer.directory))
+        .map(|layer| layer.matcher.matched_path_or_any_parents(&path, false))
+        .find(|matched| !matched.is_none())?;
+    matched.is_ignore().then_some(Rule::Gitignore)
+}
+fn listing_error(record: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        tr(Message::GitTreeLineInvalid, &[&record]),
+    )
+}
+#[cfg(test)]
+mod tests;
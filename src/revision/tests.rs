@@ -0,0 +1,140 @@
+use super::revision_files;
+use crate::{
+    content::load_file_content,
+    i18n::Locale,
+    inventory::{InventoryOptions, collect_project_inventory},
+    logging::Rule,
+    test_support::{TestDir, must, must_err},
+};
+fn tagged_repository(name: &str) -> TestDir {
+    let dir = must(TestDir::new(name), "创建测试目录失败");
+    must(
+        dir.write_str(".gitignore", "*.log\n"),
+        "写入 .gitignore 失败",
+    );
+    must(
+        dir.write_str("src/main.rs", "fn main() {}\n"),
+        "写入 main.rs 失败",
+    );
+    must(
+        dir.write_str("src/.gitignore", "generated.rs\n"),
+        "写入 src/.gitignore 失败",
+    );
+    must(
+        dir.write_str("src/generated.rs", "fn generated() {}\n"),
+        "写入 generated.rs 失败",
+    );
+    must(dir.write_str("debug.log", "trace\n"), "写入 debug.log 失败");
+    must(dir.git(&["init", "-q"]), "初始化仓库失败");
+    must(dir.git(&["add", "-A", "-f"]), "暂存文件失败");
+    must(dir.git(&["commit", "-q", "-m", "init"]), "提交失败");
+    must(dir.git(&["tag", "v1"]), "创建标签失败");
+    dir
+}
+#[test]
+fn revision_files_apply_gitignore_rules_from_that_revision() {
+    let dir = tagged_repository("revision-ignore");
+    must(dir.write_str(".gitignore", "\n"), "修改 .gitignore 失败");
+    let files = must(revision_files(dir.path(), "v1"), "读取 git 树失败");
+    let decisions: Vec<_> = files
+        .iter()
+        .map(|file| {
+            (
+                file.relative_path.to_string_lossy().into_owned(),
+                file.ignored,
+            )
+        })
+        .collect();
+    assert_eq!(
+        decisions,
+        [
+            (".gitignore".to_owned(), Some(Rule::Hidden)),
+            ("debug.log".to_owned(), Some(Rule::Gitignore)),
+            ("src/.gitignore".to_owned(), Some(Rule::Hidden)),
+            ("src/generated.rs".to_owned(), Some(Rule::Gitignore)),
+            ("src/main.rs".to_owned(), None),
+        ]
+    );
+}
+#[test]
+fn revision_inventory_reads_contents_from_git_objects() {
+    let dir = tagged_repository("revision-inventory");
+    must(
+        dir.write_str("src/main.rs", "fn main() { changed(); }\n"),
+        "修改 main.rs 失败",
+    );
+    must(
+        dir.write_str("src/extra.rs", "fn extra() {}\n"),
+        "写入 extra.rs 失败",
+    );
+    let options = InventoryOptions {
+        revision: Some("v1".to_owned()),
+        ..InventoryOptions::default()
+    };
+    let inventory = must(
+        collect_project_inventory(dir.path(), &options),
+        "收集修订版本清单失败",
+    );
+    let paths: Vec<_> = inventory
+        .tree_entries
+        .iter()
+        .map(|entry| entry.relative_path.as_str())
+        .collect();
+    assert_eq!(paths, ["src", "src/main.rs"]);
+    let file = must(
+        inventory.content_files.first().ok_or("缺少内容文件"),
+        "读取内容文件失败",
+    );
+    assert_eq!(file.code_block_language, "rust");
+    let content = must(load_file_content(file, Locale::ZhCn), "读取 git 对象失败");
+    assert_eq!(content.text, "fn main() {}\n");
+}
+#[test]
+fn unknown_revision_is_reported() {
+    let dir = tagged_repository("revision-unknown");
+    let err = must_err(
+        revision_files(dir.path(), "missing"),
+        "未知修订版本必须报错",
+    );
+    assert!(err.to_string().contains("missing"));
+    let option = must_err(
+        revision_files(dir.path(), "--output=injected.txt"),
+        "以 - 开头的修订版本必须报错",
+    );
+    assert!(option.to_string().contains("--output=injected.txt"));
+    assert!(!dir.path().join("injected.txt").exists());
+}
+#[test]
+fn revision_contents_ignore_working_tree_encoding() {
+    let dir = must(TestDir::new("revision-encoding"), "创建测试目录失败");
+    must(
+        dir.write_str(".gitattributes", "*.txt working-tree-encoding=UTF-16LE\n"),
+        "写入 .gitattributes 失败",
+    );
+    must(
+        dir.write_bytes("note.txt", &[b'h', 0, b'i', 0]),
+        "写入 UTF-16LE 文件失败",
+    );
+    must(dir.git(&["init", "-q"]), "初始化仓库失败");
+    must(dir.git(&["add", "-A"]), "暂存文件失败");
+    must(dir.git(&["commit", "-q", "-m", "init"]), "提交失败");
+    let options = InventoryOptions {
+        revision: Some("HEAD".to_owned()),
+        ..InventoryOptions::default()
+    };
+    let inventory = must(
+        collect_project_inventory(dir.path(), &options),
+        "收集修订版本清单失败",
+    );
+    let file = must(
+        inventory
+            .content_files
+            .iter()
+            .find(|file| file.relative_path == "note.txt")
+            .ok_or("缺少 note.txt"),
+        "读取内容文件失败",
+    );
+    assert_eq!(file.declared_encoding.as_deref(), Some("UTF-16LE"));
+    let content = must(load_file_content(file, Locale::ZhCn), "读取 git 对象失败");
+    assert_eq!(content.text, "hi");
+}
@@ -0,0 +1,132 @@
+use crate::{
+    content::{FileContent, count_lines},
+    inventory::{FileEntry, Omission},
+    tokens::estimate_tokens,
+};
+use alloc::collections::BTreeMap;
+use core::cmp::Reverse;
+const LARGEST_FILES_LIMIT: usize = 5;
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub(crate) struct ContentStats {
+    pub(crate) lines: usize,
+    pub(crate) tokens: usize,
+    pub(crate) encoding: Option<&'static str>,
+    pub(crate) omission: Option<Omission>,
+}
+impl ContentStats {
+    pub(crate) fn measure(content: &FileContent) -> Self {
+        let (lines, tokens) = if content.omission.is_none() {
+            (count_lines(&content.text), estimate_tokens(&content.text))
+        } else {
+            (0, 0)
+        };
+        Self {
+            lines,
+            tokens,
+            encoding: content.encoding,
+            omission: content.omission,
+        }
+    }
+}
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ProjectSummary {
+    pub file_count: usize,
+    pub total_bytes: u64,
+    pub total_lines: usize,
+    pub total_tokens: usize,
+    pub languages: Vec<LanguageCount>,
+    pub largest_files: Vec<LargestFile>,
+    pub binary_files: usize,
+    pub too_large_files: usize,
+    pub undecodable_files: usize,
+    pub encodings: Vec<EncodingCount>,
+}
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct LanguageCount {
+    pub language: String,
+    pub files: usize,
+    pub lines: usize,
+}
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct LargestFile {
+    pub path: String,
+    pub bytes: u64,
+}
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct EncodingCount {
+    pub encoding: String,
+    pub files: usize,
+}
+pub(crate) fn summarize(files: &[FileEntry], contents: &[ContentStats]) -> ProjectSummary {
+    let mut summary = ProjectSummary::default();
+    let mut languages: BTreeMap<&str, LanguageCount> = BTreeMap::new();
+    let mut encodings: BTreeMap<&str, usize> = BTreeMap::new();
+    for (file, content) in files.iter().zip(contents) {
+        summary.file_count = summary.file_count.saturating_add(1);
+        summary.total_bytes = summary.total_bytes.saturating_add(file.size);
+        let language = languages
+            .entry(file.code_block_language.as_str())
+            .or_insert_with(|| LanguageCount {
+                language: file.code_block_language.clone(),
+                ..LanguageCount::default()
+            });
+        language.files = language.files.saturating_add(1);
+        match content.omission {
+            None => {
+                language.lines = language.lines.saturating_add(content.lines);
+                summary.total_lines = summary.total_lines.saturating_add(content.lines);
+                summary.total_tokens = summary.total_tokens.saturating_add(content.tokens);
+            }
+            Some(Omission::Binary) => {
+                summary.binary_files = summary.binary_files.saturating_add(1);
+            }
+            Some(Omission::TooLarge) => {
+                summary.too_large_files = summary.too_large_files.saturating_add(1);
+            }
+            Some(Omission::Undecodable) => {
+                summary.undecodable_files = summary.undecodable_files.saturating_add(1);
+            }
+            Some(Omission::Excluded) => {}
+        }
+        if let Some(encoding) = content.encoding {
+            let count = encodings.entry(encoding).or_default();
+            *count = count.saturating_add(1);
+        }
+    }
+    summary.languages = languages.into_values().collect();
+    summary.languages.sort_by(|left, right| {
+        right
+            .lines
+            .cmp(&left.lines)
+            .then_with(|| right.files.cmp(&left.files))
+            .then_with(|| left.language.cmp(&right.language))
+    });
+    let mut largest: Vec<&FileEntry> = files.iter().collect();
+    largest.sort_by(|left, right| {
+        right
+            .size
+            .cmp(&left.size)
+            .then_with(|| left.relative_path.cmp(&right.relative_path))
+    });
+    summary.largest_files = largest
+        .into_iter()
+        .take(LARGEST_FILES_LIMIT)
+        .map(|file| LargestFile {
+            path: file.relative_path.clone(),
+            bytes: file.size,
+        })
+        .collect();
+    summary.encodings = encodings
+        .into_iter()
+        .map(|(encoding, count)| EncodingCount {
+            encoding: encoding.to_owned(),
+            files: count,
+        })
+        .collect();
+    summary
+        .encodings
+        .sort_by_key(|encoding| Reverse(encoding.files));
+    summary
+}
+#[cfg(test)]
+mod tests;
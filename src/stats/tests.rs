@@ -0,0 +1,67 @@
+use super::{ContentStats, summarize};
+use crate::{
+    content::load_file_content,
+    i18n::Locale,
+    inventory::{InventoryOptions, collect_project_inventory},
+    test_support::{TestDir, must},
+};
+#[test]
+fn summary_counts_languages_omissions_and_encodings() {
+    let dir = must(TestDir::new("summary-counts"), "创建测试目录失败");
+    must(
+        dir.write_str("src/main.rs", "fn main() {}\nfn helper() {}\n"),
+        "写入 main.rs 失败",
+    );
+    must(
+        dir.write_str("src/lib.rs", "pub mod a;\n"),
+        "写入 lib.rs 失败",
+    );
+    must(
+        dir.write_bytes(
+            "logo.bin",
+            &[
+                0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22,
+                23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38, 39,
+            ],
+        ),
+        "写入 logo.bin 失败",
+    );
+    let inventory = must(
+        collect_project_inventory(dir.path(), &InventoryOptions::default()),
+        "收集项目清单失败",
+    );
+    let contents: Vec<_> = inventory
+        .content_files
+        .iter()
+        .map(|file| {
+            ContentStats::measure(&must(
+                load_file_content(file, Locale::ZhCn),
+                "读取文件内容失败",
+            ))
+        })
+        .collect();
+    let summary = summarize(&inventory.content_files, &contents);
+    assert_eq!(summary.file_count, 3);
+    assert_eq!(summary.total_bytes, 28 + 11 + 40);
+    assert_eq!(summary.total_lines, 3);
+    assert_eq!(summary.binary_files, 1);
+    assert_eq!(
+        summary.languages.first().map(|language| (
+            language.language.as_str(),
+            language.files,
+            language.lines
+        )),
+        Some(("rust", 2, 3))
+    );
+    assert_eq!(
+        summary.largest_files.first().map(|file| file.path.as_str()),
+        Some("logo.bin")
+    );
+    assert_eq!(
+        summary
+            .encodings
+            .first()
+            .map(|encoding| (encoding.encoding.as_str(), encoding.files)),
+        Some(("UTF-8", 2))
+    );
+}
@@ -0,0 +1,532 @@
+use crate::{
+    content::count_lines,
+    front_matter::{BundleMetadata, front_matter_yaml},
+    i18n::{Locale, Message, tr},
+    inventory::{FileEntry, Omission, TreeEntry, TreeEntryKind},
+    markdown::{code_fence, commit_log_markdown, escape_heading, summary_markdown},
+    patch::{Commit, HEAD},
+    render::Renderer,
+    stats::ProjectSummary,
+    tokens::estimate_tokens,
+};
+use std::{fs, io, path::Path};
+const GLOBAL_VARIABLES: [&str; 7] = [
+    "root",
+    "summary",
+    "commit_log",
+    "front_matter",
+    "file_count",
+    "total_lines",
+    "total_tokens",
+];
+const FILE_VARIABLES: [&str; 12] = [
+    "path",
+    "heading",
+    "fence",
+    "language",
+    "lines",
+    "tokens",
+    "bytes",
+    "content",
+    "diff",
+    "binary",
+    "untracked",
+    "permalink",
+];
+const TREE_VARIABLES: [&str; 5] = ["name", "depth", "indent", "kind", "is_dir"];
+const DELETED_VARIABLES: [&str; 1] = ["path"];
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct Template {
+    nodes: Vec<Node>,
+}
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Node {
+    kind: NodeKind,
+    text: String,
+    children: Vec<Self>,
+    alternative: Vec<Self>,
+}
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum NodeKind {
+    Text,
+    Variable,
+    Each(Collection),
+    If,
+}
+impl Node {
+    const fn leaf(kind: NodeKind, text: String) -> Self {
+        Self {
+            kind,
+            text,
+            children: Vec::new(),
+            alternative: Vec::new(),
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Collection {
+    Files,
+    Tree,
+    Deleted,
+}
+impl Collection {
+    const fn variables(self) -> &'static [&'static str] {
+        match self {
+            Self::Files => &FILE_VARIABLES,
+            Self::Tree => &TREE_VARIABLES,
+            Self::Deleted => &DELETED_VARIABLES,
+        }
+    }
+}
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Token {
+    Text(String),
+    Tag(String),
+}
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum BlockEnd {
+    Eof,
+    Each,
+    Else,
+    If,
+}
+impl Template {
+    pub(crate) fn load(path: &Path) -> io::Result<Self> {
+        let source = fs::read_to_string(path).map_err(|err| {
+            io::Error::new(
+                err.kind(),
+                tr(Message::ReadTemplateFailed, &[&path.display(), &err]),
+            )
+        })?;
+        Self::parse(&source)
+            .map_err(|err| io::Error::new(err.kind(), format!("{}: {err}", path.display())))
+    }
+    pub(crate) fn parse(source: &str) -> io::Result<Self> {
+        let mut tokens = tokenize(source)?.into_iter();
+        let (nodes, end) = parse_nodes(&mut tokens)?;
+        if end != BlockEnd::Eof {
+            return Err(template_error(&tr(Message::TemplateUnexpectedClose, &[])));
+        }
+        check_variables(&nodes, &mut Vec::new())?;
+        Ok(Self { nodes })
+    }
+}
+fn check_variables(nodes: &[Node], scopes: &mut Vec<Collection>) -> io::Result<()> {
+    for node in nodes {
+        match node.kind {
+            NodeKind::Text => {}
+            NodeKind::Variable => check_variable(&node.text, scopes)?,
+            NodeKind::Each(collection) => {
+                scopes.push(collection);
+                let result = check_variables(&node.children, scopes);
+                scopes.pop();
+                result?;
+            }
+            NodeKind::If => {
+                check_variable(&node.text, scopes)?;
+                check_variables(&node.children, scopes)?;
+                check_variables(&node.alternative, scopes)?;
+            }
+        }
+    }
+    Ok(())
+}
+fn check_variable(name: &str, scopes: &[Collection]) -> io::Result<()> {
+    if GLOBAL_VARIABLES.contains(&name)
+        || scopes
+            .iter()
+            .any(|collection| collection.variables().contains(&name))
+    {
+        Ok(())
+    } else {
+        Err(template_error(&tr(
+            Message::TemplateUnknownVariable,
+            &[&name],
+        )))
+    }
+}
+fn tokenize(source: &str) -> io::Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut rest = source;
+    let mut at_line_start = true;
+    while let Some(open) = rest.find("{{") {
+        let (mut text, tag_start) = rest
+            .split_at_checked(open)
+            .ok_or_else(|| template_error(&tr(Message::TemplateTagPosition, &[])))?;
+        let after_open = tag_start.get(2..).unwrap_or_default();
+        let close = after_open
+            .find("}}")
+            .ok_or_else(|| template_error(&tr(Message::TemplateUnclosedTag, &[])))?;
+        let tag = after_open.get(..close).unwrap_or_default().trim();
+        rest = after_open
+            .get(close.saturating_add(2)..)
+            .unwrap_or_default();
+        at_line_start = line_start_after(text, at_line_start);
+        let standalone_rest = standalone_remainder(rest);
+        if is_block_tag(tag)
+            && at_line_start
+            && let Some(remainder) = standalone_rest
+        {
+            text = text.trim_end_matches([' ', '\t']);
+            rest = remainder;
+        } else {
+            at_line_start = false;
+        }
+        if !text.is_empty() {
+            tokens.push(Token::Text(text.to_owned()));
+        }
+        tokens.push(Token::Tag(tag.to_owned()));
+    }
+    if !rest.is_empty() {
+        tokens.push(Token::Text(rest.to_owned()));
+    }
+    Ok(tokens)
+}
+fn line_start_after(text: &str, was_line_start: bool) -> bool {
+    let is_blank = |segment: &str| {
+        segment
+            .chars()
+            .all(|character| character == ' ' || character == '\t')
+    };
+    text.rsplit_once('\n').map_or_else(
+        || was_line_start && is_blank(text),
+        |(_, last_line)| is_blank(last_line),
+    )
+}
+fn standalone_remainder(rest: &str) -> Option<&str> {
+    let (raw_line, remainder) = rest.split_once('\n').unwrap_or((rest, ""));
+    let line = raw_line.strip_suffix('\r').unwrap_or(raw_line);
+    line.chars()
+        .all(|character| character == ' ' || character == '\t')
+        .then_some(remainder)
+}
+fn is_block_tag(tag: &str) -> bool {
+    tag.starts_with('#') || tag.starts_with('/') || tag == "else"
+}
+fn parse_nodes<I>(tokens: &mut I) -> io::Result<(Vec<Node>, BlockEnd)>
+where
+    I: Iterator<Item = Token>,
+{
+    let mut nodes = Vec::new();
+    while let Some(token) = tokens.next() {
+        let tag = match token {
+            Token::Text(text) => {
+                nodes.push(Node::leaf(NodeKind::Text, text));
+                continue;
+            }
+            Token::Tag(tag) => tag,
+        };
+        match tag.as_str() {
+            "/each" => return Ok((nodes, BlockEnd::Each)),
+            "/if" => return Ok((nodes, BlockEnd::If)),
+            "else" => return Ok((nodes, BlockEnd::Else)),
+            _ => {}
+        }
+        if let Some(name) = tag.strip_prefix("#each ") {
+            nodes.push(parse_each(name.trim(), tokens)?);
+        } else if let Some(condition) = tag.strip_prefix("#if ") {
+            nodes.push(parse_if(condition.trim(), tokens)?);
+        } else if tag.starts_with('#') || tag.starts_with('/') {
+            return Err(template_error(&tr(Message::TemplateUnknownTag, &[&tag])));
+        } else {
+            nodes.push(Node::leaf(NodeKind::Variable, tag));
+        }
+    }
+    Ok((nodes, BlockEnd::Eof))
+}
+fn parse_each<I>(name: &str, tokens: &mut I) -> io::Result<Node>
+where
+    I: Iterator<Item = Token>,
+{
+    let collection = match name {
+        "files" => Collection::Files,
+        "tree" => Collection::Tree,
+        "deleted" => Collection::Deleted,
+        _ => {
+            return Err(template_error(&tr(
+                Message::TemplateUnknownCollection,
+                &[&name],
+            )));
+        }
+    };
+    let (body, end) = parse_nodes(tokens)?;
+    if end != BlockEnd::Each {
+        return Err(template_error(&tr(Message::TemplateUnclosedEach, &[])));
+    }
+    Ok(Node {
+        children: body,
+        ..Node::leaf(NodeKind::Each(collection), name.to_owned())
+    })
+}
+fn parse_if<I>(condition: &str, tokens: &mut I) -> io::Result<Node>
+where
+    I: Iterator<Item = Token>,
+{
+    let (then_branch, first_end) = parse_nodes(tokens)?;
+    let else_branch = match first_end {
+        BlockEnd::If => Vec::new(),
+        BlockEnd::Else => {
+            let (else_branch, second_end) = parse_nodes(tokens)?;
+            if second_end != BlockEnd::If {
+                return Err(template_error(&tr(Message::TemplateElseWithoutIf, &[])));
+            }
+            else_branch
+        }
+        BlockEnd::Eof | BlockEnd::Each => {
+            return Err(template_error(&tr(Message::TemplateUnclosedIf, &[])));
+        }
+    };
+    Ok(Node {
+        kind: NodeKind::If,
+        text: condition.to_owned(),
+        children: then_branch,
+        alternative: else_branch,
+    })
+}
+fn template_error(reason: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        tr(Message::TemplateError, &[&reason]),
+    )
+}
+struct RenderedFile {
+    entry: FileEntry,
+    content: String,
+    diff: String,
+}
+#[derive(Default)]
+struct TemplateData {
+    locale: Locale,
+    revision: Option<String>,
+    root_name: String,
+    front_matter: String,
+    summary: String,
+    commit_log: String,
+    tree: Vec<TreeEntry>,
+    files: Vec<RenderedFile>,
+    deleted: Vec<String>,
+}
+#[derive(Clone, Copy)]
+enum Scope<'data> {
+    File(&'data RenderedFile),
+    Tree(&'data TreeEntry),
+    Deleted(&'data str),
+}
+pub(crate) struct TemplateRenderer<'writer, W> {
+    writer: &'writer mut W,
+    template: Template,
+    data: TemplateData,
+}
+impl<'writer, W> TemplateRenderer<'writer, W>
+where
+    W: io::Write,
+{
+    pub(crate) fn new(
+        writer: &'writer mut W,
+        template: Template,
+        revision: Option<String>,
+        locale: Locale,
+    ) -> Self {
+        Self {
+            writer,
+            template,
+            data: TemplateData {
+                locale,
+                revision,
+                ..TemplateData::default()
+            },
+        }
+    }
+}
+fn render_nodes<'data, W>(
+    nodes: &[Node],
+    data: &'data TemplateData,
+    scopes: &mut Vec<Scope<'data>>,
+    writer: &mut W,
+) -> io::Result<()>
+where
+    W: io::Write,
+{
+    for node in nodes {
+        match node.kind {
+            NodeKind::Text => writer.write_all(node.text.as_bytes())?,
+            NodeKind::Variable => {
+                writer.write_all(data.lookup(&node.text, scopes)?.as_bytes())?;
+            }
+            NodeKind::Each(collection) => {
+                let items: Vec<Scope<'data>> = match collection {
+                    Collection::Files => data.files.iter().map(Scope::File).collect(),
+                    Collection::Tree => data.tree.iter().map(Scope::Tree).collect(),
+                    Collection::Deleted => data
+                        .deleted
+                        .iter()
+                        .map(|path| Scope::Deleted(path))
+                        .collect(),
+                };
+                for item in items {
+                    scopes.push(item);
+                    let result = render_nodes(&node.children, data, scopes, writer);
+                    scopes.pop();
+                    result?;
+                }
+            }
+            NodeKind::If => {
+                let branch = if is_truthy(&data.lookup(&node.text, scopes)?) {
+                    &node.children
+                } else {
+                    &node.alternative
+                };
+                render_nodes(branch, data, scopes, writer)?;
+            }
+        }
+    }
+    Ok(())
+}
+impl TemplateData {
+    fn lookup(&self, name: &str, scopes: &[Scope<'_>]) -> io::Result<String> {
+        for scope in scopes.iter().rev() {
+            let value = match *scope {
+                Scope::File(file) => file_variable(file, name),
+                Scope::Tree(entry) => tree_variable(entry, name),
+                Scope::Deleted(path) => (name == "path").then(|| path.to_owned()),
+            };
+            if let Some(found) = value {
+                return Ok(found);
+            }
+        }
+        let value = match name {
+            "root" => Some(self.root_name.clone()),
+            "summary" => Some(self.summary.clone()),
+            "commit_log" => Some(self.commit_log.clone()),
+            "front_matter" => Some(self.front_matter.clone()),
+            "file_count" => Some(self.files.len().to_string()),
+            "total_lines" => Some(
+                self.files
+                    .iter()
+                    .map(|file| count_lines(&file.content))
+                    .sum::<usize>()
+                    .to_string(),
+            ),
+            "total_tokens" => Some(
+                self.files
+                    .iter()
+                    .map(|file| estimate_tokens(&file.content))
+                    .sum::<usize>()
+                    .to_string(),
+            ),
+            _ => None,
+        };
+        value.ok_or_else(|| template_error(&tr(Message::TemplateUnknownVariable, &[&name])))
+    }
+}
+fn file_variable(file: &RenderedFile, name: &str) -> Option<String> {
+    match name {
+        "path" => Some(file.entry.relative_path.clone()),
+        "heading" => Some(escape_heading(&file.entry.relative_path)),
+        "fence" => Some(code_fence(&file.content, &file.entry.code_block_language)),
+        "language" => Some(file.entry.code_block_language.clone()),
+        "lines" => Some(count_lines(&file.content).to_string()),
+        "tokens" => Some(estimate_tokens(&file.content).to_string()),
+        "bytes" => Some(file.content.len().to_string()),
+        "content" => Some(file.content.clone()),
+        "diff" => Some(file.diff.clone()),
+        "binary" => Some((file.entry.omission == Some(Omission::Binary)).to_string()),
+        "untracked" => Some(file.entry.untracked.to_string()),
+        "permalink" => Some(file.entry.permalink.clone().unwrap_or_default()),
+        _ => None,
+    }
+}
+fn tree_variable(entry: &TreeEntry, name: &str) -> Option<String> {
+    match name {
+        "name" => Some(entry.name.clone()),
+        "depth" => Some(entry.depth.to_string()),
+        "indent" => Some("    ".repeat(entry.depth)),
+        "kind" => Some(entry.kind.name().to_owned()),
+        "is_dir" => Some(
+            matches!(
+                entry.kind,
+                TreeEntryKind::Directory | TreeEntryKind::Ignored | TreeEntryKind::Hidden
+            )
+            .to_string(),
+        ),
+        _ => None,
+    }
+}
+fn is_truthy(value: &str) -> bool {
+    !matches!(value, "" | "0" | "false")
+}
+impl<W> Renderer for TemplateRenderer<'_, W>
+where
+    W: io::Write,
+{
+    fn begin_document(&mut self, root_name: &str) -> io::Result<()> {
+        root_name.clone_into(&mut self.data.root_name);
+        Ok(())
+    }
+    fn front_matter(&mut self, metadata: &BundleMetadata) -> io::Result<()> {
+        self.data.front_matter = front_matter_yaml(metadata);
+        Ok(())
+    }
+    fn project_summary(&mut self, summary: &ProjectSummary) -> io::Result<()> {
+        self.data.summary = summary_markdown(summary, self.data.locale);
+        Ok(())
+    }
+    fn commit_log(&mut self, reference: &str, commits: &[Commit]) -> io::Result<()> {
+        let target = self.data.revision.as_deref().unwrap_or(HEAD);
+        self.data.commit_log = commit_log_markdown(reference, target, commits, self.data.locale);
+        Ok(())
+    }
+    fn begin_tree(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+    fn tree_entry(&mut self, entry: &TreeEntry) -> io::Result<()> {
+        self.data.tree.push(entry.clone());
+        Ok(())
+    }
+    fn end_tree(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+    fn begin_contents(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+    fn begin_file(&mut self, file: &FileEntry) -> io::Result<()> {
+        self.data.files.push(RenderedFile {
+            entry: file.clone(),
+            content: String::new(),
+            diff: String::new(),
+        });
+        Ok(())
+    }
+    fn content_chunk(&mut self, chunk: &str) -> io::Result<()> {
+        if let Some(file) = self.data.files.last_mut() {
+            file.content.push_str(chunk);
+        }
+        Ok(())
+    }
+    fn file_diff(&mut self, diff: &str) -> io::Result<()> {
+        if let Some(file) = self.data.files.last_mut() {
+            file.diff.push_str(diff);
+        }
+        Ok(())
+    }
+    fn end_file(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+    fn end_contents(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+    fn deleted_files(&mut self, paths: &[String]) -> io::Result<()> {
+        paths.clone_into(&mut self.data.deleted);
+        Ok(())
+    }
+    fn end_document(&mut self) -> io::Result<()> {
+        render_nodes(
+            &self.template.nodes,
+            &self.data,
+            &mut Vec::new(),
+            self.writer,
+        )
+    }
+}
+#[cfg(test)]
+mod tests;
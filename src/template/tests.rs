@@ -0,0 +1,108 @@
+use super::{Template, TemplateRenderer};
+use crate::{
+    i18n::{Locale, Message, tr, tr_in},
+    render::render_project,
+    test_support::{TestDir, must, must_err},
+};
+use std::io;
+fn render_template(source: &str, dir: &TestDir) -> String {
+    let template = must(Template::parse(source), "解析模板失败");
+    let mut output = Vec::new();
+    must(
+        render_project(
+            dir.path(),
+            &mut TemplateRenderer::new(&mut output, template, None, Locale::ZhCn),
+        ),
+        "渲染模板失败",
+    );
+    must(String::from_utf8(output), "模板输出必须是 UTF-8")
+}
+#[test]
+fn variables_and_file_loops_are_rendered() {
+    let dir = must(TestDir::new("template-files"), "创建测试目录失败");
+    must(
+        dir.write_str("a.rs", "fn a() {}\nfn b() {}\n"),
+        "写入 a.rs 失败",
+    );
+    let document = render_template(
+        "Files: {{file_count}}\n{{#each files}}\n# {{path}} ({{language}}, {{lines}} lines, {{tokens}} tokens)\n{{content}}{{/each}}",
+        &dir,
+    );
+    assert_eq!(
+        document,
+        "Files: 1\n# a.rs (rust, 2 lines, 5 tokens)\nfn a() {}\nfn b() {}\n"
+    );
+}
+#[test]
+fn conditionals_choose_branch_by_truthiness() {
+    let dir = must(TestDir::new("template-if"), "创建测试目录失败");
+    must(dir.write_str("src/main.rs", ""), "写入 main.rs 失败");
+    let document = render_template(
+        "{{#each tree}}\n{{indent}}{{name}}{{#if is_dir}}/{{else}} [{{kind}}]{{/if}}\n{{/each}}\n",
+        &dir,
+    );
+    assert_eq!(document, "    src/\n        main.rs [file]\n");
+}
+#[test]
+fn unknown_variables_are_rejected_when_parsing() {
+    for (source, name) in [
+        ("{{missing}}", "missing"),
+        ("{{#each tree}}{{path}}{{/each}}", "path"),
+        ("{{#each files}}{{#if binay}}x{{/if}}{{/each}}", "binay"),
+        ("{{content}}", "content"),
+    ] {
+        let err = must_err(Template::parse(source), "未知变量必须报错");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(
+            err.to_string()
+                .contains(&tr(Message::TemplateUnknownVariable, &[&name]))
+        );
+    }
+    must(
+        Template::parse("{{#each files}}{{#each deleted}}{{path}} {{language}}{{/each}}{{/each}}"),
+        "外层作用域的变量应当可用",
+    );
+}
+#[test]
+fn binary_flag_comes_from_content_detection() {
+    let dir = must(TestDir::new("template-binary"), "创建测试目录失败");
+    must(
+        dir.write_bytes("image.bin", &[0, 1, 2, 3]),
+        "写入二进制文件失败",
+    );
+    must(
+        dir.write_str(
+            "marker.txt",
+            &tr_in(Locale::ZhCn, Message::BinaryMarker, &[]),
+        ),
+        "写入标记文本失败",
+    );
+    let document = render_template("{{#each files}}{{path}}={{binary}}\n{{/each}}", &dir);
+    assert_eq!(document, "image.bin=true\nmarker.txt=false\n");
+}
+#[test]
+fn unbalanced_blocks_are_rejected() {
+    for source in [
+        "{{#each files}}",
+        "{{#if root}}x",
+        "{{/each}}",
+        "{{#each users}}{{/each}}",
+        "{{open",
+    ] {
+        let err = must_err(Template::parse(source), "不完整模板必须报错");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
+#[test]
+fn fence_and_heading_variables_are_collision_safe() {
+    let dir = must(TestDir::new("template-fence"), "创建测试目录失败");
+    must(
+        dir.write_str("a_b.md", "```\ncode\n```\n"),
+        "写入 a_b.md 失败",
+    );
+    let document = render_template(
+        "{{#each files}}\n# {{heading}}\n{{fence}}{{language}}\n{{content}}{{fence}}\n{{/each}}\n",
+        &dir,
+    );
+    assert_eq!(document, "# a\\_b.md\n````markdown\n```\ncode\n```\n````\n");
+}
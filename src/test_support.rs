@@ -5,6 +5,7 @@ use core::{
 use std::{
     env, fs, io,
     path::{Path, PathBuf},
+    process::Command,
 };
 static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
 pub(crate) struct TestDir {
@@ -39,6 +40,33 @@ impl TestDir {
         fs::create_dir_all(&path)?;
         Ok(path)
     }
+    pub(crate) fn git(&self, args: &[&str]) -> io::Result<String> {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(&self.path)
+            .args([
+                "-c",
+                "user.name=proj2md",
+                "-c",
+                "user.email=proj2md@example.com",
+                "-c",
+                "commit.gpgsign=false",
+                "-c",
+                "init.defaultBranch=main",
+            ])
+            .args(args)
+            .output()?;
+        if !output.status.success() {
+            return Err(io::Error::other(format!(
+                "git {} 失败: {}",
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .trim_end()
+            .to_owned())
+    }
 }
 impl Drop for TestDir {
     fn drop(&mut self) {
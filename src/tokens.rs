@@ -0,0 +1,22 @@
+const ASCII_CHARS_PER_TOKEN: usize = 4;
+pub(crate) fn estimate_tokens(text: &str) -> usize {
+    let mut ascii_chars = 0_usize;
+    let mut other_chars = 0_usize;
+    for character in text.chars() {
+        if character.is_ascii() {
+            ascii_chars = ascii_chars.saturating_add(1);
+        } else {
+            other_chars = other_chars.saturating_add(1);
+        }
+    }
+    ascii_chars
+        .div_ceil(ASCII_CHARS_PER_TOKEN)
+        .saturating_add(other_chars)
+}
+pub(crate) fn estimate_tokens_from_size(bytes: u64) -> usize {
+    usize::try_from(bytes)
+        .unwrap_or(usize::MAX)
+        .div_ceil(ASCII_CHARS_PER_TOKEN)
+}
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,14 @@
+use super::estimate_tokens;
+#[test]
+fn empty_text_has_no_tokens() {
+    assert_eq!(estimate_tokens(""), 0);
+}
+#[test]
+fn ascii_text_counts_four_characters_per_token() {
+    assert_eq!(estimate_tokens("fn main() {}"), 3);
+    assert_eq!(estimate_tokens("abcde"), 2);
+}
+#[test]
+fn non_ascii_characters_count_individually() {
+    assert_eq!(estimate_tokens("你好ab"), 3);
+}
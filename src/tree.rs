@@ -0,0 +1,431 @@
+use crate::{
+    gitmodules::Submodule,
+    i18n::{Locale, Message, tr, tr_in},
+    inventory::{FileEntry, Omission, TreeEntry, TreeEntryKind, mark_last_siblings},
+    stats::ContentStats,
+};
+use alloc::collections::BTreeMap;
+use std::io;
+const SIZE_UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+const COLLAPSE_PREVIEW_ENTRIES: usize = 5;
+const COLLAPSE_EXTENSION_LIMIT: usize = 5;
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub(crate) enum TreeStyle {
+    #[default]
+    Indent,
+    Unicode,
+    Ascii,
+}
+impl TreeStyle {
+    pub(crate) fn parse(name: &str) -> Option<Self> {
+        match name {
+            "indent" => Some(Self::Indent),
+            "unicode" => Some(Self::Unicode),
+            "ascii" => Some(Self::Ascii),
+            _ => None,
+        }
+    }
+    pub(crate) const fn name(self) -> &'static str {
+        match self {
+            Self::Indent => "indent",
+            Self::Unicode => "unicode",
+            Self::Ascii => "ascii",
+        }
+    }
+    const fn connectors(self) -> Option<[&'static str; 4]> {
+        match self {
+            Self::Indent => None,
+            Self::Unicode => Some(["├── ", "└── ", "│   ", "    "]),
+            Self::Ascii => Some(["|-- ", "`-- ", "|   ", "    "]),
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum TreeAnnotation {
+    Size,
+    Lines,
+    Tokens,
+    Omitted,
+}
+impl TreeAnnotation {
+    const fn bit(self) -> u8 {
+        match self {
+            Self::Size => 0b0001,
+            Self::Lines => 0b0010,
+            Self::Tokens => 0b0100,
+            Self::Omitted => 0b1000,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub(crate) struct TreeAnnotations {
+    mask: u8,
+}
+impl TreeAnnotations {
+    pub(crate) fn parse(list: &str) -> Option<Self> {
+        let mut annotations = Self::default();
+        for name in list.split(',').map(str::trim) {
+            match name {
+                "size" => annotations.insert(TreeAnnotation::Size),
+                "lines" => annotations.insert(TreeAnnotation::Lines),
+                "tokens" => annotations.insert(TreeAnnotation::Tokens),
+                "omitted" => annotations.insert(TreeAnnotation::Omitted),
+                "all" => annotations.mask = u8::MAX,
+                "none" => annotations.mask = 0,
+                _ => return None,
+            }
+        }
+        Some(annotations)
+    }
+    pub(crate) const fn insert(&mut self, annotation: TreeAnnotation) {
+        self.mask |= annotation.bit();
+    }
+    pub(crate) fn names(self) -> String {
+        let names: Vec<&str> = [
+            (TreeAnnotation::Size, "size"),
+            (TreeAnnotation::Lines, "lines"),
+            (TreeAnnotation::Tokens, "tokens"),
+            (TreeAnnotation::Omitted, "omitted"),
+        ]
+        .into_iter()
+        .filter(|&(annotation, _)| self.contains(annotation))
+        .map(|(_, name)| name)
+        .collect();
+        if names.is_empty() {
+            "none".to_owned()
+        } else {
+            names.join(",")
+        }
+    }
+    pub(crate) const fn is_empty(self) -> bool {
+        self.mask == 0
+    }
+    pub(crate) const fn contains(self, annotation: TreeAnnotation) -> bool {
+        self.mask & annotation.bit() != 0
+    }
+}
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub(crate) struct TreeOptions {
+    pub(crate) style: TreeStyle,
+    pub(crate) annotations: TreeAnnotations,
+    pub(crate) directory_totals: bool,
+    pub(crate) collapse_threshold: Option<usize>,
+}
+impl TreeOptions {
+    pub(crate) fn entry_annotations(self, entry: &TreeEntry) -> EntryAnnotations {
+        let show_stats = entry.omission != Some(Omission::Excluded)
+            && match entry.kind {
+                TreeEntryKind::File => true,
+                TreeEntryKind::Directory => self.directory_totals,
+                TreeEntryKind::Ignored | TreeEntryKind::Hidden | TreeEntryKind::Collapsed => false,
+            };
+        let shown = |annotation| show_stats && self.annotations.contains(annotation);
+        EntryAnnotations {
+            bytes: shown(TreeAnnotation::Size).then_some(entry.bytes),
+            lines: shown(TreeAnnotation::Lines).then_some(entry.lines),
+            tokens: shown(TreeAnnotation::Tokens).then_some(entry.tokens),
+            omission: entry.omission.filter(|&omission| {
+                omission == Omission::Excluded || self.annotations.contains(TreeAnnotation::Omitted)
+            }),
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub(crate) struct EntryAnnotations {
+    pub(crate) bytes: Option<u64>,
+    pub(crate) lines: Option<usize>,
+    pub(crate) tokens: Option<usize>,
+    pub(crate) omission: Option<Omission>,
+}
+pub(crate) struct TreeFormatter {
+    options: TreeOptions,
+    locale: Locale,
+    ancestors_last: Vec<bool>,
+}
+impl TreeFormatter {
+    pub(crate) const fn new(options: TreeOptions, locale: Locale) -> Self {
+        Self {
+            options,
+            locale,
+            ancestors_last: Vec::new(),
+        }
+    }
+    pub(crate) fn line(&mut self, entry: &TreeEntry) -> String {
+        let mut line = self.prefix(entry);
+        line.push_str(&entry.name);
+        match entry.kind {
+            TreeEntryKind::Directory => {
+                line.push('/');
+                if let Some(submodule) = entry.submodule.as_ref() {
+                    line.push(' ');
+                    line.push_str(&submodule_marker(submodule, self.locale));
+                }
+            }
+            TreeEntryKind::Ignored => {
+                line.push_str("/ ");
+                line.push_str(&tr_in(self.locale, Message::IgnoredMarker, &[]));
+            }
+            TreeEntryKind::Hidden => {
+                line.push_str("/ ");
+                line.push_str(&tr_in(self.locale, Message::HiddenMarker, &[]));
+            }
+            TreeEntryKind::File | TreeEntryKind::Collapsed => {}
+        }
+        if entry.untracked {
+            line.push(' ');
+            line.push_str(&tr_in(self.locale, Message::UntrackedMarker, &[]));
+        }
+        line.push_str(&self.annotation(entry));
+        line
+    }
+    pub(crate) fn prefix(&mut self, entry: &TreeEntry) -> String {
+        let Some([branch, last_branch, pipe, blank]) = self.options.style.connectors() else {
+            return "    ".repeat(entry.depth);
+        };
+        self.ancestors_last.truncate(entry.depth.saturating_sub(1));
+        let mut prefix: String = self
+            .ancestors_last
+            .iter()
+            .map(|&ancestor_last| if ancestor_last { blank } else { pipe })
+            .collect();
+        prefix.push_str(if entry.is_last { last_branch } else { branch });
+        self.ancestors_last.push(entry.is_last);
+        prefix
+    }
+    pub(crate) fn annotation(&self, entry: &TreeEntry) -> String {
+        let fields = self.options.entry_annotations(entry);
+        let mut parts = Vec::new();
+        if let Some(bytes) = fields.bytes {
+            parts.push(format_size(bytes));
+        }
+        if let Some(lines) = fields.lines {
+            parts.push(tr_in(self.locale, Message::LinesCount, &[&lines]));
+        }
+        if let Some(tokens) = fields.tokens {
+            parts.push(tr_in(self.locale, Message::TokensCount, &[&tokens]));
+        }
+        let mut annotation = String::new();
+        if !parts.is_empty() {
+            annotation.push_str(" (");
+            annotation.push_str(&parts.join(", "));
+            annotation.push(')');
+        }
+        if let Some(omission) = fields.omission {
+            annotation.push(' ');
+            annotation.push_str(&tr_in(
+                self.locale,
+                Message::OmittedMarker,
+                &[&omission_label(omission, self.locale)],
+            ));
+        }
+        annotation
+    }
+}
+fn submodule_marker(submodule: &Submodule, locale: Locale) -> String {
+    let commit = submodule
+        .commit
+        .as_deref()
+        .map_or("?", |commit| commit.get(..7_usize).unwrap_or(commit));
+    tr_in(locale, Message::SubmoduleMarker, &[&submodule.url, &commit])
+}
+pub(crate) fn omission_label(omission: Omission, locale: Locale) -> String {
+    let message = match omission {
+        Omission::Binary => Message::OmissionBinary,
+        Omission::TooLarge => Message::OmissionTooLarge,
+        Omission::Undecodable => Message::OmissionUndecodable,
+        Omission::Excluded => Message::OmissionExcluded,
+    };
+    tr_in(locale, message, &[])
+}
+pub(crate) fn format_size(bytes: u64) -> String {
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut tenths = bytes.saturating_mul(10).checked_div(1024).unwrap_or(0);
+    let mut unit = SIZE_UNITS.first().copied().unwrap_or_default();
+    for &next_unit in SIZE_UNITS.iter().skip(1) {
+        if tenths < 10_240 {
+            break;
+        }
+        tenths = tenths.checked_div(1024).unwrap_or(0);
+        unit = next_unit;
+    }
+    let whole = tenths.checked_div(10).unwrap_or(0);
+    let fraction = tenths.checked_rem(10).unwrap_or(0);
+    format!("{whole}.{fraction} {unit}")
+}
+pub(crate) fn group_digits(number: usize) -> String {
+    let digits = number.to_string();
+    let mut grouped = String::with_capacity(digits.len().saturating_mul(2));
+    for (index, digit) in digits.chars().enumerate() {
+        let remaining = digits.len().saturating_sub(index);
+        if index > 0 && remaining.checked_rem(3) == Some(0) {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+pub(crate) fn collapse_tree(
+    entries: &[TreeEntry],
+    threshold: usize,
+    locale: Locale,
+) -> Vec<TreeEntry> {
+    let mut collapsed = Vec::with_capacity(entries.len());
+    collapse_children(entries, 1, threshold, locale, &mut collapsed);
+    mark_last_siblings(&mut collapsed);
+    collapsed
+}
+fn collapse_children(
+    entries: &[TreeEntry],
+    depth: usize,
+    threshold: usize,
+    locale: Locale,
+    output: &mut Vec<TreeEntry>,
+) {
+    let groups: Vec<&[TreeEntry]> = entries.chunk_by(|_, next| next.depth > depth).collect();
+    let keep = if groups.len() > threshold {
+        COLLAPSE_PREVIEW_ENTRIES.min(threshold)
+    } else {
+        groups.len()
+    };
+    let mut hidden = HiddenEntries::default();
+    for (index, group) in groups.into_iter().enumerate() {
+        let Some((head, subtree)) = group.split_first() else {
+            continue;
+        };
+        if index < keep {
+            output.push(head.clone());
+            collapse_children(subtree, depth.saturating_add(1), threshold, locale, output);
+        } else {
+            hidden.add(group, locale);
+        }
+    }
+    if let Some(summary) = hidden.summary(locale) {
+        output.push(TreeEntry {
+            depth,
+            name: summary,
+            kind: TreeEntryKind::Collapsed,
+            ..TreeEntry::default()
+        });
+    }
+}
+#[derive(Default)]
+struct HiddenEntries {
+    directories: usize,
+    files: usize,
+    extensions: BTreeMap<String, usize>,
+}
+impl HiddenEntries {
+    fn add(&mut self, group: &[TreeEntry], locale: Locale) {
+        for entry in group {
+            match entry.kind {
+                TreeEntryKind::Directory | TreeEntryKind::Ignored | TreeEntryKind::Hidden => {
+                    self.directories = self.directories.saturating_add(1);
+                }
+                TreeEntryKind::File => {
+                    self.files = self.files.saturating_add(1);
+                    let extension = entry
+                        .name
+                        .rsplit_once('.')
+                        .filter(|&(stem, _)| !stem.is_empty())
+                        .map_or_else(
+                            || tr_in(locale, Message::NoExtension, &[]),
+                            |(_, ext)| ext.to_lowercase(),
+                        );
+                    let count = self.extensions.entry(extension).or_default();
+                    *count = count.saturating_add(1);
+                }
+                TreeEntryKind::Collapsed => {}
+            }
+        }
+    }
+    fn summary(&self, locale: Locale) -> Option<String> {
+        let mut counts = Vec::new();
+        if self.directories > 0 {
+            counts.push(tr_in(
+                locale,
+                Message::HiddenDirectories,
+                &[&group_digits(self.directories)],
+            ));
+        }
+        if self.files > 0 {
+            counts.push(tr_in(
+                locale,
+                Message::HiddenFiles,
+                &[&group_digits(self.files)],
+            ));
+        }
+        if counts.is_empty() {
+            return None;
+        }
+        let separator = tr_in(locale, Message::ListSeparator, &[]);
+        let mut summary = tr_in(locale, Message::HiddenSummary, &[&counts.join(&separator)]);
+        let mut extensions: Vec<(&String, &usize)> = self.extensions.iter().collect();
+        extensions.sort_by(|left, right| right.1.cmp(left.1).then_with(|| left.0.cmp(right.0)));
+        let mut breakdown: Vec<String> = extensions
+            .iter()
+            .take(COLLAPSE_EXTENSION_LIMIT)
+            .map(|&(extension, &count)| format!("{extension}: {}", group_digits(count)))
+            .collect();
+        if extensions.len() > COLLAPSE_EXTENSION_LIMIT {
+            breakdown.push("…".to_owned());
+        }
+        if !breakdown.is_empty() {
+            summary.push_str(" (");
+            summary.push_str(&breakdown.join(", "));
+            summary.push(')');
+        }
+        Some(summary)
+    }
+}
+pub(crate) fn annotate_tree(
+    entries: &[TreeEntry],
+    files: &[FileEntry],
+    contents: &[ContentStats],
+) -> io::Result<Vec<TreeEntry>> {
+    let stats: BTreeMap<&str, &ContentStats> = files
+        .iter()
+        .map(|file| file.relative_path.as_str())
+        .zip(contents)
+        .collect();
+    let mut annotated = entries.to_vec();
+    let mut open_directories: Vec<usize> = Vec::new();
+    for index in 0..annotated.len() {
+        let Some(entry) = annotated.get_mut(index) else {
+            continue;
+        };
+        let depth = entry.depth;
+        if entry.kind == TreeEntryKind::File
+            && let Some(&content) = stats.get(entry.relative_path.as_str())
+        {
+            entry.lines = content.lines;
+            entry.tokens = content.tokens;
+            entry.omission = entry.omission.or(content.omission);
+        }
+        let totals = (entry.bytes, entry.lines, entry.tokens);
+        let is_directory = entry.kind == TreeEntryKind::Directory;
+        while open_directories
+            .last()
+            .and_then(|&open| annotated.get(open))
+            .is_some_and(|open| open.depth >= depth)
+        {
+            open_directories.pop();
+        }
+        if is_directory {
+            open_directories.push(index);
+            continue;
+        }
+        for &open in &open_directories {
+            let directory = annotated
+                .get_mut(open)
+                .ok_or_else(|| io::Error::other(tr(Message::TreeIndexOutOfRange, &[&open])))?;
+            directory.bytes = directory.bytes.saturating_add(totals.0);
+            directory.lines = directory.lines.saturating_add(totals.1);
+            directory.tokens = directory.tokens.saturating_add(totals.2);
+        }
+    }
+    Ok(annotated)
+}
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,242 @@
+use super::{
+    TreeAnnotation, TreeAnnotations, TreeFormatter, TreeOptions, TreeStyle, annotate_tree,
+    collapse_tree, format_size, group_digits,
+};
+use crate::{
+    content::load_file_content,
+    i18n::Locale,
+    inventory::{
+        InventoryOptions, Omission, TreeEntry, TreeEntryKind, collect_project_inventory,
+        mark_last_siblings,
+    },
+    stats::ContentStats,
+    test_support::{TestDir, must},
+};
+fn entry(depth: usize, name: &str, kind: TreeEntryKind) -> TreeEntry {
+    TreeEntry {
+        depth,
+        name: name.to_owned(),
+        kind,
+        ..TreeEntry::default()
+    }
+}
+fn render_lines(entries: &[TreeEntry], options: TreeOptions) -> Vec<String> {
+    let mut formatter = TreeFormatter::new(options, Locale::ZhCn);
+    entries.iter().map(|entry| formatter.line(entry)).collect()
+}
+#[test]
+fn box_drawing_styles_connect_siblings() {
+    let mut entries = vec![
+        entry(1, "src", TreeEntryKind::Directory),
+        entry(2, "lib.rs", TreeEntryKind::File),
+        entry(2, "main.rs", TreeEntryKind::File),
+        entry(1, "Cargo.toml", TreeEntryKind::File),
+    ];
+    mark_last_siblings(&mut entries);
+    let unicode = TreeOptions {
+        style: TreeStyle::Unicode,
+        ..TreeOptions::default()
+    };
+    assert_eq!(
+        render_lines(&entries, unicode),
+        [
+            "├── src/",
+            "│   ├── lib.rs",
+            "│   └── main.rs",
+            "└── Cargo.toml"
+        ]
+    );
+    let ascii = TreeOptions {
+        style: TreeStyle::Ascii,
+        ..TreeOptions::default()
+    };
+    assert_eq!(
+        render_lines(&entries, ascii),
+        [
+            "|-- src/",
+            "|   |-- lib.rs",
+            "|   `-- main.rs",
+            "`-- Cargo.toml"
+        ]
+    );
+    assert_eq!(
+        render_lines(&entries, TreeOptions::default()),
+        [
+            "    src/",
+            "        lib.rs",
+            "        main.rs",
+            "    Cargo.toml"
+        ]
+    );
+}
+#[test]
+fn annotation_lists_parse_names() {
+    let annotations = TreeAnnotations::parse("size, tokens").unwrap_or_default();
+    assert!(annotations.contains(TreeAnnotation::Size));
+    assert!(annotations.contains(TreeAnnotation::Tokens));
+    assert!(!annotations.contains(TreeAnnotation::Lines));
+    assert!(!annotations.contains(TreeAnnotation::Omitted));
+    assert_eq!(
+        TreeAnnotations::parse("all").map(|all| all.contains(TreeAnnotation::Omitted)),
+        Some(true)
+    );
+    assert_eq!(TreeAnnotations::parse("colour"), None);
+    assert_eq!(TreeStyle::parse("unicode"), Some(TreeStyle::Unicode));
+}
+#[test]
+fn sizes_use_binary_units() {
+    assert_eq!(format_size(0), "0 B");
+    assert_eq!(format_size(1023), "1023 B");
+    assert_eq!(format_size(1536), "1.5 KiB");
+    assert_eq!(format_size(5 * 1024 * 1024), "5.0 MiB");
+}
+#[test]
+fn annotations_report_stats_and_directory_totals() {
+    let dir = must(TestDir::new("tree-annotations"), "创建测试目录失败");
+    must(
+        dir.write_str("src/main.rs", "fn main() {}\nfn helper() {}\n"),
+        "写入 main.rs 失败",
+    );
+    must(
+        dir.write_bytes("src/logo.bin", &[0, 1, 2, 3]),
+        "写入 logo.bin 失败",
+    );
+    let inventory = must(
+        collect_project_inventory(dir.path(), &InventoryOptions::default()),
+        "收集项目清单失败",
+    );
+    let contents: Vec<_> = inventory
+        .content_files
+        .iter()
+        .map(|file| {
+            ContentStats::measure(&must(
+                load_file_content(file, Locale::ZhCn),
+                "读取文件内容失败",
+            ))
+        })
+        .collect();
+    let annotated = must(
+        annotate_tree(&inventory.tree_entries, &inventory.content_files, &contents),
+        "标注目录树失败",
+    );
+    let options = TreeOptions {
+        annotations: TreeAnnotations::parse("all").unwrap_or_default(),
+        directory_totals: true,
+        ..TreeOptions::default()
+    };
+    let lines = render_lines(&annotated, options);
+    assert!(lines.contains(&"    src/ (32 B, 2 行, 约 7 tokens)".to_owned()));
+    assert!(lines.contains(&"        main.rs (28 B, 2 行, 约 7 tokens)".to_owned()));
+    assert!(
+        lines
+            .contains(&"        logo.bin (4 B, 0 行, 约 0 tokens) [已省略: 二进制文件]".to_owned())
+    );
+    let binary = annotated
+        .iter()
+        .find(|tree_entry| tree_entry.name == "logo.bin");
+    assert_eq!(
+        binary.and_then(|tree_entry| tree_entry.omission),
+        Some(Omission::Binary)
+    );
+}
+#[test]
+fn oversized_files_are_marked_without_reading() {
+    let dir = must(TestDir::new("tree-too-large"), "创建测试目录失败");
+    must(dir.write_str("big.txt", "0123456789"), "写入 big.txt 失败");
+    let options = InventoryOptions {
+        max_file_size: Some(4),
+        ..InventoryOptions::default()
+    };
+    let inventory = must(
+        collect_project_inventory(dir.path(), &options),
+        "收集项目清单失败",
+    );
+    let contents: Vec<_> = inventory
+        .content_files
+        .iter()
+        .map(|file| {
+            ContentStats::measure(&must(
+                load_file_content(file, Locale::ZhCn),
+                "读取文件内容失败",
+            ))
+        })
+        .collect();
+    let annotated = must(
+        annotate_tree(&inventory.tree_entries, &inventory.content_files, &contents),
+        "标注目录树失败",
+    );
+    let tree_options = TreeOptions {
+        annotations: TreeAnnotations::parse("omitted").unwrap_or_default(),
+        ..TreeOptions::default()
+    };
+    assert_eq!(
+        render_lines(&annotated, tree_options),
+        ["    big.txt [已省略: 文件过大]"]
+    );
+}
+#[test]
+fn oversized_directories_collapse_into_summary() {
+    let mut entries = vec![entry(1, "icons", TreeEntryKind::Directory)];
+    for index in 0..8_usize {
+        entries.push(entry(2, &format!("icon{index}.png"), TreeEntryKind::File));
+    }
+    entries.push(entry(2, "logo.svg", TreeEntryKind::File));
+    entries.push(entry(2, "themes", TreeEntryKind::Directory));
+    entries.push(entry(3, "dark.svg", TreeEntryKind::File));
+    entries.push(entry(1, "main.rs", TreeEntryKind::File));
+    let collapsed = collapse_tree(&entries, 6, Locale::ZhCn);
+    let options = TreeOptions {
+        style: TreeStyle::Unicode,
+        ..TreeOptions::default()
+    };
+    assert_eq!(
+        render_lines(&collapsed, options),
+        [
+            "├── icons/",
+            "│   ├── icon0.png",
+            "│   ├── icon1.png",
+            "│   ├── icon2.png",
+            "│   ├── icon3.png",
+            "│   ├── icon4.png",
+            "│   └── … 另有 1 个目录、5 个文件 (png: 3, svg: 2)",
+            "└── main.rs"
+        ]
+    );
+    assert_eq!(
+        collapse_tree(&entries, 20, Locale::ZhCn),
+        entries_with_last(entries)
+    );
+}
+#[test]
+fn ignored_directories_render_as_single_line() {
+    let ignored = entry(1, "node_modules", TreeEntryKind::Ignored);
+    assert_eq!(
+        render_lines(&[ignored], TreeOptions::default()),
+        ["    node_modules/ [已忽略]"]
+    );
+}
+#[test]
+fn excluded_entries_are_marked_without_annotations() {
+    let excluded = TreeEntry {
+        omission: Some(Omission::Excluded),
+        ..entry(1, "README.md", TreeEntryKind::File)
+    };
+    let binary = TreeEntry {
+        omission: Some(Omission::Binary),
+        ..entry(1, "logo.png", TreeEntryKind::File)
+    };
+    assert_eq!(
+        render_lines(&[excluded, binary], TreeOptions::default()),
+        ["    README.md [已省略: 已排除]", "    logo.png"]
+    );
+}
+#[test]
+fn digit_groups_use_commas() {
+    assert_eq!(group_digits(7), "7");
+    assert_eq!(group_digits(1987), "1,987");
+    assert_eq!(group_digits(1_234_567), "1,234,567");
+}
+fn entries_with_last(mut entries: Vec<TreeEntry>) -> Vec<TreeEntry> {
+    mark_last_siblings(&mut entries);
+    entries
+}
@@ -0,0 +1,226 @@
+use crate::{
+    front_matter::{BundleMetadata, GENERATOR},
+    inventory::{FileEntry, TreeEntry},
+    patch::Commit,
+    render::Renderer,
+    stats::ProjectSummary,
+    tree::TreeOptions,
+};
+use std::io;
+pub(crate) struct XmlRenderer<'writer, W> {
+    writer: &'writer mut W,
+    tree: TreeOptions,
+}
+impl<'writer, W> XmlRenderer<'writer, W>
+where
+    W: io::Write,
+{
+    pub(crate) const fn new(writer: &'writer mut W, tree: TreeOptions) -> Self {
+        Self { writer, tree }
+    }
+}
+impl<W> Renderer for XmlRenderer<'_, W>
+where
+    W: io::Write,
+{
+    fn begin_document(&mut self, root_name: &str) -> io::Result<()> {
+        self.writer
+            .write_all(b"<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n")?;
+        writeln!(
+            self.writer,
+            "<project generator=\"{GENERATOR}\" root=\"{}\">",
+            escape_xml(root_name)
+        )
+    }
+    fn front_matter(&mut self, metadata: &BundleMetadata) -> io::Result<()> {
+        writeln!(
+            self.writer,
+            "  <metadata generator=\"{GENERATOR}\" version=\"{}\" generated-at=\"{}\" files=\"{}\" total-bytes=\"{}\" content-hash=\"{}\">",
+            escape_xml(&metadata.version),
+            metadata.generated_at,
+            metadata.file_count,
+            metadata.total_bytes,
+            metadata.content_hash
+        )?;
+        for option in &metadata.options {
+            for value in &option.values {
+                writeln!(
+                    self.writer,
+                    "    <option name=\"{}\" value=\"{}\"/>",
+                    escape_xml(&option.name),
+                    escape_xml(value)
+                )?;
+            }
+        }
+        if let Some(git) = metadata.git.as_ref() {
+            writeln!(
+                self.writer,
+                "    <git branch=\"{}\" commit=\"{}\" dirty=\"{}\"/>",
+                escape_xml(&git.branch),
+                escape_xml(&git.commit),
+                git.dirty
+            )?;
+        }
+        self.writer.write_all(b"  </metadata>\n")
+    }
+    fn project_summary(&mut self, summary: &ProjectSummary) -> io::Result<()> {
+        writeln!(
+            self.writer,
+            "  <summary files=\"{}\" bytes=\"{}\" lines=\"{}\" tokens=\"{}\" binary=\"{}\" too-large=\"{}\" undecodable=\"{}\">",
+            summary.file_count,
+            summary.total_bytes,
+            summary.total_lines,
+            summary.total_tokens,
+            summary.binary_files,
+            summary.too_large_files,
+            summary.undecodable_files
+        )?;
+        for language in &summary.languages {
+            writeln!(
+                self.writer,
+                "    <language name=\"{}\" files=\"{}\" lines=\"{}\"/>",
+                escape_xml(&language.language),
+                language.files,
+                language.lines
+            )?;
+        }
+        for file in &summary.largest_files {
+            writeln!(
+                self.writer,
+                "    <largest path=\"{}\" bytes=\"{}\"/>",
+                escape_xml(&file.path),
+                file.bytes
+            )?;
+        }
+        for encoding in &summary.encodings {
+            writeln!(
+                self.writer,
+                "    <encoding name=\"{}\" files=\"{}\"/>",
+                escape_xml(&encoding.encoding),
+                encoding.files
+            )?;
+        }
+        self.writer.write_all(b"  </summary>\n")
+    }
+    fn commit_log(&mut self, reference: &str, commits: &[Commit]) -> io::Result<()> {
+        writeln!(
+            self.writer,
+            "  <commits base=\"{}\">",
+            escape_xml(reference)
+        )?;
+        for commit in commits {
+            writeln!(
+                self.writer,
+                "    <commit hash=\"{}\" author=\"{}\" date=\"{}\">{}</commit>",
+                escape_xml(&commit.hash),
+                escape_xml(&commit.author),
+                escape_xml(&commit.date),
+                escape_xml(&commit.subject)
+            )?;
+        }
+        self.writer.write_all(b"  </commits>\n")
+    }
+    fn begin_tree(&mut self) -> io::Result<()> {
+        self.writer.write_all(b"  <tree>\n")
+    }
+    fn tree_entry(&mut self, entry: &TreeEntry) -> io::Result<()> {
+        let element = entry.kind.name();
+        let untracked = if entry.untracked {
+            " untracked=\"true\""
+        } else {
+            ""
+        };
+        let submodule = entry
+            .submodule
+            .as_ref()
+            .map_or_else(String::new, |submodule| {
+                format!(
+                    " submodule-url=\"{}\" submodule-commit=\"{}\"",
+                    escape_xml(&submodule.url),
+                    submodule.commit.as_deref().unwrap_or_default()
+                )
+            });
+        let annotations = self.tree.entry_annotations(entry);
+        let stats: String = [
+            annotations.bytes.map(|bytes| format!(" bytes=\"{bytes}\"")),
+            annotations.lines.map(|lines| format!(" lines=\"{lines}\"")),
+            annotations
+                .tokens
+                .map(|tokens| format!(" tokens=\"{tokens}\"")),
+            annotations
+                .omission
+                .map(|omission| format!(" omission=\"{}\"", omission.name())),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        writeln!(
+            self.writer,
+            "    <{element} depth=\"{}\" name=\"{}\"{untracked}{stats}{submodule}/>",
+            entry.depth,
+            escape_xml(&entry.name)
+        )
+    }
+    fn end_tree(&mut self) -> io::Result<()> {
+        self.writer.write_all(b"  </tree>\n")
+    }
+    fn begin_contents(&mut self) -> io::Result<()> {
+        self.writer.write_all(b"  <files>\n")
+    }
+    fn begin_file(&mut self, file: &FileEntry) -> io::Result<()> {
+        let untracked = if file.untracked {
+            " untracked=\"true\""
+        } else {
+            ""
+        };
+        let permalink = file.permalink.as_deref().map_or_else(String::new, |url| {
+            format!(" permalink=\"{}\"", escape_xml(url))
+        });
+        write!(
+            self.writer,
+            "    <file path=\"{}\" language=\"{}\"{untracked}{permalink}>",
+            escape_xml(&file.relative_path),
+            escape_xml(&file.code_block_language)
+        )
+    }
+    fn content_chunk(&mut self, chunk: &str) -> io::Result<()> {
+        self.writer.write_all(escape_xml(chunk).as_bytes())
+    }
+    fn file_diff(&mut self, diff: &str) -> io::Result<()> {
+        write!(self.writer, "<diff>{}</diff>", escape_xml(diff))
+    }
+    fn end_file(&mut self) -> io::Result<()> {
+        self.writer.write_all(b"</file>\n")
+    }
+    fn end_contents(&mut self) -> io::Result<()> {
+        self.writer.write_all(b"  </files>\n")
+    }
+    fn deleted_files(&mut self, paths: &[String]) -> io::Result<()> {
+        self.writer.write_all(b"  <deleted>\n")?;
+        for path in paths {
+            writeln!(self.writer, "    <file path=\"{}\"/>", escape_xml(path))?;
+        }
+        self.writer.write_all(b"  </deleted>\n")
+    }
+    fn end_document(&mut self) -> io::Result<()> {
+        self.writer.write_all(b"</project>\n")
+    }
+}
+pub(crate) fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(character),
+            '\0'..='\u{1f}' | '\u{fffe}' | '\u{ffff}' => escaped.push(char::REPLACEMENT_CHARACTER),
+            other => escaped.push(other),
+        }
+    }
+    escaped
+}
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,81 @@
+use super::{XmlRenderer, escape_xml};
+use crate::{
+    inventory::{InventoryOptions, collect_project_inventory},
+    render::{RenderOptions, render_inventory, render_project},
+    test_support::{TestDir, must},
+    tree::{TreeAnnotations, TreeOptions},
+};
+#[test]
+fn escape_xml_escapes_markup_characters() {
+    assert_eq!(
+        escape_xml("<a href=\"x\">&'"),
+        "&lt;a href=&quot;x&quot;&gt;&amp;&apos;"
+    );
+}
+#[test]
+fn escape_xml_replaces_characters_xml_forbids() {
+    assert_eq!(
+        escape_xml("a\u{0}b\u{8}c\u{b}\u{c}d\u{1b}[0m\t\r\n\u{ffff}"),
+        "a\u{fffd}b\u{fffd}c\u{fffd}\u{fffd}d\u{fffd}[0m\t\r\n\u{fffd}"
+    );
+}
+#[test]
+fn project_xml_wraps_tree_and_files() {
+    let dir = must(TestDir::new("xml-project"), "创建测试目录失败");
+    must(
+        dir.write_str("src/main.rs", "fn main() { 1 < 2; }\n"),
+        "写入 main.rs 失败",
+    );
+    let mut output = Vec::new();
+    must(
+        render_project(
+            dir.path(),
+            &mut XmlRenderer::new(&mut output, TreeOptions::default()),
+        ),
+        "写入 XML 文档失败",
+    );
+    let document = must(String::from_utf8(output), "XML 文档必须是 UTF-8");
+    assert!(document.starts_with(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<project generator=\"proj2md\" root="
+    ));
+    assert!(document.contains("    <directory depth=\"1\" name=\"src\"/>\n"));
+    assert!(document.contains("language=\"rust\">fn main() { 1 &lt; 2; }\n</file>"));
+    assert!(document.ends_with("  </files>\n</project>\n"));
+}
+#[test]
+fn tree_entries_carry_annotations_and_exclusions() {
+    let dir = must(TestDir::new("xml-tree-annotations"), "创建测试目录失败");
+    must(dir.write_str("a.md", "one\ntwo"), "写入 a.md 失败");
+    must(dir.write_str("b.md", "skipped"), "写入 b.md 失败");
+    let inventory_options = InventoryOptions {
+        exclusions: vec!["b.md".to_owned()],
+        ..InventoryOptions::default()
+    };
+    let inventory = must(
+        collect_project_inventory(dir.path(), &inventory_options),
+        "收集项目清单失败",
+    );
+    let tree = TreeOptions {
+        annotations: must(
+            TreeAnnotations::parse("size,lines").ok_or("注解列表无效"),
+            "解析注解失败",
+        ),
+        ..TreeOptions::default()
+    };
+    let options = RenderOptions {
+        tree,
+        ..RenderOptions::default()
+    };
+    let mut output = Vec::new();
+    must(
+        render_inventory(
+            &inventory,
+            &options,
+            &mut XmlRenderer::new(&mut output, tree),
+        ),
+        "写入文档失败",
+    );
+    let document = must(String::from_utf8(output), "文档必须是 UTF-8");
+    assert!(document.contains("<file depth=\"1\" name=\"a.md\" bytes=\"7\" lines=\"2\"/>"));
+    assert!(document.contains("<file depth=\"1\" name=\"b.md\" omission=\"excluded\"/>"));
+}